@@ -0,0 +1,369 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use rustfft::{num_complex::Complex, FftPlanner};
+
+use crate::{
+    analysis_config_file::{FourierSpectrumConfig, Smoothing, SpectrumWindow},
+    converter::sibling_path,
+    error::{DataConversionErr, DataProcessingErr},
+    processing::cosine_taper,
+    record::{Component, Record},
+};
+
+use super::acceleration_in_gal;
+
+/// Fourier amplitudes(gal·s) of one frequency and the H/V ratio.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FourierPoint {
+    pub frequency: f64,
+    pub ns: f64,
+    pub ew: f64,
+    pub ud: f64,
+    /// Geometric mean of NS and EW divided by UD, or `None` if the UD amplitude is zero, e.g. of a flat UD channel.
+    pub hv: Option<f64>,
+}
+
+/// Computes the Fourier amplitude spectra of an acceleration record in gal and the H/V ratio at each FFT frequency
+/// between `min_hz` and `max_hz`(or the Nyquist frequency), or fails if the record is velocity or displacement.
+///
+/// Each component has its mean removed and is windowed, then padded with zeros to a power of two. The amplitudes are
+/// `|X(f)|·Δt` and are smoothed before the H/V ratio is taken.
+pub fn fourier_spectra(
+    record: &Record,
+    config: &FourierSpectrumConfig,
+) -> Result<Vec<FourierPoint>, DataProcessingErr> {
+    let record = acceleration_in_gal(record, "Fourier spectrum")?;
+    if record.len() < 2 {
+        return Ok(Vec::new());
+    }
+
+    let delta = record.delta();
+    let n = record.len().next_power_of_two();
+    let df = 1.0 / (n as f64 * delta);
+    let frequencies: Vec<f64> = (0..=n / 2).map(|k| k as f64 * df).collect();
+
+    let [ns, ew, ud] = [Component::Ns, Component::Ew, Component::Ud].map(|component| {
+        let amplitudes =
+            amplitude_spectrum(record.components().get(component), delta, n, config.window);
+        smooth(&amplitudes, &frequencies, config.smoothing)
+    });
+
+    let max_hz = config.max_hz.min(frequencies[n / 2]);
+    Ok(frequencies
+        .iter()
+        .enumerate()
+        .filter(|(_, &f)| f >= config.min_hz && f <= max_hz)
+        .map(|(k, &frequency)| FourierPoint {
+            frequency,
+            ns: ns[k],
+            ew: ew[k],
+            ud: ud[k],
+            hv: (ud[k] > 0.0).then(|| (ns[k] * ew[k]).sqrt() / ud[k]),
+        })
+        .collect())
+}
+
+// 0HzからナイキストまでのFFTの振幅
+fn amplitude_spectrum(values: &[f64], delta: f64, n: usize, window: SpectrumWindow) -> Vec<f64> {
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    let mut windowed: Vec<f64> = values.iter().map(|v| v - mean).collect();
+    apply_window(&mut windowed, window);
+
+    let mut buffer: Vec<Complex<f64>> = windowed
+        .iter()
+        .map(|&v| Complex::new(v, 0.0))
+        .chain(std::iter::repeat(Complex::new(0.0, 0.0)))
+        .take(n)
+        .collect();
+    FftPlanner::new().plan_fft_forward(n).process(&mut buffer);
+
+    buffer[..=n / 2].iter().map(|v| v.norm() * delta).collect()
+}
+
+fn apply_window(values: &mut [f64], window: SpectrumWindow) {
+    let n = values.len();
+    match window {
+        SpectrumWindow::Rectangular => {}
+        SpectrumWindow::Hann => {
+            for (i, v) in values.iter_mut().enumerate() {
+                *v *= 0.5 * (1.0 - (2.0 * std::f64::consts::PI * i as f64 / (n - 1) as f64).cos());
+            }
+        }
+        SpectrumWindow::CosineTaper(fraction) => cosine_taper(values, fraction),
+    }
+}
+
+/// Smooths the amplitude spectrum at each frequency with the weighted mean over the main lobe of the window.
+///
+/// `frequencies` are the evenly spaced frequencies of the amplitudes from 0 Hz.
+fn smooth(amplitudes: &[f64], frequencies: &[f64], smoothing: Smoothing) -> Vec<f64> {
+    // 中心周波数ごとの重みと、主ローブの範囲(Hz)
+    type Weight = Box<dyn Fn(f64, f64) -> f64>;
+    type Lobe = Box<dyn Fn(f64) -> (f64, f64)>;
+    let (weight, lobe): (Weight, Lobe) = match smoothing {
+        Smoothing::None => return amplitudes.to_vec(),
+        Smoothing::Parzen(bandwidth) => {
+            let half_width = 2.0 / parzen_u(bandwidth);
+            (
+                Box::new(move |f, center| parzen_weight(f - center, bandwidth)),
+                Box::new(move |center| (center - half_width, center + half_width)),
+            )
+        }
+        Smoothing::KonnoOhmachi(b) => {
+            let ratio = 10_f64.powf(std::f64::consts::PI / b);
+            (
+                Box::new(move |f, center| konno_ohmachi_weight(f, center, b)),
+                Box::new(move |center| (center / ratio, center * ratio)),
+            )
+        }
+    };
+    let df = frequencies.get(1).copied().unwrap_or(1.0);
+    let last = amplitudes.len().saturating_sub(1);
+
+    frequencies
+        .iter()
+        .enumerate()
+        .map(|(k, &center)| {
+            if center == 0.0 {
+                return amplitudes[k];
+            }
+            let (low, high) = lobe(center);
+            let first = (low / df).ceil().max(0.0) as usize;
+            let end = ((high / df).floor() as usize).min(last);
+
+            let (sum, weight_sum) = (first..=end)
+                .map(|i| (weight(frequencies[i], center), amplitudes[i]))
+                .fold((0.0, 0.0), |(sum, weight_sum), (w, a)| {
+                    (sum + w * a, weight_sum + w)
+                });
+            if weight_sum > 0.0 {
+                sum / weight_sum
+            } else {
+                amplitudes[k]
+            }
+        })
+        .collect()
+}
+
+fn parzen_u(bandwidth: f64) -> f64 {
+    280.0 / (151.0 * bandwidth)
+}
+
+// 帯域幅b(Hz)のParzenウィンドウ。u = 280 / 151b とし、主ローブ |Δf| < 2/u の外は0とする
+fn parzen_weight(df: f64, bandwidth: f64) -> f64 {
+    let u = parzen_u(bandwidth);
+    if df.abs() >= 2.0 / u {
+        return 0.0;
+    }
+
+    let x = std::f64::consts::PI * u * df / 2.0;
+    if x == 0.0 {
+        1.0
+    } else {
+        (x.sin() / x).powi(4)
+    }
+}
+
+// Konno–Ohmachiウィンドウ。主ローブ |b log10(f/fc)| < π の外は0とする
+fn konno_ohmachi_weight(f: f64, center: f64, b: f64) -> f64 {
+    if f <= 0.0 {
+        return 0.0;
+    }
+
+    let x = b * (f / center).log10();
+    if x.abs() >= std::f64::consts::PI {
+        0.0
+    } else if x == 0.0 {
+        1.0
+    } else {
+        (x.sin() / x).powi(4)
+    }
+}
+
+/// Formats the spectra as CSV, with one row per frequency. The H/V ratio is left blank where it is `None`.
+///
+/// ```text
+/// frequency_hz,ns_gal_s,ew_gal_s,ud_gal_s,hv
+/// 0.1000,12.345678,10.987654,5.432100,2.124567
+/// ```
+pub fn format_fourier_spectra_csv(points: &[FourierPoint]) -> String {
+    let mut csv = "frequency_hz,ns_gal_s,ew_gal_s,ud_gal_s,hv\n".to_string();
+
+    for point in points {
+        let hv = point.hv.map(|hv| format!("{hv:.6}")).unwrap_or_default();
+        csv.push_str(&format!(
+            "{:.4},{:.6},{:.6},{:.6},{}\n",
+            point.frequency, point.ns, point.ew, point.ud, hv
+        ));
+    }
+
+    csv
+}
+
+/// Writes the spectra next to the converted file, e.g. `*-knet.fourier.csv` for `*-knet.csv`, and returns its path.
+///
+/// The path is added to `written`, and is an error if already written in the run.
+pub fn write_fourier_spectra(
+    points: &[FourierPoint],
+    converted_path: &Path,
+    written: &mut HashSet<PathBuf>,
+) -> Result<PathBuf, DataConversionErr> {
+    let output_path = sibling_path(converted_path, "fourier", "csv");
+    if !written.insert(output_path.clone()) {
+        return Err(DataConversionErr::DuplicateOutput(output_path));
+    }
+
+    std::fs::write(&output_path, format_fourier_spectra_csv(points))
+        .map_err(|e| DataConversionErr::Io(output_path.clone(), e.into()))?;
+
+    Ok(output_path)
+}
+
+/// This module contains unit tests for the Fourier amplitude spectra.
+///
+/// # Test Categories
+///
+/// - `test_fourier_spectra_sine`: Tests the peak frequency and amplitude of a sine wave and the frequency range
+/// - `test_fourier_spectra_hv`: Tests the H/V ratio of components with known amplitude ratios
+/// - `test_fourier_spectra_flat_ud`: Tests that a flat UD channel leaves the H/V ratio blank instead of inf or NaN
+/// - `test_fourier_spectra_not_acceleration`: Tests rejection of a displacement record
+/// - `test_smoothing`: Tests that the smoothing keeps a flat spectrum and spreads a peak over its neighbours
+/// - `test_window_weights`: Tests the weights at the center and outside the main lobes
+/// - `test_write_fourier_spectra`: Tests the path of the spectra and rejection of a second write
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::record::{test_record, Unit};
+    use std::f64::consts::PI;
+    use tempfile::tempdir;
+
+    // 4096点(40.96秒)で周波数がFFTの格子点に乗る正弦波
+    fn sine(amplitude: f64, frequency: f64) -> Vec<f64> {
+        (0..4096)
+            .map(|i| amplitude * (2.0 * PI * frequency * i as f64 * 0.01).sin())
+            .collect()
+    }
+
+    fn config(window: SpectrumWindow, smoothing: Smoothing) -> FourierSpectrumConfig {
+        FourierSpectrumConfig {
+            window,
+            smoothing,
+            min_hz: 0.1,
+            max_hz: 100.0,
+        }
+    }
+
+    #[test]
+    fn test_fourier_spectra_sine() {
+        let frequency = 200.0 / 40.96;
+        let data = test_record(sine(10.0, frequency), sine(1.0, 1.0), sine(1.0, 1.0));
+        let points =
+            fourier_spectra(&data, &config(SpectrumWindow::Rectangular, Smoothing::None)).unwrap();
+
+        // 0.1Hzからナイキスト周波数(50Hz)まで
+        assert!(points[0].frequency >= 0.1);
+        assert_eq!(points.last().unwrap().frequency, 50.0);
+
+        // 振幅Aの正弦波のピークは A·T/2 となる
+        let peak = points.iter().max_by(|a, b| a.ns.total_cmp(&b.ns)).unwrap();
+        assert!((peak.frequency - frequency).abs() < 1e-9);
+        assert!((peak.ns - 10.0 * 40.96 / 2.0).abs() / peak.ns < 1e-6);
+    }
+
+    #[test]
+    fn test_fourier_spectra_hv() {
+        let data = test_record(sine(4.0, 1.0), sine(9.0, 1.0), sine(2.0, 1.0));
+        let points = fourier_spectra(
+            &data,
+            &config(SpectrumWindow::Hann, Smoothing::KonnoOhmachi(40.0)),
+        )
+        .unwrap();
+
+        // sqrt(4 × 9) / 2 = 3
+        for point in points.iter().filter(|p| (p.frequency - 1.0).abs() < 0.2) {
+            assert!((point.hv.unwrap() - 3.0).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_fourier_spectra_flat_ud() {
+        let data = test_record(sine(4.0, 1.0), sine(9.0, 1.0), vec![3.0; 4096]);
+        let points = fourier_spectra(
+            &data,
+            &config(SpectrumWindow::Hann, Smoothing::KonnoOhmachi(40.0)),
+        )
+        .unwrap();
+
+        assert!(!points.is_empty());
+        assert!(points.iter().all(|p| p.hv.is_none()));
+
+        let csv = format_fourier_spectra_csv(&points);
+        assert!(csv.lines().skip(1).all(|line| line.ends_with(",0.000000,")));
+        assert!(!csv.contains("inf") && !csv.contains("NaN"));
+    }
+
+    #[test]
+    fn test_fourier_spectra_not_acceleration() {
+        let mut data = test_record(sine(4.0, 1.0), sine(9.0, 1.0), sine(2.0, 1.0));
+        data.unit = Unit::Millimeters;
+
+        assert!(matches!(
+            fourier_spectra(&data, &config(SpectrumWindow::Hann, Smoothing::None)),
+            Err(DataProcessingErr::NotAcceleration(_, quantity, _)) if quantity == "displacement"
+        ));
+    }
+
+    #[test]
+    fn test_smoothing() {
+        let frequencies: Vec<f64> = (0..200).map(|k| k as f64 * 0.1).collect();
+        let flat = vec![2.0; 200];
+        for smoothing in [Smoothing::Parzen(0.5), Smoothing::KonnoOhmachi(40.0)] {
+            for value in smooth(&flat, &frequencies, smoothing) {
+                assert!((value - 2.0).abs() < 1e-12);
+            }
+        }
+
+        let mut peak = vec![0.0; 200];
+        peak[100] = 1.0;
+        let smoothed = smooth(&peak, &frequencies, Smoothing::Parzen(0.5));
+        assert!(smoothed[100] < 1.0);
+        assert!(smoothed[101] > 0.0);
+        assert_eq!(smoothed[150], 0.0);
+    }
+
+    #[test]
+    fn test_window_weights() {
+        assert_eq!(parzen_weight(0.0, 0.5), 1.0);
+        assert_eq!(parzen_weight(2.0, 0.5), 0.0);
+        assert_eq!(konno_ohmachi_weight(1.0, 1.0, 40.0), 1.0);
+        assert_eq!(konno_ohmachi_weight(1.3, 1.0, 40.0), 0.0);
+        assert!(konno_ohmachi_weight(1.05, 1.0, 40.0) > konno_ohmachi_weight(1.1, 1.0, 40.0));
+    }
+
+    #[test]
+    fn test_write_fourier_spectra() {
+        let dir = tempdir().unwrap();
+        let converted_path = dir.path().join("20240101-161009-ISK005-knet.csv");
+        let data = test_record(sine(4.0, 1.0), sine(9.0, 1.0), sine(2.0, 1.0));
+        let points =
+            fourier_spectra(&data, &config(SpectrumWindow::Hann, Smoothing::None)).unwrap();
+        let mut written = HashSet::new();
+
+        let path = write_fourier_spectra(&points, &converted_path, &mut written).unwrap();
+        assert_eq!(
+            path,
+            dir.path().join("20240101-161009-ISK005-knet.fourier.csv")
+        );
+        assert!(path.exists());
+
+        // 別のtoで同じ記録を変換した`*-knet.txt`も同じスペクトルを書き込む
+        let other_path = dir.path().join("20240101-161009-ISK005-knet.txt");
+        assert!(matches!(
+            write_fourier_spectra(&points, &other_path, &mut written),
+            Err(DataConversionErr::DuplicateOutput(p)) if p == path
+        ));
+    }
+}
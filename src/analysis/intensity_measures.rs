@@ -0,0 +1,510 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use serde::{Serialize, Serializer};
+
+use crate::{
+    analysis_config_file::SummaryFormat,
+    converter::sibling_path,
+    error::{DataConversionErr, DataProcessingErr},
+    record::Record,
+};
+
+use super::{
+    acceleration_in_gal, integrate_with_baseline_correction, response_spectrum::sdof_response,
+};
+
+// 標準重力加速度(gal)
+const GRAVITY_GAL: f64 = 980.665;
+// CAV5で積算する加速度の下限(gal)
+const CAV5_THRESHOLD_GAL: f64 = 5.0;
+// ブラケット継続時間の加速度の閾値(0.05g)
+const BRACKETED_THRESHOLD_GAL: f64 = 0.05 * GRAVITY_GAL;
+// Housnerのスペクトル強度の減衰定数と周期の範囲(秒)、積分の刻み
+const HOUSNER_DAMPING: f64 = 0.2;
+const HOUSNER_PERIODS: (f64, f64) = (0.1, 2.5);
+const HOUSNER_PERIOD_STEP: f64 = 0.02;
+// 長周期地震動階級の絶対速度応答スペクトルの減衰定数と周期の範囲(秒)、刻み
+const LONG_PERIOD_DAMPING: f64 = 0.05;
+const LONG_PERIODS: (f64, f64) = (1.6, 7.8);
+const LONG_PERIOD_STEP: f64 = 0.2;
+
+/// Long-period ground motion class(長周期地震動階級) of JMA.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LongPeriodClass {
+    Zero,
+    One,
+    Two,
+    Three,
+    Four,
+}
+
+impl LongPeriodClass {
+    /// Returns the class of the maximum absolute velocity response(cm/s) between 1.6 and 7.8 seconds.
+    pub fn from_sva(sva: f64) -> Self {
+        match sva {
+            v if v < 5.0 => LongPeriodClass::Zero,
+            v if v < 15.0 => LongPeriodClass::One,
+            v if v < 50.0 => LongPeriodClass::Two,
+            v if v < 100.0 => LongPeriodClass::Three,
+            _ => LongPeriodClass::Four,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            LongPeriodClass::Zero => "0",
+            LongPeriodClass::One => "1",
+            LongPeriodClass::Two => "2",
+            LongPeriodClass::Three => "3",
+            LongPeriodClass::Four => "4",
+        }
+    }
+}
+
+impl Serialize for LongPeriodClass {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// Intensity measures of one component.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct IntensityMeasures {
+    /// Arias intensity in m/s.
+    pub arias_intensity: f64,
+    /// Time between 5% and 75% of the Arias intensity in seconds.
+    pub d5_75: f64,
+    /// Time between 5% and 95% of the Arias intensity in seconds.
+    pub d5_95: f64,
+    /// Cumulative absolute velocity in cm/s.
+    pub cav: f64,
+    /// Cumulative absolute velocity of the accelerations of 5 gal or more in cm/s.
+    pub cav5: f64,
+    /// Time between the first and the last exceedance of 0.05 g in seconds, 0 if not exceeded.
+    pub bracketed_duration: f64,
+    /// Root mean square of the acceleration between 5% and 95% of the Arias intensity in gal.
+    pub rms: f64,
+    /// Housner spectrum intensity, the integral of the pseudo velocity response with 20% damping from 0.1 to 2.5
+    /// seconds, in cm.
+    pub housner_si: f64,
+    /// Maximum absolute velocity response with 5% damping from 1.6 to 7.8 seconds in cm/s.
+    pub long_period_sva: f64,
+}
+
+impl IntensityMeasures {
+    fn new(acc: &[f64], delta: f64) -> Self {
+        let husid = husid(acc, delta);
+        let total = husid.last().copied().unwrap_or(0.0);
+        let time_at =
+            |ratio: f64| husid.iter().position(|&e| e >= ratio * total).unwrap_or(0) as f64 * delta;
+        let (t5, t75, t95) = (time_at(0.05), time_at(0.75), time_at(0.95));
+
+        let (first, last) = (
+            acc.iter().position(|a| a.abs() >= BRACKETED_THRESHOLD_GAL),
+            acc.iter().rposition(|a| a.abs() >= BRACKETED_THRESHOLD_GAL),
+        );
+        let bracketed_duration = match (first, last) {
+            (Some(first), Some(last)) => (last - first) as f64 * delta,
+            _ => 0.0,
+        };
+
+        // 5%から95%までの区間の二乗平均平方根
+        let significant = &acc[(t5 / delta).round() as usize..=(t95 / delta).round() as usize];
+        let rms =
+            (significant.iter().map(|a| a * a).sum::<f64>() / significant.len() as f64).sqrt();
+
+        Self {
+            // Ia = π / 2g ∫a² dt をm/sで表す
+            arias_intensity: std::f64::consts::PI / (2.0 * GRAVITY_GAL) * total / 100.0,
+            d5_75: t75 - t5,
+            d5_95: t95 - t5,
+            cav: acc.iter().map(|a| a.abs()).sum::<f64>() * delta,
+            cav5: acc
+                .iter()
+                .map(|a| a.abs())
+                .map(|a| if a >= CAV5_THRESHOLD_GAL { a } else { 0.0 })
+                .sum::<f64>()
+                * delta,
+            bracketed_duration,
+            rms,
+            housner_si: housner_si(acc, delta),
+            long_period_sva: long_period_sva(acc, delta),
+        }
+    }
+
+    pub fn long_period_class(&self) -> LongPeriodClass {
+        LongPeriodClass::from_sva(self.long_period_sva)
+    }
+}
+
+// ∫a² dt の累積(Husidプロット)
+fn husid(acc: &[f64], delta: f64) -> Vec<f64> {
+    acc.iter()
+        .scan(0.0, |sum, a| {
+            *sum += a * a * delta;
+            Some(*sum)
+        })
+        .collect()
+}
+
+fn housner_si(acc: &[f64], delta: f64) -> f64 {
+    let (min, max) = HOUSNER_PERIODS;
+    let count = ((max - min) / HOUSNER_PERIOD_STEP).round() as usize;
+
+    let psv: Vec<f64> = (0..=count)
+        .map(|i| {
+            let period = min + i as f64 * HOUSNER_PERIOD_STEP;
+            let sd = sdof_response(acc, delta, period, HOUSNER_DAMPING)
+                .disp
+                .iter()
+                .fold(0.0_f64, |max, d| max.max(d.abs()));
+            2.0 * std::f64::consts::PI / period * sd
+        })
+        .collect();
+
+    // 台形則で周期について積分する
+    psv.windows(2)
+        .map(|w| (w[0] + w[1]) / 2.0 * HOUSNER_PERIOD_STEP)
+        .sum()
+}
+
+// 1.6秒から7.8秒までの各周期の絶対速度応答の時刻歴
+fn absolute_velocity_responses(acc: &[f64], delta: f64) -> impl Iterator<Item = Vec<f64>> + '_ {
+    let (min, max) = LONG_PERIODS;
+    let count = ((max - min) / LONG_PERIOD_STEP).round() as usize;
+    let ground_vel = integrate_with_baseline_correction(acc, delta);
+
+    (0..=count).map(move |i| {
+        let period = min + i as f64 * LONG_PERIOD_STEP;
+        // 絶対速度は相対速度と地動速度の和
+        sdof_response(acc, delta, period, LONG_PERIOD_DAMPING)
+            .vel
+            .iter()
+            .zip(&ground_vel)
+            .map(|(v, g)| v + g)
+            .collect()
+    })
+}
+
+fn long_period_sva(acc: &[f64], delta: f64) -> f64 {
+    absolute_velocity_responses(acc, delta)
+        .map(|vel| vel.iter().fold(0.0_f64, |max, v| max.max(v.abs())))
+        .fold(0.0, f64::max)
+}
+
+/// Returns the Sva of the long-period ground motion class as defined by JMA, which is the maximum over the periods
+/// from 1.6 to 7.8 seconds of the vector sum of the NS and EW absolute velocity responses at each time step.
+pub fn horizontal_long_period_sva(ns: &[f64], ew: &[f64], delta: f64) -> f64 {
+    absolute_velocity_responses(ns, delta)
+        .zip(absolute_velocity_responses(ew, delta))
+        .map(|(ns_vel, ew_vel)| {
+            ns_vel
+                .iter()
+                .zip(&ew_vel)
+                .fold(0.0_f64, |max, (n, e)| max.max(n.hypot(*e)))
+        })
+        .fold(0.0, f64::max)
+}
+
+/// Intensity measures of each component of a record.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IntensityMeasureSummary {
+    pub site_code: String,
+    pub start_time: String,
+    pub ns: IntensityMeasures,
+    pub ew: IntensityMeasures,
+    pub ud: IntensityMeasures,
+    /// Maximum of the vector sum of the NS and EW absolute velocity responses from 1.6 to 7.8 seconds in cm/s.
+    pub long_period_sva: f64,
+    /// Class of `long_period_sva`, as announced by JMA.
+    pub long_period_class: LongPeriodClass,
+}
+
+impl IntensityMeasureSummary {
+    /// Computes the intensity measures of an acceleration record, which is converted to gal if in another unit of
+    /// acceleration.
+    ///
+    /// Returns `None` if the record is empty, and fails if the record is velocity or displacement.
+    pub fn new(record: &Record) -> Result<Option<Self>, DataProcessingErr> {
+        let record = acceleration_in_gal(record, "intensity measures")?;
+        if record.is_empty() {
+            return Ok(None);
+        }
+
+        let delta = record.delta();
+        let components = record.components();
+        // 平均値を除いてから求める
+        let [ns_acc, ew_acc, ud_acc] =
+            [components.ns(), components.ew(), components.ud()].map(|acc| {
+                let mean = acc.iter().sum::<f64>() / acc.len() as f64;
+                acc.iter().map(|a| a - mean).collect::<Vec<f64>>()
+            });
+        let [ns, ew, ud] =
+            [&ns_acc, &ew_acc, &ud_acc].map(|acc| IntensityMeasures::new(acc, delta));
+        let long_period_sva = horizontal_long_period_sva(&ns_acc, &ew_acc, delta);
+
+        Ok(Some(Self {
+            site_code: record.station().code.clone(),
+            start_time: record
+                .start_time()
+                .format("%Y-%m-%dT%H:%M:%S%.3f")
+                .to_string(),
+            ns,
+            ew,
+            ud,
+            long_period_sva,
+            long_period_class: LongPeriodClass::from_sva(long_period_sva),
+        }))
+    }
+}
+
+/// Formats the summary as JSON.
+pub fn format_intensity_measures_json(
+    summary: &IntensityMeasureSummary,
+) -> Result<String, serde_json::Error> {
+    serde_json::to_string_pretty(summary)
+}
+
+/// Formats the summary as CSV, with one row per component.
+///
+/// The long-period class of each component row is that of the component alone. The last `horizontal` row has only
+/// the Sva and the class of the record, from the vector sum of NS and EW.
+///
+/// ```text
+/// site_code,start_time,component,arias_m_s,d5_75_s,d5_95_s,cav_cm_s,cav5_cm_s,bracketed_duration_s,rms_gal,housner_si_cm,long_period_sva_cm_s,long_period_class
+/// ISK005,2024-01-01T16:10:09.000,ns,1.234567,3.450,12.340,512.345678,498.765432,20.120,45.678901,98.765432,23.456789,2
+/// ISK005,2024-01-01T16:10:09.000,horizontal,,,,,,,,,31.234567,2
+/// ```
+pub fn format_intensity_measures_csv(summary: &IntensityMeasureSummary) -> String {
+    let mut csv = "site_code,start_time,component,arias_m_s,d5_75_s,d5_95_s,cav_cm_s,cav5_cm_s,\
+                   bracketed_duration_s,rms_gal,housner_si_cm,long_period_sva_cm_s,long_period_class\n"
+        .to_string();
+
+    for (component, m) in [
+        ("ns", &summary.ns),
+        ("ew", &summary.ew),
+        ("ud", &summary.ud),
+    ] {
+        csv.push_str(&format!(
+            "{},{},{},{:.6},{:.3},{:.3},{:.6},{:.6},{:.3},{:.6},{:.6},{:.6},{}\n",
+            summary.site_code,
+            summary.start_time,
+            component,
+            m.arias_intensity,
+            m.d5_75,
+            m.d5_95,
+            m.cav,
+            m.cav5,
+            m.bracketed_duration,
+            m.rms,
+            m.housner_si,
+            m.long_period_sva,
+            m.long_period_class().as_str()
+        ));
+    }
+    csv.push_str(&format!(
+        "{},{},horizontal,,,,,,,,,{:.6},{}\n",
+        summary.site_code,
+        summary.start_time,
+        summary.long_period_sva,
+        summary.long_period_class.as_str()
+    ));
+
+    csv
+}
+
+/// Writes the summary next to the converted file, e.g. `*-knet.im.json` for `*-knet.csv`, and returns its path.
+///
+/// The path is added to `written`, and is an error if already written in the run.
+pub fn write_intensity_measures(
+    summary: &IntensityMeasureSummary,
+    format: SummaryFormat,
+    converted_path: &Path,
+    written: &mut HashSet<PathBuf>,
+) -> Result<PathBuf, DataConversionErr> {
+    let output_path = sibling_path(converted_path, "im", format.extension());
+    if !written.insert(output_path.clone()) {
+        return Err(DataConversionErr::DuplicateOutput(output_path));
+    }
+
+    let contents = match format {
+        SummaryFormat::Json => format_intensity_measures_json(summary)
+            .map_err(|e| DataConversionErr::Format(output_path.clone(), e.to_string()))?,
+        SummaryFormat::Csv => format_intensity_measures_csv(summary),
+    };
+    std::fs::write(&output_path, contents)
+        .map_err(|e| DataConversionErr::Io(output_path.clone(), e.into()))?;
+
+    Ok(output_path)
+}
+
+/// This module contains unit tests for the intensity measures.
+///
+/// # Test Categories
+///
+/// - `test_intensity_measures_constant`: Tests the measures of a constant acceleration against the analytical values
+/// - `test_significant_duration`: Tests D5-75 and D5-95 of a record whose energy is evenly distributed
+/// - `test_bracketed_duration`: Tests the bracketed duration of two separated pulses and of a weak record
+/// - `test_long_period_class`: Tests the class boundaries and the class of a long-period sine wave
+/// - `test_long_period_class_polarized`: Tests the class from the vector sum of a sine wave polarized at 45 degrees
+/// - `test_intensity_measures_not_acceleration`: Tests rejection of a velocity record
+/// - `test_write_intensity_measures`: Tests the path and the contents of the CSV summary, and rejection of a second write
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::record::{test_record, Unit};
+    use std::f64::consts::PI;
+    use tempfile::tempdir;
+
+    fn record(ns: Vec<f64>) -> Record {
+        let len = ns.len();
+        test_record(ns, vec![0.0; len], vec![0.0; len])
+    }
+
+    #[test]
+    fn test_intensity_measures_constant() {
+        // ±100galの矩形波を10秒間(1000点)
+        let acc: Vec<f64> = (0..1000)
+            .map(|i| if (i / 50) % 2 == 0 { 100.0 } else { -100.0 })
+            .collect();
+        let measures = IntensityMeasures::new(&acc, 0.01);
+
+        // Ia = π / 2g × a² × T
+        let expected_arias = PI / (2.0 * GRAVITY_GAL) * 100.0 * 100.0 * 10.0 / 100.0;
+        assert!((measures.arias_intensity - expected_arias).abs() < 1e-9);
+        assert!((measures.cav - 1000.0).abs() < 1e-9);
+        assert!((measures.cav5 - 1000.0).abs() < 1e-9);
+        assert!((measures.rms - 100.0).abs() < 1e-9);
+        assert!((measures.bracketed_duration - 9.99).abs() < 1e-9);
+        assert!(measures.housner_si > 0.0);
+    }
+
+    #[test]
+    fn test_significant_duration() {
+        let measures = IntensityMeasures::new(&vec![10.0; 1001], 0.01);
+
+        assert!((measures.d5_75 - 7.0).abs() < 0.02);
+        assert!((measures.d5_95 - 9.0).abs() < 0.02);
+        // 5galに満たない加速度はCAV5に含めない
+        let weak = IntensityMeasures::new(&vec![4.0; 100], 0.01);
+        assert!(weak.cav > 0.0);
+        assert_eq!(weak.cav5, 0.0);
+    }
+
+    #[test]
+    fn test_bracketed_duration() {
+        let mut acc = vec![0.0; 1000];
+        acc[100] = 60.0;
+        acc[600] = -50.0;
+        assert!((IntensityMeasures::new(&acc, 0.01).bracketed_duration - 5.0).abs() < 1e-9);
+
+        assert_eq!(
+            IntensityMeasures::new(&vec![10.0; 100], 0.01).bracketed_duration,
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_long_period_class() {
+        assert_eq!(LongPeriodClass::from_sva(4.9), LongPeriodClass::Zero);
+        assert_eq!(LongPeriodClass::from_sva(5.0), LongPeriodClass::One);
+        assert_eq!(LongPeriodClass::from_sva(49.9), LongPeriodClass::Two);
+        assert_eq!(LongPeriodClass::from_sva(100.0), LongPeriodClass::Four);
+
+        // 周期3秒、速度振幅が約10cm/sの正弦波は共振により階級2以上となる
+        let omega = 2.0 * PI / 3.0;
+        let ns: Vec<f64> = (0..6000)
+            .map(|i| 10.0 * omega * (omega * i as f64 * 0.01).sin())
+            .collect();
+        let summary = IntensityMeasureSummary::new(&record(ns)).unwrap().unwrap();
+        assert!(summary.ns.long_period_sva > 15.0);
+        assert!(summary.long_period_class >= LongPeriodClass::Two);
+        assert_eq!(summary.ud.long_period_class(), LongPeriodClass::Zero);
+        assert_eq!(IntensityMeasureSummary::new(&record(Vec::new())), Ok(None));
+    }
+
+    #[test]
+    fn test_long_period_class_polarized() {
+        // 周期3秒、速度振幅6cm/sの正弦波をN45°E方向に与えると、各成分の振幅は6/√2cm/sとなる
+        let omega = 2.0 * PI / 3.0;
+        let component: Vec<f64> = (0..6000)
+            .map(|i| 6.0 / 2.0_f64.sqrt() * omega * (omega * i as f64 * 0.01).sin())
+            .collect();
+        let len = component.len();
+        let summary = IntensityMeasureSummary::new(&test_record(
+            component.clone(),
+            component,
+            vec![0.0; len],
+        ))
+        .unwrap()
+        .unwrap();
+
+        // 時刻ごとのベクトル合成は各成分の√2倍となり、成分ごとの階級より大きくなる
+        assert!(
+            (summary.long_period_sva - summary.ns.long_period_sva * 2.0_f64.sqrt()).abs() < 1e-9
+        );
+        assert_eq!(summary.ns.long_period_class(), LongPeriodClass::Two);
+        assert_eq!(summary.ew.long_period_class(), LongPeriodClass::Two);
+        assert_eq!(summary.long_period_class, LongPeriodClass::Three);
+    }
+
+    #[test]
+    fn test_intensity_measures_not_acceleration() {
+        let mut data = record(vec![1.0; 100]);
+        data.unit = Unit::MetersPerSecond;
+
+        assert_eq!(
+            IntensityMeasureSummary::new(&data),
+            Err(DataProcessingErr::NotAcceleration(
+                "intensity measures".to_string(),
+                "velocity".to_string(),
+                "ISK005".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_write_intensity_measures() {
+        let dir = tempdir().unwrap();
+        let converted_path = dir.path().join("20240101-161009-ISK005-knet.csv");
+        let summary = IntensityMeasureSummary::new(&record(vec![0.0, 2.0, -2.0, 0.0]))
+            .unwrap()
+            .unwrap();
+
+        let mut written = HashSet::new();
+        let path =
+            write_intensity_measures(&summary, SummaryFormat::Csv, &converted_path, &mut written)
+                .unwrap();
+        assert_eq!(path, dir.path().join("20240101-161009-ISK005-knet.im.csv"));
+        let csv = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(csv.lines().count(), 5);
+        assert!(csv
+            .lines()
+            .nth(4)
+            .unwrap()
+            .starts_with("ISK005,2024-01-01T16:10:09.000,horizontal,,,,,,,,,"));
+        assert!(csv
+            .lines()
+            .nth(1)
+            .unwrap()
+            .starts_with("ISK005,2024-01-01T16:10:09.000,ns,"));
+
+        let path =
+            write_intensity_measures(&summary, SummaryFormat::Json, &converted_path, &mut written)
+                .unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(json["long_period_class"], "0");
+        assert!(json["ns"]["cav"].as_f64().unwrap() > 0.0);
+
+        // 別のtoで同じ記録を変換した`*-knet.txt`も同じサマリーを書き込む
+        let other_path = dir.path().join("20240101-161009-ISK005-knet.txt");
+        assert!(matches!(
+            write_intensity_measures(&summary, SummaryFormat::Json, &other_path, &mut written),
+            Err(DataConversionErr::DuplicateOutput(p)) if p == path
+        ));
+    }
+}
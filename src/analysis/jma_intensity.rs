@@ -0,0 +1,257 @@
+use crate::{error::DataProcessingErr, record::Record};
+
+use super::{acceleration_in_gal, filter_by_frequency};
+
+// 合成加速度がその値以上となる時間の合計が0.3秒となる値から計測震度を求める
+const DURATION_THRESHOLD_SEC: f64 = 0.3;
+
+/// Seismic intensity class(震度階級) of the JMA scale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ShindoClass {
+    Zero,
+    One,
+    Two,
+    Three,
+    Four,
+    FiveLower,
+    FiveUpper,
+    SixLower,
+    SixUpper,
+    Seven,
+}
+
+impl ShindoClass {
+    /// Returns the class of an instrumental intensity that has already been rounded to one decimal place.
+    pub fn from_intensity(intensity: f64) -> Self {
+        match intensity {
+            i if i < 0.5 => ShindoClass::Zero,
+            i if i < 1.5 => ShindoClass::One,
+            i if i < 2.5 => ShindoClass::Two,
+            i if i < 3.5 => ShindoClass::Three,
+            i if i < 4.5 => ShindoClass::Four,
+            i if i < 5.0 => ShindoClass::FiveLower,
+            i if i < 5.5 => ShindoClass::FiveUpper,
+            i if i < 6.0 => ShindoClass::SixLower,
+            i if i < 6.5 => ShindoClass::SixUpper,
+            _ => ShindoClass::Seven,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            ShindoClass::Zero => "0",
+            ShindoClass::One => "1",
+            ShindoClass::Two => "2",
+            ShindoClass::Three => "3",
+            ShindoClass::Four => "4",
+            ShindoClass::FiveLower => "5-",
+            ShindoClass::FiveUpper => "5+",
+            ShindoClass::SixLower => "6-",
+            ShindoClass::SixUpper => "6+",
+            ShindoClass::Seven => "7",
+        }
+    }
+}
+
+/// JMA instrumental seismic intensity(計測震度) of a record.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JmaIntensity {
+    /// Intensity before rounding, `2 log10(a) + 0.94`.
+    pub value: f64,
+}
+
+impl JmaIntensity {
+    /// Intensity as announced by JMA, which is rounded at the third decimal place and then truncated at the second.
+    pub fn rounded(&self) -> f64 {
+        ((self.value * 100.0).round() / 10.0).floor() / 10.0
+    }
+
+    pub fn class(&self) -> ShindoClass {
+        ShindoClass::from_intensity(self.rounded())
+    }
+}
+
+/// Computes the JMA instrumental seismic intensity of an acceleration record, which is converted to gal if in
+/// another unit of acceleration.
+///
+/// Each component is filtered in the frequency domain with the period effect, high-cut and low-cut filters, and the
+/// intensity is given by the value `a` of the vector sum of the three components that is exceeded for 0.3 seconds
+/// in total. A record without motion has an intensity of negative infinity, which is class 0. Returns `None` only if
+/// the record is shorter than 0.3 seconds, and fails if the record is velocity or displacement.
+pub fn jma_intensity(record: &Record) -> Result<Option<JmaIntensity>, DataProcessingErr> {
+    let record = acceleration_in_gal(record, "JMA instrumental seismic intensity")?;
+    let delta = record.delta();
+    let threshold_count = (DURATION_THRESHOLD_SEC / delta).round() as usize;
+    if threshold_count == 0 || record.len() < threshold_count {
+        return Ok(None);
+    }
+
+    let filtered: Vec<Vec<f64>> = record
+        .components()
+        .iter()
+        .map(|(_, values)| filter_by_frequency(values, delta, jma_filter_gain))
+        .collect();
+
+    let mut vector_sum: Vec<f64> = (0..record.len())
+        .map(|i| filtered.iter().map(|c| c[i].powi(2)).sum::<f64>().sqrt())
+        .collect();
+    vector_sum.sort_by(|a, b| b.total_cmp(a));
+
+    // aが0の場合、log10は負の無限大となる
+    let a = vector_sum[threshold_count - 1];
+    Ok(Some(JmaIntensity {
+        value: 2.0 * a.log10() + 0.94,
+    }))
+}
+
+/// Formats the intensity of a record as a CSV line without a line break, whose intensity and class are blank if
+/// `None`. The intensity of a record without motion is written as `-inf`.
+///
+/// ```text
+/// ISK005,2024-01-01T16:10:09.000,5.1,5+
+/// ```
+pub fn format_jma_intensity(record: &Record, intensity: Option<JmaIntensity>) -> String {
+    let (rounded, class) = intensity
+        .map(|i| {
+            (
+                format!("{:.1}", i.rounded()),
+                i.class().as_str().to_string(),
+            )
+        })
+        .unwrap_or_default();
+
+    format!(
+        "{},{},{},{}",
+        record.station().code,
+        record.start_time().format("%Y-%m-%dT%H:%M:%S%.3f"),
+        rounded,
+        class
+    )
+}
+
+// 周期効果、ハイカット、ローカットフィルターの積
+fn jma_filter_gain(f: f64) -> f64 {
+    if f <= 0.0 {
+        return 0.0;
+    }
+
+    let period_effect = (1.0 / f).sqrt();
+
+    let y = f / 10.0;
+    let high_cut = (1.0
+        + 0.694 * y.powi(2)
+        + 0.241 * y.powi(4)
+        + 0.0557 * y.powi(6)
+        + 0.009664 * y.powi(8)
+        + 0.00134 * y.powi(10)
+        + 0.000155 * y.powi(12))
+    .powf(-0.5);
+
+    let low_cut = (1.0 - (-(f / 0.5).powi(3)).exp()).sqrt();
+
+    period_effect * high_cut * low_cut
+}
+
+/// This module contains unit tests for the JMA instrumental seismic intensity.
+///
+/// # Test Categories
+///
+/// - `test_shindo_class`: Tests the class boundaries including 5-/5+/6-/6+
+/// - `test_rounded`: Tests the rounding at the third decimal place and the truncation at the second
+/// - `test_jma_intensity_sine`: Tests the intensity of a 1 Hz sine wave against the analytical value
+/// - `test_jma_intensity_too_short`: Tests that a record shorter than 0.3 seconds has no intensity
+/// - `test_jma_intensity_zero`: Tests that a record without motion is class 0
+/// - `test_jma_intensity_not_acceleration`: Tests rejection of a velocity record
+/// - `test_format_jma_intensity`: Tests the line of a record with and without the intensity
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::record::{test_record, Unit};
+    use std::f64::consts::PI;
+
+    fn record(ns: Vec<f64>) -> Record {
+        let len = ns.len();
+        test_record(ns, vec![0.0; len], vec![0.0; len])
+    }
+
+    #[test]
+    fn test_shindo_class() {
+        for (intensity, expected) in [
+            (0.4, "0"),
+            (0.5, "1"),
+            (4.4, "4"),
+            (4.5, "5-"),
+            (4.9, "5-"),
+            (5.0, "5+"),
+            (5.5, "6-"),
+            (6.0, "6+"),
+            (6.5, "7"),
+        ] {
+            assert_eq!(ShindoClass::from_intensity(intensity).as_str(), expected);
+        }
+    }
+
+    #[test]
+    fn test_rounded() {
+        assert_eq!(JmaIntensity { value: 4.495 }.rounded(), 4.5);
+        assert_eq!(JmaIntensity { value: 4.494 }.rounded(), 4.4);
+        assert_eq!(JmaIntensity { value: 6.58 }.class(), ShindoClass::Seven);
+    }
+
+    #[test]
+    fn test_jma_intensity_sine() {
+        // 振幅100galの1Hzの正弦波(20秒)
+        let ns: Vec<f64> = (0..2000)
+            .map(|i| 100.0 * (2.0 * PI * i as f64 * 0.01).sin())
+            .collect();
+
+        // 1Hzではフィルターの係数はほぼ1となり、0.3秒を超える値は振幅の約0.9997倍となる
+        let intensity = jma_intensity(&record(ns)).unwrap().unwrap();
+        let expected = 2.0 * (100.0 * jma_filter_gain(1.0) * 0.9997_f64).log10() + 0.94;
+
+        assert!((intensity.value - expected).abs() < 0.02);
+        assert_eq!(intensity.class(), ShindoClass::FiveLower);
+    }
+
+    #[test]
+    fn test_jma_intensity_too_short() {
+        assert_eq!(jma_intensity(&record(vec![1.0; 20])), Ok(None));
+    }
+
+    #[test]
+    fn test_jma_intensity_zero() {
+        let intensity = jma_intensity(&record(vec![0.0; 1000])).unwrap().unwrap();
+
+        assert_eq!(intensity.value, f64::NEG_INFINITY);
+        assert_eq!(intensity.class(), ShindoClass::Zero);
+    }
+
+    #[test]
+    fn test_jma_intensity_not_acceleration() {
+        let mut data = record(vec![1.0; 1000]);
+        data.unit = Unit::CentimetersPerSecond;
+
+        assert_eq!(
+            jma_intensity(&data),
+            Err(DataProcessingErr::NotAcceleration(
+                "JMA instrumental seismic intensity".to_string(),
+                "velocity".to_string(),
+                "ISK005".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_format_jma_intensity() {
+        let data = record(vec![0.0; 10]);
+
+        assert_eq!(
+            format_jma_intensity(&data, Some(JmaIntensity { value: 5.14 })),
+            "ISK005,2024-01-01T16:10:09.000,5.1,5+"
+        );
+        assert_eq!(
+            format_jma_intensity(&data, None),
+            "ISK005,2024-01-01T16:10:09.000,,"
+        );
+    }
+}
@@ -0,0 +1,227 @@
+use std::borrow::Cow;
+
+use rustfft::{num_complex::Complex, FftPlanner};
+
+use crate::{
+    error::DataProcessingErr,
+    record::{Record, Unit},
+};
+
+pub mod fourier_spectrum;
+pub mod intensity_measures;
+pub mod jma_intensity;
+pub mod peak;
+pub mod response_spectrum;
+pub mod rotation;
+
+/// Integrates the waveform with the trapezoidal rule, starting from zero.
+pub fn integrate(values: &[f64], delta: f64) -> Vec<f64> {
+    let mut integrated = Vec::with_capacity(values.len());
+    let mut sum = 0.0;
+
+    for (i, v) in values.iter().enumerate() {
+        if i > 0 {
+            sum += (values[i - 1] + v) * delta / 2.0;
+        }
+        integrated.push(sum);
+    }
+
+    integrated
+}
+
+/// Integrates the waveform in the frequency domain by dividing each frequency by `i2πf`.
+///
+/// As the zero frequency is dropped, the result has an offset depending on the padding, which is to be removed by a
+/// baseline correction.
+///
+/// The waveform is padded with zeros to twice its length or more to reduce the wrap-around of the periodic result.
+pub fn integrate_in_frequency_domain(values: &[f64], delta: f64) -> Vec<f64> {
+    if values.is_empty() {
+        return Vec::new();
+    }
+
+    let n = (2 * values.len()).next_power_of_two();
+    let mut buffer: Vec<Complex<f64>> = values
+        .iter()
+        .map(|&v| Complex::new(v, 0.0))
+        .chain(std::iter::repeat(Complex::new(0.0, 0.0)))
+        .take(n)
+        .collect();
+
+    let mut planner = FftPlanner::new();
+    planner.plan_fft_forward(n).process(&mut buffer);
+
+    // 負の周波数は k - n として扱う
+    let df = 1.0 / (n as f64 * delta);
+    for (k, value) in buffer.iter_mut().enumerate() {
+        let frequency = if k <= n / 2 {
+            k as f64
+        } else {
+            k as f64 - n as f64
+        } * df;
+        *value = if k == 0 {
+            Complex::new(0.0, 0.0)
+        } else {
+            *value / Complex::new(0.0, 2.0 * std::f64::consts::PI * frequency)
+        };
+    }
+
+    planner.plan_fft_inverse(n).process(&mut buffer);
+
+    buffer
+        .iter()
+        .take(values.len())
+        .map(|v| v.re / n as f64)
+        .collect()
+}
+
+/// Removes the straight line fitted by least squares from the waveform.
+pub fn remove_linear_trend(values: &mut [f64]) {
+    let n = values.len() as f64;
+    if values.len() < 2 {
+        values.iter_mut().for_each(|v| *v = 0.0);
+        return;
+    }
+
+    let mean_x = (n - 1.0) / 2.0;
+    let mean_y = values.iter().sum::<f64>() / n;
+    let (sxy, sxx) = values
+        .iter()
+        .enumerate()
+        .fold((0.0, 0.0), |(sxy, sxx), (i, v)| {
+            let dx = i as f64 - mean_x;
+            (sxy + dx * (v - mean_y), sxx + dx * dx)
+        });
+    let slope = sxy / sxx;
+
+    for (i, v) in values.iter_mut().enumerate() {
+        *v -= mean_y + slope * (i as f64 - mean_x);
+    }
+}
+
+/// Integrates the waveform and removes the linear trend of the result, which is caused by the baseline offset of
+/// the integrated waveform.
+pub fn integrate_with_baseline_correction(values: &[f64], delta: f64) -> Vec<f64> {
+    let mut integrated = integrate(values, delta);
+    remove_linear_trend(&mut integrated);
+    integrated
+}
+
+/// Returns the record in gal, or fails if it is not acceleration, from which `analysis` is computed.
+pub(crate) fn acceleration_in_gal<'a>(
+    record: &'a Record,
+    analysis: &str,
+) -> Result<Cow<'a, Record>, DataProcessingErr> {
+    record.to_unit(Unit::Gal).map_err(|_| {
+        DataProcessingErr::NotAcceleration(
+            analysis.to_string(),
+            record.quantity().as_str().to_string(),
+            record.station().code.clone(),
+        )
+    })
+}
+
+/// Filters the waveform in the frequency domain by multiplying each frequency(Hz) by `gain`.
+///
+/// The waveform is padded with zeros to a power of two before FFT, and the padding is removed from the result.
+/// As the gain is real, the phase of the waveform is not changed.
+fn filter_by_frequency(values: &[f64], delta: f64, gain: impl Fn(f64) -> f64) -> Vec<f64> {
+    if values.is_empty() {
+        return Vec::new();
+    }
+
+    let n = values.len().next_power_of_two();
+    let mut buffer: Vec<Complex<f64>> = values
+        .iter()
+        .map(|&v| Complex::new(v, 0.0))
+        .chain(std::iter::repeat(Complex::new(0.0, 0.0)))
+        .take(n)
+        .collect();
+
+    let mut planner = FftPlanner::new();
+    planner.plan_fft_forward(n).process(&mut buffer);
+
+    // 負の周波数側も同じ係数を掛けて、実数の波形を保つ
+    let df = 1.0 / (n as f64 * delta);
+    for (k, value) in buffer.iter_mut().enumerate() {
+        *value *= gain(k.min(n - k) as f64 * df);
+    }
+
+    planner.plan_fft_inverse(n).process(&mut buffer);
+
+    buffer
+        .iter()
+        .take(values.len())
+        .map(|v| v.re / n as f64)
+        .collect()
+}
+
+/// This module contains unit tests for the helpers shared by the analyses.
+///
+/// # Test Categories
+///
+/// - `test_integrate`: Tests the trapezoidal integration of a linear waveform
+/// - `test_integrate_in_frequency_domain`: Tests the integration of a sine wave against the analytical cosine
+/// - `test_remove_linear_trend`: Tests removal of a straight line
+/// - `test_filter_by_frequency`: Tests that a unit gain keeps the waveform and a low-pass gain removes a high frequency
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_integrate() {
+        assert_eq!(
+            integrate(&[0.0, 1.0, 2.0, 3.0], 0.5),
+            vec![0.0, 0.25, 1.0, 2.25]
+        );
+    }
+
+    #[test]
+    fn test_integrate_in_frequency_domain() {
+        // a = sin(ωt) の積分は、平均を除くと -cos(ωt) / ω となる
+        let (delta, omega) = (0.01, 2.0 * PI);
+        let values: Vec<f64> = (0..1000)
+            .map(|i| (omega * i as f64 * delta).sin())
+            .collect();
+        let mut integrated = integrate_in_frequency_domain(&values, delta);
+        assert_eq!(integrated.len(), 1000);
+
+        let mean = integrated.iter().sum::<f64>() / integrated.len() as f64;
+        integrated.iter_mut().for_each(|v| *v -= mean);
+        // 端の影響を避けるため、中央の区間で比べる
+        for (i, v) in integrated.iter().enumerate().skip(250).take(500) {
+            let expected = -(omega * i as f64 * delta).cos() / omega;
+            assert!((v - expected).abs() < 2e-3, "{}: {} != {}", i, v, expected);
+        }
+    }
+
+    #[test]
+    fn test_remove_linear_trend() {
+        let mut values: Vec<f64> = (0..5).map(|i| 2.0 * i as f64 + 1.0).collect();
+        remove_linear_trend(&mut values);
+        assert!(values.iter().all(|v| v.abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_filter_by_frequency() {
+        let delta = 0.01;
+        let low: Vec<f64> = (0..1024)
+            .map(|i| (2.0 * PI * 1.0 * i as f64 * delta).sin())
+            .collect();
+        let mixed: Vec<f64> = low
+            .iter()
+            .enumerate()
+            .map(|(i, v)| v + (2.0 * PI * 25.0 * i as f64 * delta).sin())
+            .collect();
+
+        for (a, e) in filter_by_frequency(&low, delta, |_| 1.0).iter().zip(&low) {
+            assert!((a - e).abs() < 1e-9);
+        }
+        // 端の影響を避けるため、中央の区間で低周波成分だけが残ることを確認する
+        let filtered = filter_by_frequency(&mixed, delta, |f| if f < 10.0 { 1.0 } else { 0.0 });
+        for (a, e) in filtered.iter().zip(&low).skip(256).take(512) {
+            assert!((a - e).abs() < 1e-2);
+        }
+    }
+}
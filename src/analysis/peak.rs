@@ -0,0 +1,353 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+
+use crate::{
+    analysis_config_file::{RotD, SummaryFormat},
+    converter::sibling_path,
+    error::{DataConversionErr, DataProcessingErr},
+    record::Record,
+};
+
+use super::{
+    acceleration_in_gal, integrate_with_baseline_correction,
+    rotation::{median, rotated_peaks, rotd_azimuth},
+};
+
+/// Peak absolute value of a waveform and the elapsed time from the start time of the record when it occurs.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Peak {
+    pub value: f64,
+    pub time_sec: f64,
+}
+
+impl Peak {
+    fn find(values: impl Iterator<Item = f64>, delta: f64) -> Self {
+        let (index, value) = values
+            .map(f64::abs)
+            .enumerate()
+            .fold((0, 0.0), |max, (i, v)| if v > max.1 { (i, v) } else { max });
+
+        Self {
+            value,
+            time_sec: index as f64 * delta,
+        }
+    }
+
+    // 水平2成分をRotD50またはRotD100の方位に回転した成分の最大値
+    fn rotated(ns: &[f64], ew: &[f64], rotd: RotD, delta: f64) -> Self {
+        let azimuth = rotd_azimuth(ns, ew, rotd);
+        let (sin, cos) = azimuth.to_radians().sin_cos();
+
+        Self::find(ns.iter().zip(ew).map(|(n, e)| n * cos + e * sin), delta)
+    }
+
+    // 応答スペクトルのRotD50と同じく、各方位の最大値の中央値とする
+    // 時刻は中央の2つのうち小さい方の方位に回転した成分の最大値の時刻
+    fn rotd50(ns: &[f64], ew: &[f64], delta: f64) -> Self {
+        let peaks: Vec<f64> = rotated_peaks(ns, ew)
+            .into_iter()
+            .map(|(_, peak)| peak)
+            .collect();
+
+        Self {
+            value: median(&peaks),
+            ..Self::rotated(ns, ew, RotD::RotD50, delta)
+        }
+    }
+}
+
+/// Peaks of one quantity, per component, as vector sums and as RotD50/RotD100 of the horizontals.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct PeakSet {
+    pub ns: Peak,
+    pub ew: Peak,
+    pub ud: Peak,
+    /// Peak of the vector sum of NS and EW.
+    pub horizontal: Peak,
+    /// Peak of the vector sum of NS, EW and UD.
+    pub vector: Peak,
+    /// Median of the peaks of the horizontal component rotated to each azimuth, as RotD50 of the response spectra.
+    pub rotd50: Peak,
+    /// Peak of the horizontal component rotated to the azimuth of RotD100, the largest over all azimuths.
+    pub rotd100: Peak,
+}
+
+impl PeakSet {
+    fn new(ns: &[f64], ew: &[f64], ud: &[f64], delta: f64) -> Self {
+        let horizontal = ns.iter().zip(ew).map(|(n, e)| n.hypot(*e));
+        let vector = ns
+            .iter()
+            .zip(ew)
+            .zip(ud)
+            .map(|((n, e), u)| (n * n + e * e + u * u).sqrt());
+
+        Self {
+            ns: Peak::find(ns.iter().copied(), delta),
+            ew: Peak::find(ew.iter().copied(), delta),
+            ud: Peak::find(ud.iter().copied(), delta),
+            horizontal: Peak::find(horizontal, delta),
+            vector: Peak::find(vector, delta),
+            rotd50: Peak::rotd50(ns, ew, delta),
+            rotd100: Peak::rotated(ns, ew, RotD::RotD100, delta),
+        }
+    }
+
+    fn rows(&self) -> [(&str, &Peak); 7] {
+        [
+            ("ns", &self.ns),
+            ("ew", &self.ew),
+            ("ud", &self.ud),
+            ("horizontal", &self.horizontal),
+            ("vector", &self.vector),
+            ("rotd50", &self.rotd50),
+            ("rotd100", &self.rotd100),
+        ]
+    }
+}
+
+/// Peak ground acceleration(gal), velocity(cm/s) and displacement(cm) of a record.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PeakSummary {
+    pub site_code: String,
+    /// Start time of the record, to which the times of the peaks are relative.
+    pub start_time: String,
+    pub pga: PeakSet,
+    pub pgv: PeakSet,
+    pub pgd: PeakSet,
+}
+
+impl PeakSummary {
+    /// Computes the peaks of an acceleration record in gal, whatever the unit of acceleration is, or fails if the
+    /// record is velocity or displacement.
+    ///
+    /// The velocity and displacement are integrated from the acceleration with its mean removed, and the linear trend of
+    /// each integrated waveform is removed as a baseline correction.
+    pub fn new(record: &Record) -> Result<Self, DataProcessingErr> {
+        let record = acceleration_in_gal(record, "peak ground motion summary")?;
+        let delta = record.delta();
+        let components = record.components();
+
+        let mut acc: Vec<Vec<f64>> = components.iter().map(|(_, v)| v.to_vec()).collect();
+        for values in &mut acc {
+            let mean = values.iter().sum::<f64>() / values.len().max(1) as f64;
+            values.iter_mut().for_each(|v| *v -= mean);
+        }
+        let vel: Vec<Vec<f64>> = acc
+            .iter()
+            .map(|v| integrate_with_baseline_correction(v, delta))
+            .collect();
+        let disp: Vec<Vec<f64>> = vel
+            .iter()
+            .map(|v| integrate_with_baseline_correction(v, delta))
+            .collect();
+
+        // 最大加速度は記録された値そのものから求める
+        Ok(Self {
+            site_code: record.station().code.clone(),
+            start_time: record
+                .start_time()
+                .format("%Y-%m-%dT%H:%M:%S%.3f")
+                .to_string(),
+            pga: PeakSet::new(components.ns(), components.ew(), components.ud(), delta),
+            pgv: PeakSet::new(&vel[0], &vel[1], &vel[2], delta),
+            pgd: PeakSet::new(&disp[0], &disp[1], &disp[2], delta),
+        })
+    }
+}
+
+/// Formats the summary as JSON.
+pub fn format_peak_summary_json(summary: &PeakSummary) -> Result<String, serde_json::Error> {
+    serde_json::to_string_pretty(summary)
+}
+
+/// Formats the summary as CSV, with one row per quantity and component.
+///
+/// ```text
+/// site_code,start_time,quantity,unit,component,peak,time_sec
+/// ISK005,2024-01-01T16:10:09.000,pga,gal,ns,123.456,12.340
+/// ```
+pub fn format_peak_summary_csv(summary: &PeakSummary) -> String {
+    let mut csv = "site_code,start_time,quantity,unit,component,peak,time_sec\n".to_string();
+
+    for (quantity, unit, peaks) in [
+        ("pga", "gal", &summary.pga),
+        ("pgv", "cm/s", &summary.pgv),
+        ("pgd", "cm", &summary.pgd),
+    ] {
+        for (component, peak) in peaks.rows() {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{:.3},{:.3}\n",
+                summary.site_code,
+                summary.start_time,
+                quantity,
+                unit,
+                component,
+                peak.value,
+                peak.time_sec
+            ));
+        }
+    }
+
+    csv
+}
+
+/// Writes the summary next to the converted file, e.g. `*-knet.peak.json` for `*-knet.csv`, and returns its path.
+///
+/// The path is added to `written`, and is an error if already written in the run, e.g. by another conversion of the
+/// same record to a different `to`.
+pub fn write_peak_summary(
+    summary: &PeakSummary,
+    format: SummaryFormat,
+    converted_path: &Path,
+    written: &mut HashSet<PathBuf>,
+) -> Result<PathBuf, DataConversionErr> {
+    let output_path = sibling_path(converted_path, "peak", format.extension());
+    if !written.insert(output_path.clone()) {
+        return Err(DataConversionErr::DuplicateOutput(output_path));
+    }
+
+    let contents = match format {
+        SummaryFormat::Json => format_peak_summary_json(summary)
+            .map_err(|e| DataConversionErr::Format(output_path.clone(), e.to_string()))?,
+        SummaryFormat::Csv => format_peak_summary_csv(summary),
+    };
+    std::fs::write(&output_path, contents)
+        .map_err(|e| DataConversionErr::Io(output_path.clone(), e.into()))?;
+
+    Ok(output_path)
+}
+
+/// This module contains unit tests for the peak ground motion summary.
+///
+/// # Test Categories
+///
+/// - `test_peak_summary_sine`: Tests the PGA, PGV and PGD of a sine wave against the analytical values
+/// - `test_peak_summary_not_acceleration`: Tests rejection of a displacement record
+/// - `test_peak_vector`: Tests the horizontal and 3D vector peaks
+/// - `test_peak_rotd`: Tests RotD50/RotD100 of a motion polarized at 45 degrees and RotD50 as the median of the peaks
+/// - `test_write_peak_summary`: Tests the path and the contents of the JSON and CSV summaries, and rejection of a second
+///   write
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::record::{test_record, Unit};
+    use std::f64::consts::PI;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_peak_summary_sine() {
+        // a = A sin(ωt) の場合、v = -A/ω cos(ωt) + C、d = -A/ω² sin(ωt) + Ct + C'
+        let (amplitude, omega) = (100.0, 2.0 * PI);
+        let ns: Vec<f64> = (0..2000)
+            .map(|i| amplitude * (omega * i as f64 * 0.01).sin())
+            .collect();
+        let summary = PeakSummary::new(&test_record(ns, vec![0.0; 2000], vec![0.0; 2000])).unwrap();
+
+        assert!((summary.pga.ns.value - amplitude).abs() < 0.1);
+        // 正負のピークは0.25秒から0.5秒ごとに現れる
+        assert!(((summary.pga.ns.time_sec - 0.25) % 0.5).abs() < 1e-9);
+        assert!((summary.pgv.ns.value - amplitude / omega).abs() / (amplitude / omega) < 0.02);
+        // 変位には一次の基線補正で取り切れない長周期の誤差が残る
+        assert!(
+            (summary.pgd.ns.value - amplitude / omega.powi(2)).abs() / (amplitude / omega.powi(2))
+                < 0.1
+        );
+        assert_eq!(summary.pga.ew.value, 0.0);
+    }
+
+    #[test]
+    fn test_peak_summary_not_acceleration() {
+        let mut data = test_record(vec![1.0; 10], vec![0.0; 10], vec![0.0; 10]);
+        data.unit = Unit::Centimeters;
+
+        assert_eq!(
+            PeakSummary::new(&data),
+            Err(DataProcessingErr::NotAcceleration(
+                "peak ground motion summary".to_string(),
+                "displacement".to_string(),
+                "ISK005".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_peak_vector() {
+        let peaks = PeakSet::new(&[3.0, 0.0], &[-4.0, 1.0], &[0.0, -12.0], 0.01);
+
+        assert_eq!(peaks.ew.value, 4.0);
+        assert_eq!(peaks.horizontal.value, 5.0);
+        assert_eq!(peaks.horizontal.time_sec, 0.0);
+        assert!((peaks.vector.value - 145.0_f64.sqrt()).abs() < 1e-12);
+        assert_eq!(peaks.vector.time_sec, 0.01);
+    }
+
+    #[test]
+    fn test_peak_rotd() {
+        // N45°E方向に偏った水平動では、RotD100は各成分の√2倍となる
+        let motion: Vec<f64> = (0..200).map(|i| (i as f64 * 0.05).sin()).collect();
+        let peaks = PeakSet::new(&motion, &motion, &[0.0; 200], 0.01);
+
+        assert!((peaks.rotd100.value - peaks.ns.value * 2.0_f64.sqrt()).abs() < 1e-12);
+        assert_eq!(peaks.rotd100.time_sec, peaks.ns.time_sec);
+        // 中央値の方位は偏りの方向から45度離れ、その最大値は各成分と等しい
+        assert!((peaks.rotd50.value - peaks.ns.value).abs() / peaks.ns.value < 0.03);
+        assert!(peaks.rotd50.value <= peaks.rotd100.value);
+
+        // RotD50は中央の2つの最大値の平均で、応答スペクトルのRotD50と定義が一致する
+        let ns: Vec<f64> = (0..200).map(|i| (i as f64 * 0.05).sin()).collect();
+        let ew: Vec<f64> = (0..200).map(|i| 0.3 * (i as f64 * 0.11).cos()).collect();
+        let sorted: Vec<f64> = rotated_peaks(&ns, &ew)
+            .into_iter()
+            .map(|(_, p)| p)
+            .collect();
+        let peaks = PeakSet::new(&ns, &ew, &[0.0; 200], 0.01);
+        assert_eq!(peaks.rotd50.value, (sorted[89] + sorted[90]) / 2.0);
+        assert_ne!(peaks.rotd50.value, sorted[89]);
+    }
+
+    #[test]
+    fn test_write_peak_summary() {
+        let dir = tempdir().unwrap();
+        let converted_path = dir.path().join("20240101-161009-ISK005-knet.csv");
+        let summary = PeakSummary::new(&test_record(
+            vec![0.0, 2.0, -1.0],
+            vec![0.0; 3],
+            vec![0.0; 3],
+        ))
+        .unwrap();
+
+        let mut written = HashSet::new();
+        let json_path =
+            write_peak_summary(&summary, SummaryFormat::Json, &converted_path, &mut written)
+                .unwrap();
+        assert_eq!(
+            json_path,
+            dir.path().join("20240101-161009-ISK005-knet.peak.json")
+        );
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&json_path).unwrap()).unwrap();
+        assert_eq!(json["site_code"], "ISK005");
+        assert_eq!(json["pga"]["ns"]["value"], 2.0);
+
+        let csv_path =
+            write_peak_summary(&summary, SummaryFormat::Csv, &converted_path, &mut written)
+                .unwrap();
+        let csv = std::fs::read_to_string(&csv_path).unwrap();
+        assert_eq!(csv.lines().count(), 22);
+        assert_eq!(
+            csv.lines().nth(1).unwrap(),
+            "ISK005,2024-01-01T16:10:09.000,pga,gal,ns,2.000,0.010"
+        );
+
+        // 別のtoで同じ記録を変換した`*-knet.txt`も同じサマリーを書き込む
+        let other_path = dir.path().join("20240101-161009-ISK005-knet.txt");
+        assert!(matches!(
+            write_peak_summary(&summary, SummaryFormat::Json, &other_path, &mut written),
+            Err(DataConversionErr::DuplicateOutput(p)) if p == json_path
+        ));
+    }
+}
@@ -0,0 +1,337 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    converter::sibling_path,
+    error::{DataConversionErr, DataProcessingErr},
+    record::Record,
+};
+
+use super::{
+    acceleration_in_gal,
+    rotation::{median, rotated_peaks},
+};
+
+/// Time histories of a single-degree-of-freedom system excited by a ground acceleration.
+pub(crate) struct SdofResponse {
+    /// Relative displacement.
+    pub(crate) disp: Vec<f64>,
+    /// Relative velocity.
+    pub(crate) vel: Vec<f64>,
+    /// Absolute acceleration.
+    pub(crate) acc: Vec<f64>,
+}
+
+/// Solves the response of a single-degree-of-freedom system to the ground acceleration with the Nigam–Jennings
+/// method, which is exact for an acceleration varying linearly within each time step.
+pub(crate) fn sdof_response(
+    ground_acc: &[f64],
+    delta: f64,
+    period: f64,
+    damping: f64,
+) -> SdofResponse {
+    let w = 2.0 * std::f64::consts::PI / period;
+    let h = damping;
+    let sqrt_1_h2 = (1.0 - h * h).sqrt();
+    let wd = w * sqrt_1_h2;
+    let dt = delta;
+
+    let e = (-h * w * dt).exp();
+    let (s, c) = (wd * dt).sin_cos();
+
+    let a11 = e * (h / sqrt_1_h2 * s + c);
+    let a12 = e * s / wd;
+    let a21 = -w / sqrt_1_h2 * e * s;
+    let a22 = e * (c - h / sqrt_1_h2 * s);
+
+    let k1 = (2.0 * h * h - 1.0) / (w * w * dt);
+    let k2 = 2.0 * h / (w * w * w * dt);
+    let b11 = e * ((k1 + h / w) * s / wd + (k2 + 1.0 / (w * w)) * c) - k2;
+    let b12 = -e * (k1 * s / wd + k2 * c) - 1.0 / (w * w) + k2;
+    let b21 = e
+        * ((k1 + h / w) * (c - h / sqrt_1_h2 * s) - (k2 + 1.0 / (w * w)) * (wd * s + h * w * c))
+        + 1.0 / (w * w * dt);
+    let b22 = -e * (k1 * (c - h / sqrt_1_h2 * s) - k2 * (wd * s + h * w * c)) - 1.0 / (w * w * dt);
+
+    let n = ground_acc.len();
+    let mut disp = vec![0.0; n];
+    let mut vel = vec![0.0; n];
+    for i in 1..n {
+        let (ag0, ag1) = (ground_acc[i - 1], ground_acc[i]);
+        disp[i] = a11 * disp[i - 1] + a12 * vel[i - 1] + b11 * ag0 + b12 * ag1;
+        vel[i] = a21 * disp[i - 1] + a22 * vel[i - 1] + b21 * ag0 + b22 * ag1;
+    }
+
+    // 絶対加速度は運動方程式から求める
+    let acc = disp
+        .iter()
+        .zip(&vel)
+        .map(|(d, v)| -(2.0 * h * w * v + w * w * d))
+        .collect();
+
+    SdofResponse { disp, vel, acc }
+}
+
+fn peak(values: impl Iterator<Item = f64>) -> f64 {
+    values.fold(0.0, |max, v| max.max(v.abs()))
+}
+
+/// Spectral acceleration(gal), velocity(cm/s) and displacement(cm) of one period.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpectralValues {
+    pub sa: f64,
+    pub sv: f64,
+    pub sd: f64,
+}
+
+impl SpectralValues {
+    fn new(response: &SdofResponse) -> Self {
+        Self {
+            sa: peak(response.acc.iter().copied()),
+            sv: peak(response.vel.iter().copied()),
+            sd: peak(response.disp.iter().copied()),
+        }
+    }
+}
+
+/// Spectral values of one damping ratio and period, per component and as RotD50/RotD100 of the horizontals.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpectrumPoint {
+    pub damping: f64,
+    pub period: f64,
+    pub ns: SpectralValues,
+    pub ew: SpectralValues,
+    pub ud: SpectralValues,
+    /// Median over the rotation angles of the peak responses of the rotated horizontal component.
+    pub rotd50: SpectralValues,
+    /// Maximum over the rotation angles of the peak responses of the rotated horizontal component.
+    pub rotd100: SpectralValues,
+}
+
+/// Computes the elastic response spectra of an acceleration record in gal for each damping ratio and period, whatever
+/// the unit of acceleration is, or fails if the record is velocity or displacement.
+///
+/// RotD50 and RotD100 are given by rotating the responses of NS and EW from 0 to 179 degrees, which is equivalent
+/// to rotating the ground acceleration as the system is linear.
+pub fn response_spectra(
+    record: &Record,
+    damping: &[f64],
+    periods: &[f64],
+) -> Result<Vec<SpectrumPoint>, DataProcessingErr> {
+    let record = acceleration_in_gal(record, "response spectrum")?;
+    let delta = record.delta();
+    let components = record.components();
+    let mut points = Vec::with_capacity(damping.len() * periods.len());
+
+    for &h in damping {
+        for &period in periods {
+            let [ns, ew, ud] = [components.ns(), components.ew(), components.ud()]
+                .map(|values| sdof_response(values, delta, period, h));
+            let (rotd50, rotd100) = rotd(&ns, &ew);
+
+            points.push(SpectrumPoint {
+                damping: h,
+                period,
+                ns: SpectralValues::new(&ns),
+                ew: SpectralValues::new(&ew),
+                ud: SpectralValues::new(&ud),
+                rotd50,
+                rotd100,
+            });
+        }
+    }
+
+    Ok(points)
+}
+
+// 各回転角での最大応答の中央値と最大値
+fn rotd(ns: &SdofResponse, ew: &SdofResponse) -> (SpectralValues, SpectralValues) {
+    let sorted_peaks = |x: &[f64], y: &[f64]| -> Vec<f64> {
+        rotated_peaks(x, y)
+            .into_iter()
+            .map(|(_, peak)| peak)
+            .collect()
+    };
+
+    let sa = sorted_peaks(&ns.acc, &ew.acc);
+    let sv = sorted_peaks(&ns.vel, &ew.vel);
+    let sd = sorted_peaks(&ns.disp, &ew.disp);
+
+    (
+        SpectralValues {
+            sa: median(&sa),
+            sv: median(&sv),
+            sd: median(&sd),
+        },
+        SpectralValues {
+            sa: sa[sa.len() - 1],
+            sv: sv[sv.len() - 1],
+            sd: sd[sd.len() - 1],
+        },
+    )
+}
+
+/// Formats the spectra as CSV, with one row per damping ratio, period and component.
+///
+/// ```text
+/// damping,period_sec,component,sa_gal,sv_cm_s,sd_cm
+/// 0.05,0.1000,ns,512.345678,8.123456,0.129876
+/// ```
+pub fn format_response_spectra_csv(points: &[SpectrumPoint]) -> String {
+    let mut csv = "damping,period_sec,component,sa_gal,sv_cm_s,sd_cm\n".to_string();
+
+    for point in points {
+        for (component, values) in [
+            ("ns", &point.ns),
+            ("ew", &point.ew),
+            ("ud", &point.ud),
+            ("rotd50", &point.rotd50),
+            ("rotd100", &point.rotd100),
+        ] {
+            csv.push_str(&format!(
+                "{},{:.4},{},{:.6},{:.6},{:.6}\n",
+                point.damping, point.period, component, values.sa, values.sv, values.sd
+            ));
+        }
+    }
+
+    csv
+}
+
+/// Writes the spectra next to the converted file, e.g. `*-knet.spectrum.csv` for `*-knet.csv`, and returns its path.
+///
+/// The path is added to `written`, and is an error if already written in the run.
+pub fn write_response_spectra(
+    points: &[SpectrumPoint],
+    converted_path: &Path,
+    written: &mut HashSet<PathBuf>,
+) -> Result<PathBuf, DataConversionErr> {
+    let output_path = sibling_path(converted_path, "spectrum", "csv");
+    if !written.insert(output_path.clone()) {
+        return Err(DataConversionErr::DuplicateOutput(output_path));
+    }
+
+    std::fs::write(&output_path, format_response_spectra_csv(points))
+        .map_err(|e| DataConversionErr::Io(output_path.clone(), e.into()))?;
+
+    Ok(output_path)
+}
+
+/// This module contains unit tests for the elastic response spectra.
+///
+/// # Test Categories
+///
+/// - `test_sdof_response_static`: Tests the static displacement under a constant acceleration
+/// - `test_response_spectra_resonance`: Tests the resonant amplification of a sine wave by `1 / 2h`
+/// - `test_response_spectra_short_period`: Tests that Sa of a very short period equals PGA
+/// - `test_rotd`: Tests RotD50/RotD100 of a record whose horizontal motion is polarized at 45 degrees
+/// - `test_response_spectra_not_acceleration`: Tests rejection of a velocity record
+/// - `test_write_response_spectra`: Tests the path of the spectra and rejection of a second write
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::record::{test_record, Unit};
+    use std::f64::consts::PI;
+    use tempfile::tempdir;
+
+    fn record(ns: Vec<f64>, ew: Vec<f64>) -> Record {
+        let len = ns.len();
+        test_record(ns, ew, vec![0.0; len])
+    }
+
+    fn sine(amplitude: f64, period: f64, len: usize) -> Vec<f64> {
+        (0..len)
+            .map(|i| amplitude * (2.0 * PI * i as f64 * 0.01 / period).sin())
+            .collect()
+    }
+
+    #[test]
+    fn test_sdof_response_static() {
+        let period = 0.5;
+        let response = sdof_response(&vec![100.0; 3000], 0.01, period, 0.05);
+        let w = 2.0 * PI / period;
+
+        // 十分に減衰した後は x = -a / ω² に収束する
+        assert!((response.disp[2999] + 100.0 / (w * w)).abs() < 1e-6);
+        assert!(response.vel[2999].abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_response_spectra_resonance() {
+        let points = response_spectra(
+            &record(sine(10.0, 1.0, 6000), vec![0.0; 6000]),
+            &[0.05],
+            &[1.0],
+        )
+        .unwrap();
+
+        // 共振時の定常応答は入力の 1 / 2h 倍となる
+        assert!((points[0].ns.sa / 10.0 - 10.0).abs() < 0.2);
+        let w = 2.0 * PI;
+        assert!((points[0].ns.sd - points[0].ns.sa / (w * w)).abs() / points[0].ns.sd < 0.01);
+    }
+
+    #[test]
+    fn test_response_spectra_short_period() {
+        let points = response_spectra(
+            &record(sine(50.0, 1.0, 1000), vec![0.0; 1000]),
+            &[0.05],
+            &[0.02],
+        )
+        .unwrap();
+
+        assert!((points[0].ns.sa - 50.0).abs() / 50.0 < 0.01);
+    }
+
+    #[test]
+    fn test_rotd() {
+        let values = sine(10.0, 0.5, 1000);
+        let points = response_spectra(&record(values.clone(), values), &[0.05], &[0.5]).unwrap();
+        let point = points[0];
+
+        // 45度方向に偏った水平動では、RotD100は各成分の√2倍となる
+        assert!((point.rotd100.sa - point.ns.sa * 2.0_f64.sqrt()).abs() / point.rotd100.sa < 1e-3);
+        assert!(point.rotd50.sa < point.rotd100.sa);
+        assert!(point.rotd50.sa > point.ns.sa * 0.5);
+    }
+
+    #[test]
+    fn test_response_spectra_not_acceleration() {
+        let mut data = record(sine(10.0, 0.5, 1000), vec![0.0; 1000]);
+        data.unit = Unit::MetersPerSecond;
+
+        assert!(matches!(
+            response_spectra(&data, &[0.05], &[0.5]),
+            Err(DataProcessingErr::NotAcceleration(_, quantity, _)) if quantity == "velocity"
+        ));
+    }
+
+    #[test]
+    fn test_write_response_spectra() {
+        let dir = tempdir().unwrap();
+        let converted_path = dir.path().join("20240101-161009-ISK005-knet.csv");
+        let points = response_spectra(
+            &record(sine(10.0, 0.5, 1000), vec![0.0; 1000]),
+            &[0.05],
+            &[0.5],
+        )
+        .unwrap();
+        let mut written = HashSet::new();
+
+        let path = write_response_spectra(&points, &converted_path, &mut written).unwrap();
+        assert_eq!(
+            path,
+            dir.path().join("20240101-161009-ISK005-knet.spectrum.csv")
+        );
+        assert!(path.exists());
+
+        // 別のtoで同じ記録を変換した`*-knet.txt`も同じスペクトルを書き込む
+        let other_path = dir.path().join("20240101-161009-ISK005-knet.txt");
+        assert!(matches!(
+            write_response_spectra(&points, &other_path, &mut written),
+            Err(DataConversionErr::DuplicateOutput(p)) if p == path
+        ));
+    }
+}
@@ -0,0 +1,116 @@
+use crate::analysis_config_file::RotD;
+
+// RotD50, RotD100を求める際の回転角の刻み(度)
+const ROTATION_STEP_DEG: usize = 1;
+
+/// Rotates the horizontal components by `azimuth` degrees clockwise.
+///
+/// Returns the component at `azimuth` from NS and the component 90 degrees clockwise from it, which are NS and EW
+/// again if `azimuth` is 0.
+pub fn rotate(ns: &[f64], ew: &[f64], azimuth: f64) -> (Vec<f64>, Vec<f64>) {
+    let (sin, cos) = azimuth.to_radians().sin_cos();
+
+    ns.iter()
+        .zip(ew)
+        .map(|(n, e)| (n * cos + e * sin, -n * sin + e * cos))
+        .unzip()
+}
+
+/// Returns the azimuth in degrees from NS, between 0 and 179, at which the peak of the rotated component is RotD50
+/// or RotD100.
+///
+/// As the number of angles is even, the azimuth of RotD50 is that of the lower of the two middle peaks.
+pub fn rotd_azimuth(ns: &[f64], ew: &[f64], rotd: RotD) -> f64 {
+    let peaks = rotated_peaks(ns, ew);
+    let index = match rotd {
+        RotD::RotD50 => (peaks.len() - 1) / 2,
+        RotD::RotD100 => peaks.len() - 1,
+    };
+
+    peaks[index].0
+}
+
+/// Returns the peak absolute value of the component rotated to each angle from 0 to 179 degrees with the angle,
+/// sorted in ascending order of the peaks.
+pub(crate) fn rotated_peaks(ns: &[f64], ew: &[f64]) -> Vec<(f64, f64)> {
+    let mut peaks: Vec<(f64, f64)> = (0..180)
+        .step_by(ROTATION_STEP_DEG)
+        .map(|deg| {
+            let (sin, cos) = (deg as f64).to_radians().sin_cos();
+            let peak = ns
+                .iter()
+                .zip(ew)
+                .fold(0.0_f64, |max, (n, e)| max.max((n * cos + e * sin).abs()));
+            (deg as f64, peak)
+        })
+        .collect();
+    peaks.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+    peaks
+}
+
+/// Returns the median of values sorted in ascending order, the mean of the two middle values if the number is even.
+pub(crate) fn median(sorted: &[f64]) -> f64 {
+    let n = sorted.len();
+    if n % 2 == 0 {
+        (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0
+    } else {
+        sorted[n / 2]
+    }
+}
+
+/// This module contains unit tests for the rotation of the horizontal components.
+///
+/// # Test Categories
+///
+/// - `test_rotate`: Tests rotation by 0, 90 and 30 degrees and that the horizontal amplitude is kept
+/// - `test_rotd_azimuth`: Tests the azimuths of RotD50 and RotD100 of a motion polarized at 30 degrees
+/// - `test_median`: Tests the median of odd and even numbers of values
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotate() {
+        let (ns, ew) = (vec![1.0, 0.0, 3.0], vec![0.0, 2.0, 4.0]);
+
+        assert_eq!(rotate(&ns, &ew, 0.0), (ns.clone(), ew.clone()));
+
+        // 90度回転すると、新しいNSは元のEW、新しいEWは元のNSの逆向きとなる
+        let (x, y) = rotate(&ns, &ew, 90.0);
+        for i in 0..ns.len() {
+            assert!((x[i] - ew[i]).abs() < 1e-12);
+            assert!((y[i] + ns[i]).abs() < 1e-12);
+        }
+
+        let (x, y) = rotate(&[1.0], &[0.0], 30.0);
+        assert!((x[0] - 30_f64.to_radians().cos()).abs() < 1e-12);
+        assert!((y[0] + 0.5).abs() < 1e-12);
+        let (x, y) = rotate(&ns, &ew, 30.0);
+        assert!((x[2].hypot(y[2]) - 5.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_rotd_azimuth() {
+        // N30°E方向に偏った水平動
+        let (sin, cos) = 30_f64.to_radians().sin_cos();
+        let motion: Vec<f64> = (0..100).map(|i| (i as f64 * 0.1).sin()).collect();
+        let ns: Vec<f64> = motion.iter().map(|m| m * cos).collect();
+        let ew: Vec<f64> = motion.iter().map(|m| m * sin).collect();
+
+        assert_eq!(rotd_azimuth(&ns, &ew, RotD::RotD100), 30.0);
+        // 最大値の中央値は偏りの方向から45度離れた方向で生じる
+        let offset = (rotd_azimuth(&ns, &ew, RotD::RotD50) - 30.0).rem_euclid(90.0);
+        assert!((offset - 45.0).abs() <= 1.0);
+
+        let peaks = rotated_peaks(&ns, &ew);
+        assert_eq!(peaks.len(), 180);
+        assert!(peaks.windows(2).all(|w| w[0].1 <= w[1].1));
+    }
+
+    #[test]
+    fn test_median() {
+        assert_eq!(median(&[1.0, 2.0, 5.0]), 2.0);
+        assert_eq!(median(&[1.0, 2.0, 4.0, 5.0]), 3.0);
+    }
+}
@@ -1,36 +1,72 @@
 use std::{
-    collections::HashSet,
+    collections::{BTreeMap, HashSet},
     fmt::Write,
     path::{Path, PathBuf},
 };
 
 use serde::{Deserialize, Serialize};
 
-use crate::error::{AnalysisConfigErr, AppError, ConfigValidationErr, IoErrWrapper};
+use crate::{
+    byte_order::Endian,
+    error::{AnalysisConfigErr, AppError, ConfigValidationErr, IoErrWrapper},
+    record::{Quantity, Unit},
+};
 
-const MULTIPLE_AXIS_TYPE: [&From; 2] = [&From::JpNiedKnet, &From::TkAfadAsc];
+const MULTIPLE_AXIS_TYPE: [&From; 4] = [
+    &From::JpNiedKnet,
+    &From::JpNiedKik,
+    &From::TkAfadAsc,
+    &From::PeerAt2,
+];
 
 /// File format before conversion.  
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
 #[serde(rename_all = "snake_case")]
 pub enum From {
     JpNiedKnet,
+    /// KiK-net, which records the borehole(`*.NS1`, `*.EW1`, `*.UD1`) and surface(`*.NS2`, `*.EW2`, `*.UD2`) sensors.
+    JpNiedKik,
     UsScsnV2,
     NzGeonetV1a,
     NzGeonetV2a,
     TwPalertSac,
     TkAfadAsc,
+    /// COSMOS strong-motion format(Volume 0, 1 and 2) distributed by CESMD.
+    Cosmos,
+    /// PEER NGA AT2, which has one component per file.
+    PeerAt2,
+    /// miniSEED, whose channels are split into NS/EW/UD by the last letter of the channel code.
+    MiniSeed,
 }
 
 impl From {
     fn to_snake_case(&self) -> &str {
         match self {
             From::JpNiedKnet => "jp_nied_knet",
+            From::JpNiedKik => "jp_nied_kik",
             From::UsScsnV2 => "us_scsn_v2",
             From::NzGeonetV1a => "nz_geonet_v1a",
             From::NzGeonetV2a => "nz_geonet_v2a",
             From::TwPalertSac => "tw_palert_sac",
             From::TkAfadAsc => "tk_afad_asc",
+            From::Cosmos => "cosmos",
+            From::PeerAt2 => "peer_at2",
+            From::MiniSeed => "mini_seed",
+        }
+    }
+
+    /// Institution name used for the `n` of `NameFormat`.
+    pub fn institution_name(&self) -> &str {
+        match self {
+            From::JpNiedKnet => "knet",
+            From::JpNiedKik => "kik",
+            From::UsScsnV2 => "scsn",
+            From::NzGeonetV1a | From::NzGeonetV2a => "geonet",
+            From::TwPalertSac => "palert",
+            From::TkAfadAsc => "afad",
+            From::Cosmos => "cosmos",
+            From::PeerAt2 => "peer",
+            From::MiniSeed => "mseed",
         }
     }
 }
@@ -41,6 +77,51 @@ impl From {
 pub enum To {
     JpJmaCsv,
     JpStera3dTxt,
+    /// PEER NGA AT2, written as one file per component in g.
+    PeerAt2,
+    /// miniSEED, written as one Steim2 channel per component in a single file.
+    MiniSeed,
+    /// Binary SAC, written as one file per component.
+    Sac,
+}
+
+impl To {
+    fn to_snake_case(&self) -> &str {
+        match self {
+            To::JpJmaCsv => "jp_jma_csv",
+            To::JpStera3dTxt => "jp_stera3d_txt",
+            To::PeerAt2 => "peer_at2",
+            To::MiniSeed => "mini_seed",
+            To::Sac => "sac",
+        }
+    }
+
+    /// Returns the unit defined by the file format, which has no field for the unit.
+    pub fn fixed_unit(&self) -> Option<Unit> {
+        match self {
+            To::JpJmaCsv => None,
+            To::JpStera3dTxt => Some(Unit::Gal),
+            To::PeerAt2 => Some(Unit::G),
+            To::MiniSeed | To::Sac => None,
+        }
+    }
+}
+
+/// File format of the peak ground motion summary written next to each converted file.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum SummaryFormat {
+    Json,
+    Csv,
+}
+
+impl SummaryFormat {
+    pub fn extension(&self) -> &str {
+        match self {
+            SummaryFormat::Json => "json",
+            SummaryFormat::Csv => "csv",
+        }
+    }
 }
 
 /// File format before conversion.
@@ -53,7 +134,7 @@ pub enum AccAxis {
 }
 
 impl AccAxis {
-    fn as_str(&self) -> &str {
+    pub fn as_str(&self) -> &str {
         match self {
             AccAxis::Ns => "ns",
             AccAxis::Ew => "ew",
@@ -62,6 +143,24 @@ impl AccAxis {
     }
 }
 
+/// Position of the sensor of KiK-net, which is indicated by the number at the end of the extension.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
+pub enum SensorPosition {
+    /// `*.NS1`, `*.EW1`, `*.UD1`
+    Borehole,
+    /// `*.NS2`, `*.EW2`, `*.UD2`
+    Surface,
+}
+
+impl SensorPosition {
+    pub fn as_str(&self) -> &str {
+        match self {
+            SensorPosition::Borehole => "borehole",
+            SensorPosition::Surface => "surface",
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "kebab-case")]
 pub enum NameFormat {
@@ -73,7 +172,7 @@ pub enum NameFormat {
     YyyymmddHhmmssSnN,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub struct Config {
     pub global: GlobalConfig,
     pub conversion: Vec<ConversionConfig>,
@@ -124,18 +223,175 @@ pub struct GlobalConfig {
     pub name_format: NameFormat,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 pub struct ConversionConfig {
     pub name: String,
     pub from: From,
     pub to: To,
+    /// Writes the PGA, PGV and PGD of each record next to the converted file if set.
+    #[serde(default)]
+    pub peak_summary: Option<SummaryFormat>,
+    /// Writes the elastic response spectra of each record next to the converted file if set.
+    #[serde(default)]
+    pub response_spectrum: Option<ResponseSpectrumConfig>,
+    /// Writes the Fourier amplitude spectra and the H/V ratio of each record next to the converted file if set.
+    #[serde(default)]
+    pub fourier_spectrum: Option<FourierSpectrumConfig>,
+    /// Writes the Arias intensity, significant durations, CAV and other intensity measures of each record next to the
+    /// converted file if set.
+    #[serde(default)]
+    pub intensity_measures: Option<SummaryFormat>,
+    /// Steps applied to the acceleration in order before writing. The steps, and the integration if any, are written
+    /// to `*.processing.json` next to the converted file.
+    #[serde(default)]
+    pub processing: Vec<ProcessingStep>,
+    /// Resamples each record to this sampling rate(Hz) after the processing steps if set, which cannot be set
+    /// together with a `resample` step in `processing`.
+    #[serde(default)]
+    pub resample_hz: Option<f32>,
+    /// Quantity written to the converted files, which is integrated from the acceleration if not acceleration.
+    #[serde(default)]
+    pub output_quantity: Quantity,
+    /// Integration of the acceleration used when `output_quantity` is velocity or displacement.
+    #[serde(default)]
+    pub integration: IntegrationConfig,
+    /// Unit of the converted files, which is the unit defined by `to` or the unit in centimeters of
+    /// `output_quantity` by default.
+    #[serde(default)]
+    pub output_unit: Option<Unit>,
+    /// Sensitivity converting the counts of the source files, which is required by `from = "mini_seed"` and
+    /// optional for `from = "tw_palert_sac"`.
+    #[serde(default)]
+    pub sensitivity: Option<SensitivityConfig>,
+    /// Codes and record length of the files written by `to = "mini_seed"`, the defaults of `MiniSeedConfig` if
+    /// omitted.
+    #[serde(default)]
+    pub mini_seed: Option<MiniSeedConfig>,
+    /// Byte order and channel codes of the files written by `to = "sac"`, the defaults of `SacConfig` if omitted.
+    #[serde(default)]
+    pub sac: Option<SacConfig>,
     pub group: Vec<GroupConfig>,
 }
 
 impl ConversionConfig {
+    /// Returns the unit of the converted files.
+    pub fn output_unit(&self) -> Unit {
+        self.output_unit
+            .or(self
+                .to
+                .fixed_unit()
+                .filter(|unit| unit.quantity() == self.output_quantity))
+            .unwrap_or(self.output_quantity.default_unit())
+    }
+
+    /// Returns the processing steps followed by the resampling to `resample_hz`.
+    pub fn processing_steps(&self) -> Vec<ProcessingStep> {
+        let mut steps = self.processing.clone();
+        if let Some(hz) = self.resample_hz {
+            steps.push(ProcessingStep::Resample { hz });
+        }
+        steps
+    }
+
     pub fn validate(&self) -> Result<(), Vec<AnalysisConfigErr>> {
         let mut errors: Vec<AnalysisConfigErr> = Vec::new();
 
+        if let Some(response_spectrum) = &self.response_spectrum {
+            let _ = response_spectrum.validate(&self.name).map_err(|e| {
+                errors.push(e.into());
+            });
+        }
+
+        if let Some(fourier_spectrum) = &self.fourier_spectrum {
+            let _ = fourier_spectrum.validate(&self.name).map_err(|e| {
+                errors.push(e.into());
+            });
+        }
+
+        for step in &self.processing_steps() {
+            let _ = step.validate(&self.name).map_err(|e| {
+                errors.push(e.into());
+            });
+        }
+
+        // 両方を設定すると、書かれた順序と異なる位置で2回リサンプリングされるため認めない
+        if self.resample_hz.is_some()
+            && self
+                .processing
+                .iter()
+                .any(|step| matches!(step, ProcessingStep::Resample { .. }))
+        {
+            errors.push(
+                ConfigValidationErr::InvalidProcessingStep(
+                    "resample_hz cannot be set together with a resample step".to_string(),
+                    self.name.clone(),
+                )
+                .into(),
+            );
+        }
+
+        let _ = self.integration.validate(&self.name).map_err(|e| {
+            errors.push(e.into());
+        });
+
+        match &self.sensitivity {
+            Some(_) if !matches!(self.from, From::MiniSeed | From::TwPalertSac) => errors.push(
+                ConfigValidationErr::InvalidSensitivity(
+                    format!("'{}' does not use sensitivity", self.from.to_snake_case()),
+                    self.name.clone(),
+                )
+                .into(),
+            ),
+            Some(sensitivity) => {
+                let _ = sensitivity.validate(&self.name).map_err(|e| {
+                    errors.push(e.into());
+                });
+            }
+            None if self.from == From::MiniSeed => errors.push(
+                ConfigValidationErr::InvalidSensitivity(
+                    format!("'{}' requires sensitivity", self.from.to_snake_case()),
+                    self.name.clone(),
+                )
+                .into(),
+            ),
+            None => {}
+        }
+
+        let output_unit = self.output_unit();
+        if output_unit.quantity() != self.output_quantity {
+            errors.push(
+                ConfigValidationErr::MismatchedOutputUnit(
+                    output_unit.as_str().to_string(),
+                    self.output_quantity.as_str().to_string(),
+                    self.name.clone(),
+                )
+                .into(),
+            );
+        } else if let Some(fixed_unit) = self.to.fixed_unit() {
+            if output_unit != fixed_unit {
+                errors.push(
+                    ConfigValidationErr::UnsupportedOutputUnit(
+                        output_unit.as_str().to_string(),
+                        self.to.to_snake_case().to_string(),
+                        self.name.clone(),
+                    )
+                    .into(),
+                );
+            }
+        }
+
+        if let Some(mini_seed) = &self.mini_seed {
+            let _ = mini_seed.validate(&self.name).map_err(|e| {
+                errors.push(e.into());
+            });
+        }
+
+        if let Some(sac) = &self.sac {
+            let _ = sac.validate(&self.name).map_err(|e| {
+                errors.push(e.into());
+            });
+        }
+
         for (g_index, group_config) in self.group.iter().enumerate() {
             let id: usize = g_index + 1;
             let acceptable_exts: &[&str] = Self::assign_ext_based_on_from(&self.from);
@@ -157,15 +413,558 @@ impl ConversionConfig {
     fn assign_ext_based_on_from(from: &From) -> &[&str] {
         match from {
             From::JpNiedKnet => &["ns", "ew", "ud"],
+            From::JpNiedKik => &["ns1", "ew1", "ud1", "ns2", "ew2", "ud2"],
             From::UsScsnV2 => &["v2"],
             From::NzGeonetV1a => &["v1a"],
             From::NzGeonetV2a => &["v2a"],
             From::TwPalertSac => &["sac"],
             From::TkAfadAsc => &["asc"],
+            From::Cosmos => &["v0", "v1", "v2", "smc"],
+            From::PeerAt2 => &["at2"],
+            From::MiniSeed => &["mseed", "miniseed", "msd"],
+        }
+    }
+}
+
+#[cfg(test)]
+impl ConversionConfig {
+    /// Creates a conversion of a single group with the default settings, for the unit tests of the extractors.
+    pub(crate) fn for_test(name: &str, from: From, to: To, files: Vec<FileConfig>) -> Self {
+        let mut conversion: ConversionConfig = toml::from_str(&format!(
+            "name = \"{name}\"\nfrom = \"{}\"\nto = \"{}\"\ngroup = []",
+            from.to_snake_case(),
+            to.to_snake_case()
+        ))
+        .unwrap();
+        conversion.group.push(GroupConfig { files });
+        conversion
+    }
+}
+
+/// Settings of the elastic response spectra.
+///
+/// ```toml
+/// [conversion.response_spectrum]
+/// damping = [0.02, 0.05, 0.10]
+/// periods = [0.1, 0.2, 0.5, 1.0, 2.0, 5.0]
+/// ```
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct ResponseSpectrumConfig {
+    /// Damping ratios, 5% if omitted.
+    #[serde(default = "default_damping")]
+    pub damping: Vec<f64>,
+    /// Natural periods in seconds, 100 periods spaced logarithmically from 0.02 to 10 seconds if omitted.
+    #[serde(default = "default_periods")]
+    pub periods: Vec<f64>,
+}
+
+impl ResponseSpectrumConfig {
+    fn validate(&self, name: &str) -> Result<(), ConfigValidationErr> {
+        let invalid = |reason: &str| {
+            Err(ConfigValidationErr::InvalidResponseSpectrum(
+                reason.to_string(),
+                name.to_string(),
+            ))
+        };
+
+        if self.damping.is_empty() || self.periods.is_empty() {
+            return invalid("damping and periods must not be empty");
+        }
+        if self.damping.iter().any(|&h| !(0.0..1.0).contains(&h)) {
+            return invalid("damping must be 0 or more and less than 1");
+        }
+        if self.periods.iter().any(|&t| t <= 0.0 || !t.is_finite()) {
+            return invalid("periods must be positive");
+        }
+
+        Ok(())
+    }
+}
+
+fn default_damping() -> Vec<f64> {
+    vec![0.05]
+}
+
+fn default_periods() -> Vec<f64> {
+    let (min, max, count) = (0.02_f64, 10.0_f64, 100);
+    (0..count)
+        .map(|i| min * (max / min).powf(i as f64 / (count - 1) as f64))
+        .collect()
+}
+
+/// Settings of the Fourier amplitude spectra.
+///
+/// ```toml
+/// [conversion.fourier_spectrum]
+/// window = "hann"
+/// smoothing = { konno_ohmachi = 40.0 }
+/// min_hz = 0.2
+/// max_hz = 20.0
+/// ```
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy)]
+pub struct FourierSpectrumConfig {
+    /// Window applied to each component before FFT, a cosine taper of 5% at each end if omitted.
+    #[serde(default)]
+    pub window: SpectrumWindow,
+    /// Smoothing of the amplitude spectra, not smoothed if omitted.
+    #[serde(default)]
+    pub smoothing: Smoothing,
+    /// Lowest frequency written, 0.1 Hz if omitted.
+    #[serde(default = "default_min_hz")]
+    pub min_hz: f64,
+    /// Highest frequency written, 25 Hz or the Nyquist frequency if omitted.
+    #[serde(default = "default_max_hz")]
+    pub max_hz: f64,
+}
+
+impl FourierSpectrumConfig {
+    fn validate(&self, name: &str) -> Result<(), ConfigValidationErr> {
+        let invalid = |reason: &str| {
+            Err(ConfigValidationErr::InvalidFourierSpectrum(
+                reason.to_string(),
+                name.to_string(),
+            ))
+        };
+
+        if !(self.min_hz > 0.0 && self.min_hz < self.max_hz) {
+            return invalid("frequencies must be 0 < min_hz < max_hz");
+        }
+        if let SpectrumWindow::CosineTaper(fraction) = self.window {
+            if !(fraction > 0.0 && fraction <= 0.5) {
+                return invalid("cosine_taper must be more than 0 and 0.5 or less");
+            }
+        }
+        match self.smoothing {
+            Smoothing::Parzen(width) | Smoothing::KonnoOhmachi(width)
+                if width <= 0.0 || !width.is_finite() =>
+            {
+                return invalid("smoothing width must be positive");
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
+
+fn default_min_hz() -> f64 {
+    0.1
+}
+
+fn default_max_hz() -> f64 {
+    25.0
+}
+
+/// Window applied to a waveform before FFT.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum SpectrumWindow {
+    Rectangular,
+    Hann,
+    /// Cosine taper of the fraction of the samples at each end.
+    CosineTaper(f64),
+}
+
+impl Default for SpectrumWindow {
+    fn default() -> Self {
+        SpectrumWindow::CosineTaper(0.05)
+    }
+}
+
+/// Smoothing of an amplitude spectrum.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Smoothing {
+    #[default]
+    None,
+    /// Parzen window of the bandwidth in Hz.
+    Parzen(f64),
+    /// Konno–Ohmachi window of the coefficient `b`, typically 40.
+    KonnoOhmachi(f64),
+}
+
+/// Step of the processing chain applied to the acceleration.
+///
+/// ```toml
+/// [[conversion.processing]]
+/// step = "detrend"
+/// method = "linear"
+///
+/// [[conversion.processing]]
+/// step = "band_pass"
+/// low_hz = 0.1
+/// high_hz = 20.0
+/// order = 4
+/// causal = false
+///
+/// [[conversion.processing]]
+/// step = "resample"
+/// hz = 100.0
+///
+/// [[conversion.processing]]
+/// step = "rotate"
+/// azimuth = "rotd100"
+/// ```
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(tag = "step", rename_all = "snake_case")]
+pub enum ProcessingStep {
+    /// Removes the mean or the least-squares line.
+    Detrend { method: DetrendMethod },
+    /// Removes the least-squares polynomial of `order`.
+    PolynomialBaseline { order: usize },
+    /// Applies a cosine taper to `fraction` of the samples at each end.
+    Taper { fraction: f64 },
+    /// Applies a Butterworth band-pass filter, which is zero-phase(forward and backward) unless `causal` is set.
+    BandPass {
+        low_hz: f64,
+        high_hz: f64,
+        order: usize,
+        #[serde(default)]
+        causal: bool,
+    },
+    /// Resamples to `hz` with a band-limited interpolation, which also works as an anti-aliasing filter when
+    /// decimating.
+    Resample { hz: f32 },
+    /// Rotates the horizontal components so that NS holds the component at `azimuth` and EW the one 90 degrees
+    /// clockwise from it.
+    ///
+    /// The azimuth of RotD50 or RotD100 is recorded in the processing history as the degrees found.
+    Rotate { azimuth: Azimuth },
+}
+
+impl ProcessingStep {
+    fn validate(&self, name: &str) -> Result<(), ConfigValidationErr> {
+        let invalid = |reason: &str| {
+            Err(ConfigValidationErr::InvalidProcessingStep(
+                reason.to_string(),
+                name.to_string(),
+            ))
+        };
+
+        match self {
+            ProcessingStep::Detrend { .. } => {}
+            ProcessingStep::PolynomialBaseline { order } => {
+                if *order > MAX_POLYNOMIAL_ORDER {
+                    return invalid(&format!(
+                        "polynomial_baseline order must be {} or less",
+                        MAX_POLYNOMIAL_ORDER
+                    ));
+                }
+            }
+            ProcessingStep::Taper { fraction } => {
+                if !(*fraction > 0.0 && *fraction <= 0.5) {
+                    return invalid("taper fraction must be more than 0 and 0.5 or less");
+                }
+            }
+            ProcessingStep::BandPass {
+                low_hz,
+                high_hz,
+                order,
+                ..
+            } => {
+                if !(*low_hz > 0.0 && low_hz < high_hz) {
+                    return invalid("band_pass corners must be 0 < low_hz < high_hz");
+                }
+                if *order == 0 {
+                    return invalid("band_pass order must be 1 or more");
+                }
+            }
+            ProcessingStep::Resample { hz } => {
+                if !(*hz > 0.0 && hz.is_finite()) {
+                    return invalid("resample hz must be more than 0");
+                }
+            }
+            ProcessingStep::Rotate {
+                azimuth: Azimuth::Degrees(degrees),
+            } => {
+                if !degrees.is_finite() {
+                    return invalid("rotate azimuth must be a finite number of degrees");
+                }
+            }
+            ProcessingStep::Rotate { .. } => {}
+        }
+
+        Ok(())
+    }
+}
+
+/// Sensitivity of the recorder, which converts the counts into `unit` by dividing them by `counts_per_unit`.
+///
+/// ```toml
+/// [conversion.sensitivity]
+/// counts_per_unit = 419430.0
+/// unit = "m/s2"
+/// ```
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy)]
+pub struct SensitivityConfig {
+    pub counts_per_unit: f64,
+    /// Unit of acceleration that `counts_per_unit` refers to.
+    #[serde(default)]
+    pub unit: Unit,
+}
+
+impl SensitivityConfig {
+    /// Returns the factor converting the counts to gal, or `None` if `unit` is not a unit of acceleration.
+    pub fn gal_per_count(&self) -> Option<f64> {
+        Some(self.unit.factor_to(Unit::Gal)? / self.counts_per_unit)
+    }
+
+    fn validate(&self, name: &str) -> Result<(), ConfigValidationErr> {
+        let invalid = |reason: &str| {
+            Err(ConfigValidationErr::InvalidSensitivity(
+                reason.to_string(),
+                name.to_string(),
+            ))
+        };
+
+        if !(self.counts_per_unit > 0.0 && self.counts_per_unit.is_finite()) {
+            return invalid("counts_per_unit must be more than 0");
+        }
+        if self.unit.quantity() != Quantity::Acceleration {
+            return invalid("unit must be a unit of acceleration");
+        }
+
+        Ok(())
+    }
+}
+
+/// Codes and encoding of the miniSEED files written by `to = "mini_seed"`.
+///
+/// The samples are multiplied by `counts_per_unit` and rounded to integers, as Steim2 compresses integers.
+///
+/// ```toml
+/// [conversion.mini_seed]
+/// network = "JP"
+/// station = "ISK05"
+/// location = "00"
+/// channels = { ns = "HNN", ew = "HNE", ud = "HNZ" }
+/// record_length = 512
+/// counts_per_unit = 1000.0
+/// ```
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct MiniSeedConfig {
+    /// Network code of 1 or 2 characters, `XX` if omitted.
+    #[serde(default = "default_network")]
+    pub network: String,
+    /// Station code of 1 to 5 characters, the site code of the record if omitted.
+    #[serde(default)]
+    pub station: Option<String>,
+    /// Location code of up to 2 characters, empty if omitted.
+    #[serde(default)]
+    pub location: String,
+    #[serde(default)]
+    pub channels: ChannelCodes,
+    /// Length of each record in bytes, a power of 2 from 256 to 65536, 512 if omitted.
+    #[serde(default = "default_record_length")]
+    pub record_length: usize,
+    /// Counts per unit of the converted record, 1000 if omitted.
+    #[serde(default = "default_counts_per_unit")]
+    pub counts_per_unit: f64,
+}
+
+impl Default for MiniSeedConfig {
+    fn default() -> Self {
+        Self {
+            network: default_network(),
+            station: None,
+            location: String::new(),
+            channels: ChannelCodes::default(),
+            record_length: default_record_length(),
+            counts_per_unit: default_counts_per_unit(),
         }
     }
 }
 
+impl MiniSeedConfig {
+    fn validate(&self, name: &str) -> Result<(), ConfigValidationErr> {
+        let invalid = |reason: &str| {
+            Err(ConfigValidationErr::InvalidMiniSeed(
+                reason.to_string(),
+                name.to_string(),
+            ))
+        };
+        // SEEDのコードは英数字のみ
+        let is_code = |code: &str, lengths: std::ops::RangeInclusive<usize>| {
+            lengths.contains(&code.len()) && code.chars().all(|c| c.is_ascii_alphanumeric())
+        };
+
+        if !is_code(&self.network, 1..=2) {
+            return invalid("network must be 1 or 2 alphanumeric characters");
+        }
+        if !self.station.as_ref().is_none_or(|s| is_code(s, 1..=5)) {
+            return invalid("station must be 1 to 5 alphanumeric characters");
+        }
+        if !is_code(&self.location, 0..=2) {
+            return invalid("location must be up to 2 alphanumeric characters");
+        }
+        let ChannelCodes { ns, ew, ud } = &self.channels;
+        if ![ns, ew, ud].iter().all(|c| is_code(c, 3..=3)) {
+            return invalid("channels must be 3 alphanumeric characters");
+        }
+        if ns == ew || ew == ud || ud == ns {
+            return invalid("channels must differ from each other");
+        }
+        if !(self.record_length.is_power_of_two() && (256..=65536).contains(&self.record_length)) {
+            return invalid("record_length must be a power of 2 from 256 to 65536");
+        }
+        if !(self.counts_per_unit > 0.0 && self.counts_per_unit.is_finite()) {
+            return invalid("counts_per_unit must be more than 0");
+        }
+
+        Ok(())
+    }
+}
+
+/// Byte order and channel codes of the SAC files written by `to = "sac"`.
+///
+/// ```toml
+/// [conversion.sac]
+/// endian = "big"
+/// channels = { ns = "HNN", ew = "HNE", ud = "HNZ" }
+/// ```
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Default)]
+pub struct SacConfig {
+    /// Byte order, little endian if omitted.
+    #[serde(default)]
+    pub endian: Endian,
+    /// Channel codes written to KCMPNM.
+    #[serde(default)]
+    pub channels: ChannelCodes,
+}
+
+impl SacConfig {
+    fn validate(&self, name: &str) -> Result<(), ConfigValidationErr> {
+        let ChannelCodes { ns, ew, ud } = &self.channels;
+
+        // KCMPNMは8文字
+        if ![ns, ew, ud]
+            .iter()
+            .all(|c| (1..=8).contains(&c.len()) && c.is_ascii())
+        {
+            return Err(ConfigValidationErr::InvalidSac(
+                "channels must be 1 to 8 ASCII characters".to_string(),
+                name.to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Channel codes of the components, `HNN`, `HNE` and `HNZ` if omitted.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct ChannelCodes {
+    pub ns: String,
+    pub ew: String,
+    pub ud: String,
+}
+
+impl Default for ChannelCodes {
+    fn default() -> Self {
+        Self {
+            ns: "HNN".to_string(),
+            ew: "HNE".to_string(),
+            ud: "HNZ".to_string(),
+        }
+    }
+}
+
+fn default_network() -> String {
+    "XX".to_string()
+}
+
+fn default_record_length() -> usize {
+    512
+}
+
+fn default_counts_per_unit() -> f64 {
+    1000.0
+}
+
+// 多項式の次数が大きいと正規方程式が不安定になる
+const MAX_POLYNOMIAL_ORDER: usize = 10;
+
+/// Integration of the acceleration into velocity and displacement.
+///
+/// ```toml
+/// [conversion.integration]
+/// method = "frequency_domain"
+/// baseline = { polynomial = 3 }
+/// ```
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Default)]
+pub struct IntegrationConfig {
+    #[serde(default)]
+    pub method: IntegrationMethod,
+    /// Baseline removed after each integration.
+    #[serde(default)]
+    pub baseline: Baseline,
+}
+
+impl IntegrationConfig {
+    fn validate(&self, name: &str) -> Result<(), ConfigValidationErr> {
+        if let Baseline::Polynomial(order) = self.baseline {
+            if order > MAX_POLYNOMIAL_ORDER {
+                return Err(ConfigValidationErr::InvalidIntegration(
+                    format!("baseline order must be {} or less", MAX_POLYNOMIAL_ORDER),
+                    name.to_string(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrationMethod {
+    /// Trapezoidal rule.
+    #[default]
+    TimeDomain,
+    /// Division by `i2πf` after FFT.
+    FrequencyDomain,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Baseline {
+    None,
+    /// Least-squares line.
+    #[default]
+    Linear,
+    /// Least-squares polynomial of the order.
+    Polynomial(usize),
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum DetrendMethod {
+    Mean,
+    Linear,
+}
+
+/// Azimuth to which the horizontal components are rotated, as degrees or `"rotd50"`/`"rotd100"`.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy)]
+#[serde(untagged)]
+pub enum Azimuth {
+    /// Degrees clockwise from the current NS, which is north unless the record has been rotated, e.g. the strike of
+    /// a fault plus 90 for the fault-normal component.
+    Degrees(f64),
+    /// Azimuth at which the peak acceleration of the rotated component is RotD50 or RotD100.
+    RotD(RotD),
+}
+
+/// Orientation-independent peak of the horizontal component rotated to every azimuth.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+pub enum RotD {
+    /// Median over the azimuths.
+    #[serde(rename = "rotd50")]
+    RotD50,
+    /// Maximum over the azimuths.
+    #[serde(rename = "rotd100")]
+    RotD100,
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
 pub struct GroupConfig {
     pub files: Vec<FileConfig>,
@@ -206,25 +1005,54 @@ impl GroupConfig {
 
         // 各成分が別のファイルで管理されている形式の場合はNS,EW,UDの3つが必要
         if MULTIPLE_AXIS_TYPE.contains(&from) {
-            let mut required_axis = vec!["ns", "ew", "ud"];
+            // KiK-netは地中と地表のセンサーごとに3成分が揃っている必要がある
+            let mut files_by_sensor: BTreeMap<Option<SensorPosition>, Vec<&FileConfig>> =
+                BTreeMap::new();
             for file in &self.files {
-                // acc_axisが存在するか
-                if let Some(acc_axis) = &file.acc_axis {
-                    // 一致する要素が存在するか
-                    if let Some(pos) = required_axis.iter().position(|&x| x == acc_axis.as_str()) {
-                        required_axis.remove(pos);
+                files_by_sensor
+                    .entry(file.sensor_position())
+                    .or_default()
+                    .push(file);
+            }
+
+            for (sensor, files) in files_by_sensor {
+                let mut required_axis = vec!["ns", "ew", "ud"];
+                for file in files {
+                    // acc_axisが存在するか
+                    if let Some(acc_axis) = &file.acc_axis {
+                        // 一致する要素が存在するか
+                        if let Some(pos) =
+                            required_axis.iter().position(|&x| x == acc_axis.as_str())
+                        {
+                            required_axis.remove(pos);
+                        } else {
+                            errors.push(
+                                ConfigValidationErr::DuplicateAccAxis(
+                                    from.to_snake_case().to_string(),
+                                    name.to_string(),
+                                    id,
+                                )
+                                .into(),
+                            );
+                        }
                     } else {
                         errors.push(
-                            ConfigValidationErr::DuplicateAccAxis(
-                                from.to_snake_case().to_string(),
-                                name.to_string(),
-                                id,
-                            )
-                            .into(),
+                            ConfigValidationErr::RequiredAccAxis(name.to_string(), id).into(),
                         );
                     }
-                } else {
-                    errors.push(ConfigValidationErr::RequiredAccAxis(name.to_string(), id).into());
+                }
+
+                if !required_axis.is_empty() {
+                    let missing_axis = match sensor {
+                        Some(sensor) => {
+                            format!("{}: {}", sensor.as_str(), required_axis.join(", "))
+                        }
+                        None => required_axis.join(", "),
+                    };
+                    errors.push(
+                        ConfigValidationErr::MissingAccAxis(missing_axis, name.to_string(), id)
+                            .into(),
+                    );
                 }
             }
         // 全ての成分が単一ファイル内で管理されている形式
@@ -258,6 +1086,17 @@ pub struct FileConfig {
 }
 
 impl FileConfig {
+    /// Returns the sensor position indicated by the extension, only for KiK-net files such as `*.NS1` or `*.UD2`.
+    pub fn sensor_position(&self) -> Option<SensorPosition> {
+        let extension = self.path.extension()?.to_string_lossy().to_lowercase();
+
+        match extension.as_str() {
+            "ns1" | "ew1" | "ud1" => Some(SensorPosition::Borehole),
+            "ns2" | "ew2" | "ud2" => Some(SensorPosition::Surface),
+            _ => None,
+        }
+    }
+
     pub fn validate(&self, acceptable_exts: &[&str]) -> Result<(), Vec<AnalysisConfigErr>> {
         let mut errors: Vec<AnalysisConfigErr> = Vec::new();
 
@@ -325,3 +1164,484 @@ fn hashset_to_string(set: &HashSet<String>) -> String {
     }
     result
 }
+
+/// This module contains unit tests for the validation of the analysis configuration.
+///
+/// # Test Categories
+///
+/// - `test_sensor_position`: Tests the sensor position indicated by the KiK-net extensions
+/// - `test_validate_kik_both_sensors`: Tests a group with both the borehole and surface sensors
+/// - `test_validate_kik_missing_axis`: Tests detection of a sensor whose components are incomplete
+/// - `test_validate_knet_missing_axis`: Tests detection of missing components for K-NET
+/// - `test_response_spectrum_config`: Tests the default and the validation of the response spectrum settings
+/// - `test_fourier_spectrum_config`: Tests the default and the validation of the Fourier spectrum settings
+/// - `test_processing_steps`: Tests parsing of the processing chain and rejection of invalid steps
+/// - `test_resample_hz`: Tests resampling after the processing steps and rejection of a duplicate resample step
+/// - `test_output_unit`: Tests the default output unit and rejection of a unit not supported by `to`
+/// - `test_output_quantity`: Tests the default unit of each quantity and rejection of a unit of another quantity
+/// - `test_sensitivity_config`: Tests the factor to gal and the validation of the sensitivity for each source format
+/// - `test_mini_seed_config`: Tests the default codes and record length and the validation of the miniSEED output
+/// - `test_sac_config`: Tests the default byte order and the validation of the SAC output channels
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, acc_axis: AccAxis) -> FileConfig {
+        FileConfig {
+            path: PathBuf::from(path),
+            acc_axis: Some(acc_axis),
+        }
+    }
+
+    #[test]
+    fn test_sensor_position() {
+        assert_eq!(
+            file("IBRH130103111446.NS1", AccAxis::Ns).sensor_position(),
+            Some(SensorPosition::Borehole)
+        );
+        assert_eq!(
+            file("IBRH130103111446.UD2", AccAxis::Ud).sensor_position(),
+            Some(SensorPosition::Surface)
+        );
+        assert_eq!(
+            file("ISK0052401011610.EW", AccAxis::Ew).sensor_position(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_validate_kik_both_sensors() {
+        let group = GroupConfig {
+            files: vec![
+                file("IBRH130103111446.NS1", AccAxis::Ns),
+                file("IBRH130103111446.EW1", AccAxis::Ew),
+                file("IBRH130103111446.UD1", AccAxis::Ud),
+                file("IBRH130103111446.NS2", AccAxis::Ns),
+                file("IBRH130103111446.EW2", AccAxis::Ew),
+                file("IBRH130103111446.UD2", AccAxis::Ud),
+            ],
+        };
+
+        assert!(group
+            .validate_file_by_acc_axis(&From::JpNiedKik, "tohoku", 1)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_validate_kik_missing_axis() {
+        let group = GroupConfig {
+            files: vec![
+                file("IBRH130103111446.NS1", AccAxis::Ns),
+                file("IBRH130103111446.EW1", AccAxis::Ew),
+                file("IBRH130103111446.UD1", AccAxis::Ud),
+                file("IBRH130103111446.NS2", AccAxis::Ns),
+            ],
+        };
+
+        let errors = group
+            .validate_file_by_acc_axis(&From::JpNiedKik, "tohoku", 1)
+            .unwrap_err();
+
+        assert_eq!(
+            errors,
+            vec![AnalysisConfigErr::Validation(
+                ConfigValidationErr::MissingAccAxis(
+                    "surface: ew, ud".to_string(),
+                    "tohoku".to_string(),
+                    1
+                )
+            )]
+        );
+    }
+
+    #[test]
+    fn test_validate_knet_missing_axis() {
+        let group = GroupConfig {
+            files: vec![
+                file("ISK0052401011610.NS", AccAxis::Ns),
+                file("ISK0052401011610.EW", AccAxis::Ew),
+            ],
+        };
+
+        let errors = group
+            .validate_file_by_acc_axis(&From::JpNiedKnet, "noto", 2)
+            .unwrap_err();
+
+        assert_eq!(
+            errors,
+            vec![AnalysisConfigErr::Validation(
+                ConfigValidationErr::MissingAccAxis("ud".to_string(), "noto".to_string(), 2)
+            )]
+        );
+    }
+
+    #[test]
+    fn test_fourier_spectrum_config() {
+        let config: FourierSpectrumConfig = toml::from_str("").unwrap();
+        assert_eq!(config.window, SpectrumWindow::CosineTaper(0.05));
+        assert_eq!(config.smoothing, Smoothing::None);
+        assert_eq!((config.min_hz, config.max_hz), (0.1, 25.0));
+        assert!(config.validate("noto").is_ok());
+
+        let config: FourierSpectrumConfig =
+            toml::from_str("window = \"hann\"\nsmoothing = { konno_ohmachi = 40.0 }").unwrap();
+        assert_eq!(config.window, SpectrumWindow::Hann);
+        assert_eq!(config.smoothing, Smoothing::KonnoOhmachi(40.0));
+
+        for invalid in [
+            "min_hz = 10.0\nmax_hz = 1.0",
+            "window = { cosine_taper = 0.8 }",
+            "smoothing = { parzen = 0.0 }",
+        ] {
+            let config: FourierSpectrumConfig = toml::from_str(invalid).unwrap();
+            assert!(matches!(
+                config.validate("noto"),
+                Err(ConfigValidationErr::InvalidFourierSpectrum(_, name)) if name == "noto"
+            ));
+        }
+    }
+
+    #[test]
+    fn test_response_spectrum_config() {
+        let config: ResponseSpectrumConfig = toml::from_str("").unwrap();
+        assert_eq!(config.damping, vec![0.05]);
+        assert_eq!(config.periods.len(), 100);
+        assert!((config.periods[0] - 0.02).abs() < 1e-12);
+        assert!((config.periods[99] - 10.0).abs() < 1e-9);
+        assert!(config.validate("noto").is_ok());
+
+        let config: ResponseSpectrumConfig =
+            toml::from_str("damping = [0.05, 1.2]\nperiods = [1.0]").unwrap();
+        assert!(matches!(
+            config.validate("noto"),
+            Err(ConfigValidationErr::InvalidResponseSpectrum(_, name)) if name == "noto"
+        ));
+    }
+
+    #[test]
+    fn test_processing_steps() {
+        #[derive(Deserialize)]
+        struct Processing {
+            processing: Vec<ProcessingStep>,
+        }
+
+        let parsed: Processing = toml::from_str(
+            r#"
+            [[processing]]
+            step = "detrend"
+            method = "mean"
+
+            [[processing]]
+            step = "band_pass"
+            low_hz = 0.1
+            high_hz = 20.0
+            order = 4
+
+            [[processing]]
+            step = "rotate"
+            azimuth = 30
+
+            [[processing]]
+            step = "rotate"
+            azimuth = "rotd50"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            parsed.processing,
+            vec![
+                ProcessingStep::Detrend {
+                    method: DetrendMethod::Mean
+                },
+                ProcessingStep::BandPass {
+                    low_hz: 0.1,
+                    high_hz: 20.0,
+                    order: 4,
+                    causal: false
+                },
+                ProcessingStep::Rotate {
+                    azimuth: Azimuth::Degrees(30.0)
+                },
+                ProcessingStep::Rotate {
+                    azimuth: Azimuth::RotD(RotD::RotD50)
+                },
+            ]
+        );
+        assert!(parsed.processing.iter().all(|s| s.validate("noto").is_ok()));
+
+        for step in [
+            ProcessingStep::Taper { fraction: 0.6 },
+            ProcessingStep::BandPass {
+                low_hz: 5.0,
+                high_hz: 1.0,
+                order: 2,
+                causal: true,
+            },
+            ProcessingStep::PolynomialBaseline { order: 11 },
+            ProcessingStep::Resample { hz: 0.0 },
+            ProcessingStep::Rotate {
+                azimuth: Azimuth::Degrees(f64::NAN),
+            },
+        ] {
+            assert!(matches!(
+                step.validate("noto"),
+                Err(ConfigValidationErr::InvalidProcessingStep(_, name)) if name == "noto"
+            ));
+        }
+    }
+
+    #[test]
+    fn test_resample_hz() {
+        let config = |settings: &str| -> ConversionConfig {
+            toml::from_str(&format!(
+                "name = \"noto\"\nfrom = \"jp_nied_knet\"\nto = \"jp_jma_csv\"\ngroup = []\n{}",
+                settings
+            ))
+            .unwrap()
+        };
+
+        let valid =
+            config("resample_hz = 50.0\n[[processing]]\nstep = \"detrend\"\nmethod = \"mean\"");
+        assert!(valid.validate().is_ok());
+        assert_eq!(
+            valid.processing_steps(),
+            vec![
+                ProcessingStep::Detrend {
+                    method: DetrendMethod::Mean
+                },
+                ProcessingStep::Resample { hz: 50.0 },
+            ]
+        );
+
+        let errors = config("resample_hz = 50.0\n[[processing]]\nstep = \"resample\"\nhz = 20.0")
+            .validate()
+            .unwrap_err();
+        assert!(matches!(
+            &errors[..],
+            [AnalysisConfigErr::Validation(ConfigValidationErr::InvalidProcessingStep(reason, name))]
+                if reason.contains("resample_hz") && name == "noto"
+        ));
+    }
+
+    #[test]
+    fn test_output_unit() {
+        let config = |to: &str, output_unit: &str| -> ConversionConfig {
+            toml::from_str(&format!(
+                "name = \"noto\"\nfrom = \"jp_nied_knet\"\nto = \"{}\"\n{}\ngroup = []",
+                to, output_unit
+            ))
+            .unwrap()
+        };
+
+        assert_eq!(config("jp_jma_csv", "").output_unit(), Unit::Gal);
+        assert_eq!(
+            config("jp_jma_csv", "output_unit = \"m/s2\"").output_unit(),
+            Unit::MetersPerSecondSquared
+        );
+        assert!(config("jp_stera3d_txt", "output_unit = \"gal\"")
+            .validate()
+            .is_ok());
+
+        let errors = config("jp_stera3d_txt", "output_unit = \"g\"")
+            .validate()
+            .unwrap_err();
+        assert!(matches!(
+            &errors[..],
+            [AnalysisConfigErr::Validation(ConfigValidationErr::UnsupportedOutputUnit(unit, to, _))]
+                if unit == "g" && to == "jp_stera3d_txt"
+        ));
+    }
+
+    #[test]
+    fn test_sensitivity_config() {
+        let config = |sensitivity: &str| -> ConversionConfig {
+            toml::from_str(&format!(
+                "name = \"noto\"\nfrom = \"mini_seed\"\nto = \"jp_jma_csv\"\ngroup = []\n{}",
+                sensitivity
+            ))
+            .unwrap()
+        };
+
+        let valid = config("[sensitivity]\ncounts_per_unit = 200.0\nunit = \"m/s2\"");
+        assert!(valid.validate().is_ok());
+        assert_eq!(valid.sensitivity.unwrap().gal_per_count(), Some(0.5));
+        assert_eq!(
+            config("[sensitivity]\ncounts_per_unit = 4.0")
+                .sensitivity
+                .unwrap()
+                .gal_per_count(),
+            Some(0.25)
+        );
+
+        for (sensitivity, expected) in [
+            ("", "'mini_seed' requires sensitivity"),
+            (
+                "[sensitivity]\ncounts_per_unit = 0.0",
+                "counts_per_unit must be more than 0",
+            ),
+            (
+                "[sensitivity]\ncounts_per_unit = 1.0\nunit = \"cm/s\"",
+                "unit must be a unit of acceleration",
+            ),
+        ] {
+            let errors = config(sensitivity).validate().unwrap_err();
+            assert!(matches!(
+                &errors[..],
+                [AnalysisConfigErr::Validation(ConfigValidationErr::InvalidSensitivity(reason, _))]
+                    if reason == expected
+            ));
+        }
+
+        let with_from = |from: From| ConversionConfig {
+            from,
+            ..valid.clone()
+        };
+        assert!(with_from(From::TwPalertSac).validate().is_ok());
+        let errors = with_from(From::JpNiedKnet).validate().unwrap_err();
+        assert!(matches!(
+            &errors[..],
+            [AnalysisConfigErr::Validation(ConfigValidationErr::InvalidSensitivity(reason, _))]
+                if reason == "'jp_nied_knet' does not use sensitivity"
+        ));
+    }
+
+    #[test]
+    fn test_mini_seed_config() {
+        let config = |mini_seed: &str| -> ConversionConfig {
+            toml::from_str(&format!(
+                "name = \"noto\"\nfrom = \"jp_nied_knet\"\nto = \"mini_seed\"\ngroup = []\n{}",
+                mini_seed
+            ))
+            .unwrap()
+        };
+
+        let default = config("[mini_seed]");
+        assert_eq!(default.mini_seed, Some(MiniSeedConfig::default()));
+        assert!(default.validate().is_ok());
+        let mini_seed = MiniSeedConfig::default();
+        assert_eq!(mini_seed.network, "XX");
+        assert_eq!(mini_seed.channels.ud, "HNZ");
+        assert_eq!(mini_seed.record_length, 512);
+
+        let custom = config(
+            "[mini_seed]\nnetwork = \"JP\"\nstation = \"ISK05\"\nlocation = \"00\"\n\
+             channels = { ns = \"HN1\", ew = \"HN2\", ud = \"HNZ\" }\nrecord_length = 4096",
+        );
+        assert!(custom.validate().is_ok());
+        assert_eq!(custom.mini_seed.unwrap().channels.ns, "HN1");
+
+        for (mini_seed, expected) in [
+            (
+                "network = \"JPN\"",
+                "network must be 1 or 2 alphanumeric characters",
+            ),
+            (
+                "station = \"ISK005\"",
+                "station must be 1 to 5 alphanumeric characters",
+            ),
+            (
+                "channels = { ns = \"HNZ\", ew = \"HNE\", ud = \"HNZ\" }",
+                "channels must differ from each other",
+            ),
+            (
+                "record_length = 1000",
+                "record_length must be a power of 2 from 256 to 65536",
+            ),
+            (
+                "counts_per_unit = -1.0",
+                "counts_per_unit must be more than 0",
+            ),
+        ] {
+            let errors = config(&format!("[mini_seed]\n{mini_seed}"))
+                .validate()
+                .unwrap_err();
+            assert!(matches!(
+                &errors[..],
+                [AnalysisConfigErr::Validation(ConfigValidationErr::InvalidMiniSeed(reason, _))]
+                    if reason == expected
+            ));
+        }
+    }
+
+    #[test]
+    fn test_sac_config() {
+        let config = |sac: &str| -> ConversionConfig {
+            toml::from_str(&format!(
+                "name = \"noto\"\nfrom = \"jp_nied_knet\"\nto = \"sac\"\ngroup = []\n{}",
+                sac
+            ))
+            .unwrap()
+        };
+
+        let default = config("");
+        assert_eq!(default.sac, None);
+        assert!(default.validate().is_ok());
+        assert_eq!(SacConfig::default().endian, Endian::Little);
+
+        let big = config("[sac]\nendian = \"big\"");
+        assert!(big.validate().is_ok());
+        assert_eq!(big.sac.unwrap().endian, Endian::Big);
+
+        let errors = config("[sac]\nchannels = { ns = \"HNN\", ew = \"\", ud = \"HNZ\" }")
+            .validate()
+            .unwrap_err();
+        assert!(matches!(
+            &errors[..],
+            [AnalysisConfigErr::Validation(ConfigValidationErr::InvalidSac(reason, _))]
+                if reason == "channels must be 1 to 8 ASCII characters"
+        ));
+    }
+
+    #[test]
+    fn test_output_quantity() {
+        let config = |to: &str, settings: &str| -> ConversionConfig {
+            toml::from_str(&format!(
+                "name = \"noto\"\nfrom = \"jp_nied_knet\"\nto = \"{}\"\n{}\ngroup = []",
+                to, settings
+            ))
+            .unwrap()
+        };
+
+        let velocity = config("jp_jma_csv", "output_quantity = \"velocity\"");
+        assert_eq!(velocity.output_unit(), Unit::CentimetersPerSecond);
+        assert_eq!(velocity.integration, IntegrationConfig::default());
+        assert!(velocity.validate().is_ok());
+
+        let displacement = config(
+            "jp_jma_csv",
+            "output_quantity = \"displacement\"\noutput_unit = \"m\"\n\
+             integration = { method = \"frequency_domain\", baseline = { polynomial = 3 } }",
+        );
+        assert_eq!(displacement.output_unit(), Unit::Meters);
+        assert_eq!(
+            displacement.integration,
+            IntegrationConfig {
+                method: IntegrationMethod::FrequencyDomain,
+                baseline: Baseline::Polynomial(3),
+            }
+        );
+        assert!(displacement.validate().is_ok());
+
+        let errors = config(
+            "jp_jma_csv",
+            "output_quantity = \"velocity\"\noutput_unit = \"gal\"",
+        )
+        .validate()
+        .unwrap_err();
+        assert!(matches!(
+            &errors[..],
+            [AnalysisConfigErr::Validation(ConfigValidationErr::MismatchedOutputUnit(unit, quantity, _))]
+                if unit == "gal" && quantity == "velocity"
+        ));
+
+        // STERA_3Dは加速度のみを扱う
+        let errors = config("jp_stera3d_txt", "output_quantity = \"velocity\"")
+            .validate()
+            .unwrap_err();
+        assert!(matches!(
+            &errors[..],
+            [AnalysisConfigErr::Validation(ConfigValidationErr::UnsupportedOutputUnit(unit, _, _))]
+                if unit == "cm/s"
+        ));
+    }
+}
@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+
+/// Byte order of a binary file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Endian {
+    #[default]
+    Little,
+    Big,
+}
+
+fn read_bytes(bytes: &[u8], offset: usize) -> [u8; 4] {
+    [
+        bytes[offset],
+        bytes[offset + 1],
+        bytes[offset + 2],
+        bytes[offset + 3],
+    ]
+}
+
+pub(crate) fn read_f32(bytes: &[u8], offset: usize, endian: Endian) -> f32 {
+    match endian {
+        Endian::Little => f32::from_le_bytes(read_bytes(bytes, offset)),
+        Endian::Big => f32::from_be_bytes(read_bytes(bytes, offset)),
+    }
+}
+
+pub(crate) fn read_i32(bytes: &[u8], offset: usize, endian: Endian) -> i32 {
+    match endian {
+        Endian::Little => i32::from_le_bytes(read_bytes(bytes, offset)),
+        Endian::Big => i32::from_be_bytes(read_bytes(bytes, offset)),
+    }
+}
+
+pub(crate) fn f32_bytes(value: f32, endian: Endian) -> [u8; 4] {
+    match endian {
+        Endian::Little => value.to_le_bytes(),
+        Endian::Big => value.to_be_bytes(),
+    }
+}
+
+pub(crate) fn i32_bytes(value: i32, endian: Endian) -> [u8; 4] {
+    match endian {
+        Endian::Little => value.to_le_bytes(),
+        Endian::Big => value.to_be_bytes(),
+    }
+}
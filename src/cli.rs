@@ -41,9 +41,26 @@ pub struct Args {
     /// Sets the logging level
     #[clap(short, long, value_enum, default_value_t = LogLevel::Info)]
     pub log_level: LogLevel,
+
+    /// Sets what to do with the extracted records
+    #[clap(short, long, value_enum, default_value_t = Mode::Convert)]
+    pub mode: Mode,
+}
+
+/// Processing applied to the records extracted from the analysis configuration file.
+#[derive(Debug, Clone, Copy, clap::ValueEnum, PartialEq, Eq)]
+pub enum Mode {
+    /// Writes each record in the file format of `to`.
+    Convert,
+    /// Prints the JMA instrumental seismic intensity of each record to stdout as `site,start time,intensity,class`.
+    ///
+    /// The processing steps and the resampling of the configuration are not applied, as the intensity is defined on
+    /// the recorded acceleration.
+    Intensity,
 }
 
 impl Args {
+    #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         Args::parse()
     }
@@ -159,6 +176,7 @@ mod tests {
             input_file_path: file_path.clone(),
             output_dir_path: PathBuf::from("."),
             log_level: LogLevel::Info,
+            mode: Mode::Convert,
         };
 
         assert!(args.validate_input_file_path(&file_path).is_ok());
@@ -184,6 +202,7 @@ mod tests {
                 input_file_path: file_path.clone(),
                 output_dir_path: PathBuf::from("."),
                 log_level: LogLevel::Info,
+                mode: Mode::Convert,
             };
 
             let result = args.validate_input_file_path(&file_path);
@@ -219,6 +238,7 @@ mod tests {
             input_file_path: non_existent_file_path.clone(),
             output_dir_path: PathBuf::from("."),
             log_level: LogLevel::Info,
+            mode: Mode::Convert,
         };
 
         let result = args.validate_input_file_path(&non_existent_file_path);
@@ -241,6 +261,7 @@ mod tests {
             input_file_path: dir.path().to_path_buf(),
             output_dir_path: PathBuf::from("."),
             log_level: LogLevel::Info,
+            mode: Mode::Convert,
         };
 
         let result = args.validate_input_file_path(dir.path());
@@ -263,6 +284,7 @@ mod tests {
             input_file_path: PathBuf::from("test.toml"),
             output_dir_path: dir.path().to_path_buf(),
             log_level: LogLevel::Info,
+            mode: Mode::Convert,
         };
 
         assert!(args.validate_output_dir_path(dir.path()).is_ok());
@@ -277,6 +299,7 @@ mod tests {
             input_file_path: PathBuf::from("test.toml"),
             output_dir_path: non_existent_dir.clone(),
             log_level: LogLevel::Info,
+            mode: Mode::Convert,
         };
 
         let result = args.validate_output_dir_path(&non_existent_dir);
@@ -303,6 +326,7 @@ mod tests {
             input_file_path: PathBuf::from("test.toml"),
             output_dir_path: file_path.clone(),
             log_level: LogLevel::Info,
+            mode: Mode::Convert,
         };
 
         let result = args.validate_output_dir_path(&file_path);
@@ -336,6 +360,7 @@ mod tests {
                 input_file_path: file_path.clone(),
                 output_dir_path: PathBuf::from("."),
                 log_level: LogLevel::Info,
+                mode: Mode::Convert,
             };
 
             assert!(args.validate_input_file_path(&file_path).is_ok());
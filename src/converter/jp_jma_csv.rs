@@ -0,0 +1,107 @@
+use std::path::Path;
+
+use crate::{
+    error::DataConversionErr,
+    record::{Record, Unit},
+};
+
+use super::Converter;
+
+pub const EXTENSION: &str = "csv";
+
+/// Converter for `To::JpJmaCsv`.
+pub struct JpJmaCsvConverter;
+
+impl Converter for JpJmaCsvConverter {
+    fn extension(&self) -> &str {
+        EXTENSION
+    }
+
+    fn write(&self, data: &Record, output_path: &Path) -> Result<(), DataConversionErr> {
+        write_jp_jma_csv(data, output_path)
+    }
+}
+
+/// Formats the data in the CSV layout of the JMA seismic intensity waveform.
+///
+/// ```text
+///  SITE CODE= ISK005
+///  LAT.= 37.3003
+///  LON.= 136.7698
+///  SAMPLING RATE= 100Hz
+///  UNIT  = gal
+///  INITIAL TIME = 2024 01 01 16 10 09
+///  NS,EW,UD
+/// -0.012,0.034,-0.005
+/// ```
+///
+/// The latitude and longitude are left blank if the station coordinates are unknown.
+pub fn format_jp_jma_csv(data: &Record) -> String {
+    let station = data.station();
+    let coordinate = |value: Option<f64>| value.map(|v| v.to_string()).unwrap_or_default();
+    let mut csv = format!(
+        " SITE CODE= {}\n LAT.= {}\n LON.= {}\n SAMPLING RATE= {}Hz\n UNIT  = {}\n INITIAL TIME = {}\n NS,EW,UD\n",
+        station.code,
+        coordinate(station.lat),
+        coordinate(station.lon),
+        data.sampling_rate(),
+        data.unit().as_str(),
+        data.start_time().format("%Y %m %d %H %M %S"),
+    );
+
+    let precision = precision(data.unit());
+    for [ns, ew, ud] in data.components().rows() {
+        csv.push_str(&format!(
+            "{:.*},{:.*},{:.*}\n",
+            precision, ns, precision, ew, precision, ud
+        ));
+    }
+
+    csv
+}
+
+// 単位によらずcm単位で小数点以下3桁程度の分解能となる桁数
+fn precision(unit: Unit) -> usize {
+    match unit {
+        Unit::Gal | Unit::CentimetersPerSecond | Unit::Centimeters => 3,
+        Unit::MillimetersPerSecondSquared | Unit::MillimetersPerSecond | Unit::Millimeters => 2,
+        Unit::MetersPerSecondSquared | Unit::MetersPerSecond | Unit::Meters => 5,
+        Unit::G => 6,
+    }
+}
+
+/// Writes the data to `output_path` as a JMA CSV file.
+pub fn write_jp_jma_csv(data: &Record, output_path: &Path) -> Result<(), DataConversionErr> {
+    std::fs::write(output_path, format_jp_jma_csv(data))
+        .map_err(|e| DataConversionErr::Io(output_path.to_path_buf(), e.into()))
+}
+
+/// This module contains unit tests for the JMA CSV writer.
+///
+/// # Test Categories
+///
+/// - `test_format_jp_jma_csv`: Tests the header and the NS, EW, UD columns
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::record::test_record;
+
+    #[test]
+    fn test_format_jp_jma_csv() {
+        let data = test_record(vec![0.0125, -1.5], vec![2.0, 0.001], vec![-0.1234, 3.0]);
+
+        assert_eq!(
+            format_jp_jma_csv(&data),
+            " SITE CODE= ISK005
+ LAT.= 37.3003
+ LON.= 136.7698
+ SAMPLING RATE= 100Hz
+ UNIT  = gal
+ INITIAL TIME = 2024 01 01 16 10 09
+ NS,EW,UD
+0.013,2.000,-0.123
+-1.500,0.001,3.000
+"
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use std::path::Path;
+
+use crate::{error::DataConversionErr, record::Record};
+
+use super::Converter;
+
+pub const EXTENSION: &str = "txt";
+
+/// Converter for `To::JpStera3dTxt`.
+pub struct JpStera3dTxtConverter;
+
+impl Converter for JpStera3dTxtConverter {
+    fn extension(&self) -> &str {
+        EXTENSION
+    }
+
+    fn write(&self, data: &Record, output_path: &Path) -> Result<(), DataConversionErr> {
+        write_jp_stera3d_txt(data, output_path)
+    }
+}
+
+/// Formats the data as a ground motion input file of STERA_3D.
+///
+/// The first line has the sampling interval(s) and the number of points, followed by one row of
+/// NS, EW, UD acceleration(gal) per time step.
+///
+/// ```text
+/// 0.01 3
+/// -0.0120 0.0340 -0.0050
+/// 0.2310 -0.1100 0.0020
+/// 0.0000 0.0150 -0.0310
+/// ```
+pub fn format_jp_stera3d_txt(data: &Record) -> String {
+    let mut txt = format!("{} {}\n", data.delta(), data.len());
+
+    for [ns, ew, ud] in data.components().rows() {
+        txt.push_str(&format!("{:.4} {:.4} {:.4}\n", ns, ew, ud));
+    }
+
+    txt
+}
+
+/// Writes the data to `output_path` as a STERA_3D text file.
+pub fn write_jp_stera3d_txt(data: &Record, output_path: &Path) -> Result<(), DataConversionErr> {
+    std::fs::write(output_path, format_jp_stera3d_txt(data))
+        .map_err(|e| DataConversionErr::Io(output_path.to_path_buf(), e.into()))
+}
+
+/// This module contains unit tests for the STERA_3D text writer.
+///
+/// # Test Categories
+///
+/// - `test_format_jp_stera3d_txt`: Tests the header and the three-component rows
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::record::test_record;
+
+    #[test]
+    fn test_format_jp_stera3d_txt() {
+        let data = Record {
+            sampling_rate: 200.0,
+            ..test_record(vec![0.0125, -1.5], vec![2.0, 0.001], vec![-0.1234, 3.0])
+        };
+
+        assert_eq!(
+            format_jp_stera3d_txt(&data),
+            "0.005 2
+0.0125 2.0000 -0.1234
+-1.5000 0.0010 3.0000
+"
+        );
+    }
+}
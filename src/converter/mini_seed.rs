@@ -0,0 +1,507 @@
+use std::path::Path;
+
+use chrono::{DateTime, Datelike, NaiveDateTime, TimeDelta, Timelike};
+
+use crate::{
+    analysis_config_file::MiniSeedConfig,
+    error::DataConversionErr,
+    extractor::mini_seed::sampling_rate,
+    record::{Component, Record},
+};
+
+use super::Converter;
+
+pub const EXTENSION: &str = "mseed";
+
+// 固定ヘッダー(48バイト)、ブロケット1000(8バイト)、ブロケット1001(8バイト)の後にデータが続く
+const FIXED_HEADER_SIZE: usize = 48;
+const BLOCKETTE_SIZE: usize = 8;
+const BLOCKETTE_100_SIZE: usize = 12;
+
+// Steim2のフレームは16ワード(64バイト)で、データの開始位置もフレーム境界に揃える
+const FRAME_SIZE: usize = 64;
+const WORDS_PER_FRAME: usize = 16;
+
+const STEIM2: u8 = 11;
+const BIG_ENDIAN: u8 = 1;
+
+// 1ワードに詰める差分の個数とビット数、およびそれを示すコード(nibble, dnib)
+const STEIM2_PACKINGS: [(usize, u32, u32, u32); 7] = [
+    (7, 4, 3, 2),
+    (6, 5, 3, 1),
+    (5, 6, 3, 0),
+    (4, 8, 1, 0),
+    (3, 10, 2, 3),
+    (2, 15, 2, 2),
+    (1, 30, 2, 1),
+];
+
+/// Converter for `To::MiniSeed`.
+///
+/// Each component is written as a channel of Steim2 records in big endian, one channel after another in a single
+/// file.
+pub struct MiniSeedConverter {
+    pub config: MiniSeedConfig,
+}
+
+impl MiniSeedConverter {
+    pub fn new(config: MiniSeedConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl Converter for MiniSeedConverter {
+    fn extension(&self) -> &str {
+        EXTENSION
+    }
+
+    fn write(&self, data: &Record, output_path: &Path) -> Result<(), DataConversionErr> {
+        write_mini_seed(data, &self.config, output_path)
+    }
+}
+
+/// Encodes the three components of the data as miniSEED records.
+///
+/// The samples in the unit of the record are multiplied by `counts_per_unit` and rounded. The start time and the
+/// sampling rate are those of the record, and the station code is the site code unless `station` is set.
+pub fn encode_mini_seed(data: &Record, config: &MiniSeedConfig) -> Result<Vec<u8>, String> {
+    let station = config.station.as_ref().unwrap_or(&data.station().code);
+    if !(1..=5).contains(&station.len()) || !station.is_ascii() {
+        return Err(format!(
+            "the station code '{station}' must be 1 to 5 characters, set mini_seed.station"
+        ));
+    }
+
+    let mut bytes: Vec<u8> = Vec::new();
+    let mut sequence = 1;
+
+    for component in Component::ALL {
+        let channel = match component {
+            Component::Ns => &config.channels.ns,
+            Component::Ew => &config.channels.ew,
+            Component::Ud => &config.channels.ud,
+        };
+        let counts = data
+            .components()
+            .get(component)
+            .iter()
+            .map(|v| {
+                let count = (v * config.counts_per_unit).round();
+                if count.abs() <= i32::MAX as f64 {
+                    Ok(count as i32)
+                } else {
+                    Err(format!("{v} exceeds the range of counts in {channel}"))
+                }
+            })
+            .collect::<Result<Vec<i32>, String>>()?;
+
+        let header = RecordHeader {
+            network: &config.network,
+            station,
+            location: &config.location,
+            channel,
+            sampling_rate: data.sampling_rate() as f64,
+            record_length: config.record_length,
+        };
+
+        // 各レコードの最初の差分は、前のレコードの最後のサンプルからの差分とする
+        let mut written = 0;
+        let mut previous = counts.first().copied().unwrap_or_default();
+        while written < counts.len() {
+            let start_time = data.start_time() + seconds(written as f64 / header.sampling_rate);
+            let (record, num_samples) =
+                header.encode(sequence, start_time, &counts[written..], previous)?;
+            bytes.extend(record);
+
+            written += num_samples;
+            previous = counts[written - 1];
+            sequence = sequence % 999_999 + 1;
+        }
+    }
+
+    Ok(bytes)
+}
+
+/// Writes the data to `output_path` as a miniSEED file.
+pub fn write_mini_seed(
+    data: &Record,
+    config: &MiniSeedConfig,
+    output_path: &Path,
+) -> Result<(), DataConversionErr> {
+    let bytes = encode_mini_seed(data, config)
+        .map_err(|reason| DataConversionErr::Format(output_path.to_path_buf(), reason))?;
+
+    std::fs::write(output_path, bytes)
+        .map_err(|e| DataConversionErr::Io(output_path.to_path_buf(), e.into()))
+}
+
+// 1チャンネル分のレコードに共通するヘッダーの値
+struct RecordHeader<'a> {
+    network: &'a str,
+    station: &'a str,
+    location: &'a str,
+    channel: &'a str,
+    sampling_rate: f64,
+    record_length: usize,
+}
+
+impl RecordHeader<'_> {
+    // 1レコードに収まるだけのサンプルを符号化し、レコードと格納したサンプル数を返す
+    fn encode(
+        &self,
+        sequence: usize,
+        start_time: NaiveDateTime,
+        counts: &[i32],
+        previous: i32,
+    ) -> Result<(Vec<u8>, usize), String> {
+        let (factor, multiplier) = rate_factor_multiplier(self.sampling_rate);
+        let exact = sampling_rate(factor, multiplier) == self.sampling_rate;
+
+        // 係数と乗数で表せないサンプリングレートはブロケット100で書き込む
+        let blockettes_end =
+            FIXED_HEADER_SIZE + BLOCKETTE_SIZE * 2 + if exact { 0 } else { BLOCKETTE_100_SIZE };
+        let data_offset = blockettes_end.div_ceil(FRAME_SIZE) * FRAME_SIZE;
+        let frames = (self.record_length - data_offset) / FRAME_SIZE;
+        let (data, num_samples, frames_used) = encode_steim2(counts, previous, frames)?;
+
+        let mut record = vec![0_u8; self.record_length];
+        put(&mut record, 0, format!("{:06}D ", sequence).as_bytes());
+        for (offset, len, code) in [
+            (8, 5, self.station),
+            (13, 2, self.location),
+            (15, 3, self.channel),
+            (18, 2, self.network),
+        ] {
+            put(&mut record, offset, format!("{code:<len$}").as_bytes());
+        }
+
+        // BTIMEは0.0001秒単位のため、端数の-50から49マイクロ秒はブロケット1001に書き込む
+        let micros = start_time.and_utc().timestamp_micros();
+        let rounded = (micros + 50).div_euclid(100) * 100;
+        let btime = DateTime::from_timestamp_micros(rounded)
+            .ok_or_else(|| format!("invalid start time {start_time}"))?
+            .naive_utc();
+        put(&mut record, 20, &(btime.year() as u16).to_be_bytes());
+        put(&mut record, 22, &(btime.ordinal() as u16).to_be_bytes());
+        put(
+            &mut record,
+            24,
+            &[
+                btime.hour() as u8,
+                btime.minute() as u8,
+                btime.second() as u8,
+            ],
+        );
+        put(
+            &mut record,
+            28,
+            &((btime.nanosecond() / 100_000) as u16).to_be_bytes(),
+        );
+        put(&mut record, 30, &(num_samples as u16).to_be_bytes());
+        put(&mut record, 32, &factor.to_be_bytes());
+        put(&mut record, 34, &multiplier.to_be_bytes());
+        record[39] = if exact { 2 } else { 3 };
+        put(&mut record, 44, &(data_offset as u16).to_be_bytes());
+        put(&mut record, 46, &(FIXED_HEADER_SIZE as u16).to_be_bytes());
+
+        // ブロケット1000: 符号化形式、ワード順序、レコード長の指数
+        let b1001 = FIXED_HEADER_SIZE + BLOCKETTE_SIZE;
+        put(&mut record, FIXED_HEADER_SIZE, &1000_u16.to_be_bytes());
+        put(
+            &mut record,
+            FIXED_HEADER_SIZE + 2,
+            &(b1001 as u16).to_be_bytes(),
+        );
+        put(
+            &mut record,
+            FIXED_HEADER_SIZE + 4,
+            &[
+                STEIM2,
+                BIG_ENDIAN,
+                self.record_length.trailing_zeros() as u8,
+            ],
+        );
+
+        // ブロケット1001: マイクロ秒とフレーム数
+        let next = if exact { 0 } else { b1001 + BLOCKETTE_SIZE };
+        put(&mut record, b1001, &1001_u16.to_be_bytes());
+        put(&mut record, b1001 + 2, &(next as u16).to_be_bytes());
+        put(
+            &mut record,
+            b1001 + 5,
+            &[
+                (micros - rounded) as i8 as u8,
+                0,
+                u8::try_from(frames_used).unwrap_or(0),
+            ],
+        );
+
+        // ブロケット100: 実際のサンプリングレート
+        if !exact {
+            put(&mut record, next, &100_u16.to_be_bytes());
+            put(
+                &mut record,
+                next + 4,
+                &(self.sampling_rate as f32).to_be_bytes(),
+            );
+        }
+
+        put(&mut record, data_offset, &data);
+
+        Ok((record, num_samples))
+    }
+}
+
+/// Compresses the counts with Steim2 into at most `frames` frames.
+///
+/// Returns the frames, the number of counts compressed and the number of frames used. The first difference is taken
+/// from `previous`, which is the last count of the preceding record.
+pub fn encode_steim2(
+    counts: &[i32],
+    previous: i32,
+    frames: usize,
+) -> Result<(Vec<u8>, usize, usize), String> {
+    let differences: Vec<i32> = std::iter::once(previous)
+        .chain(counts.iter().copied())
+        .collect::<Vec<i32>>()
+        .windows(2)
+        .map(|w| w[1].wrapping_sub(w[0]))
+        .collect();
+    // サンプル数はヘッダーの16bit整数で表せる範囲とする
+    let max_samples = counts.len().min(u16::MAX as usize);
+
+    let mut words: Vec<u32> = Vec::with_capacity(frames * WORDS_PER_FRAME);
+    let mut consumed = 0;
+    for f_index in 0..frames {
+        if consumed == max_samples {
+            break;
+        }
+        let mut frame = [0_u32; WORDS_PER_FRAME];
+        // 最初のフレームの1, 2ワード目は先頭と末尾のサンプル値(積分定数)
+        let first_word = if f_index == 0 { 3 } else { 1 };
+
+        for w_index in first_word..WORDS_PER_FRAME {
+            if consumed == max_samples {
+                break;
+            }
+
+            let remaining = &differences[consumed..max_samples];
+            let Some(&(count, bits, nibble, dnib)) =
+                STEIM2_PACKINGS.iter().find(|(count, bits, _, _)| {
+                    *count <= remaining.len() && remaining[..*count].iter().all(|&d| fits(d, *bits))
+                })
+            else {
+                return Err(format!(
+                    "the difference {} exceeds 30 bits of Steim2",
+                    remaining[0]
+                ));
+            };
+
+            frame[0] |= nibble << (30 - 2 * w_index);
+            frame[w_index] = pack(&remaining[..count], bits, dnib);
+            consumed += count;
+        }
+
+        words.extend(frame);
+    }
+
+    if consumed == 0 {
+        return Err("no samples fit in the record".to_string());
+    }
+    words[1] = counts[0] as u32;
+    words[2] = counts[consumed - 1] as u32;
+
+    let frames_used = words.len() / WORDS_PER_FRAME;
+    let bytes = words.iter().flat_map(|w| w.to_be_bytes()).collect();
+
+    Ok((bytes, consumed, frames_used))
+}
+
+// 差分が`bits`ビットの符号付き整数で表せるか
+fn fits(difference: i32, bits: u32) -> bool {
+    let limit = 1_i64 << (bits - 1);
+    (-limit..limit).contains(&(difference as i64))
+}
+
+// 上位ビットから`bits`ビットずつ差分を詰める
+fn pack(differences: &[i32], bits: u32, dnib: u32) -> u32 {
+    let mask = (1_u32 << bits) - 1;
+    let count = differences.len() as u32;
+
+    differences
+        .iter()
+        .enumerate()
+        .fold(dnib << 30, |word, (i, &d)| {
+            word | ((d as u32 & mask) << (bits * (count - 1 - i as u32)))
+        })
+}
+
+// サンプリングレートを表す係数と乗数
+fn rate_factor_multiplier(rate: f64) -> (i16, i16) {
+    let max = i16::MAX as f64;
+
+    if rate.fract() == 0.0 && rate <= max {
+        return (rate as i16, 1);
+    }
+    if rate < 1.0 && (1.0 / rate).fract() == 0.0 && 1.0 / rate <= max {
+        return (-(1.0 / rate) as i16, 1);
+    }
+    // 小数のサンプリングレートは、10のべき乗で割った値として表す
+    for divisor in [10.0, 100.0, 1000.0, 10000.0] {
+        let factor = (rate * divisor).round();
+        if factor <= max && factor / divisor == rate {
+            return (factor as i16, -(divisor as i16));
+        }
+    }
+
+    ((rate.round().clamp(1.0, max)) as i16, 1)
+}
+
+fn seconds(sec: f64) -> TimeDelta {
+    TimeDelta::microseconds((sec * 1e6).round() as i64)
+}
+
+fn put(bytes: &mut [u8], offset: usize, value: &[u8]) {
+    bytes[offset..offset + value.len()].copy_from_slice(value);
+}
+
+/// This module contains unit tests for the miniSEED writer.
+///
+/// # Test Categories
+///
+/// - `test_encode_steim2`: Tests the round trip of differences of every width through the miniSEED reader
+/// - `test_encode_mini_seed`: Tests the codes, the records split by the record length and the counts read back
+/// - `test_encode_mini_seed_station_code`: Tests rejection of a site code longer than 5 characters
+/// - `test_rate_factor_multiplier`: Tests the sample rate factor and multiplier of integer and decimal rates
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::byte_order::Endian;
+    use crate::extractor::mini_seed::{decode, merge_records, parse_mini_seed, Encoding};
+    use crate::record::test_record;
+
+    fn record(code: &str, len: usize) -> Record {
+        let wave = |scale: f64| -> Vec<f64> {
+            (0..len)
+                .map(|i| scale * (i as f64 * 0.37).sin() * (1.0 + i as f64 * 0.1))
+                .collect()
+        };
+
+        let mut data = test_record(wave(1.0), wave(-250.0), wave(0.001));
+        data.station.code = code.to_string();
+        data.start_time += TimeDelta::microseconds(123_456);
+        data
+    }
+
+    #[test]
+    fn test_encode_steim2() {
+        let counts: Vec<i32> = [
+            0,
+            3,
+            -4,
+            5,
+            1,
+            0,
+            7,
+            -8,
+            20,
+            -30,
+            31,
+            100,
+            -120,
+            500,
+            -400,
+            16000,
+            -16000,
+            500_000_000,
+            -500_000_000,
+            0,
+        ]
+        .iter()
+        .scan(0_i32, |sum, d: &i32| {
+            *sum = sum.wrapping_add(*d);
+            Some(*sum)
+        })
+        .collect();
+
+        let (bytes, consumed, frames_used) = encode_steim2(&counts, 0, 7).unwrap();
+        assert_eq!(consumed, counts.len());
+        assert_eq!(frames_used, 1);
+        let expected: Vec<f64> = counts.iter().map(|&c| c as f64).collect();
+        assert_eq!(
+            decode(&bytes, consumed, Encoding::Steim2, Endian::Big),
+            Ok(expected)
+        );
+
+        // 1フレーム(13ワード)に収まらない分は次のレコードに回す
+        let (_, consumed, frames_used) = encode_steim2(&[0, 400_000_000].repeat(20), 0, 1).unwrap();
+        assert_eq!((consumed, frames_used), (13, 1));
+        assert!(encode_steim2(&[0, i32::MAX], 0, 1).is_err());
+    }
+
+    #[test]
+    fn test_encode_mini_seed() {
+        let data = record("ISK05", 1000);
+        let config = MiniSeedConfig {
+            network: "JP".to_string(),
+            location: "00".to_string(),
+            record_length: 256,
+            ..MiniSeedConfig::default()
+        };
+
+        let bytes = encode_mini_seed(&data, &config).unwrap();
+        assert_eq!(bytes.len() % 256, 0);
+
+        let path = Path::new("test.mseed");
+        let records = parse_mini_seed(&bytes, path).unwrap();
+        assert!(records.len() > 3);
+        assert!(records.iter().all(|r| r.encoding == Encoding::Steim2));
+        assert_eq!(records[0].start_time, data.start_time);
+
+        let traces = merge_records(records.into_iter().map(|r| (r, path)).collect()).unwrap();
+        let channels: Vec<String> = traces.iter().map(|t| t.nslc.to_string()).collect();
+        assert_eq!(
+            channels,
+            ["JP.ISK05.00.HNE", "JP.ISK05.00.HNN", "JP.ISK05.00.HNZ"]
+        );
+
+        for (trace, component) in traces
+            .iter()
+            .zip([Component::Ew, Component::Ns, Component::Ud])
+        {
+            assert_eq!(trace.start_time, data.start_time);
+            assert_eq!(trace.sampling_rate, 100.0);
+            let expected = data.components().get(component);
+            assert_eq!(trace.samples.len(), expected.len());
+            for (count, value) in trace.samples.iter().zip(expected) {
+                assert_eq!(*count, (value * 1000.0).round());
+            }
+        }
+    }
+
+    #[test]
+    fn test_encode_mini_seed_station_code() {
+        let data = record("ISK005", 10);
+
+        assert!(encode_mini_seed(&data, &MiniSeedConfig::default())
+            .unwrap_err()
+            .contains("ISK005"));
+
+        let config = MiniSeedConfig {
+            station: Some("ISK5".to_string()),
+            ..MiniSeedConfig::default()
+        };
+        let bytes = encode_mini_seed(&data, &config).unwrap();
+        let records = parse_mini_seed(&bytes, Path::new("test.mseed")).unwrap();
+        assert_eq!(records[0].nslc.station, "ISK5");
+        assert_eq!(records[0].nslc.network, "XX");
+    }
+
+    #[test]
+    fn test_rate_factor_multiplier() {
+        assert_eq!(rate_factor_multiplier(100.0), (100, 1));
+        assert_eq!(rate_factor_multiplier(0.1), (-10, 1));
+        assert_eq!(rate_factor_multiplier(12.5), (125, -10));
+        assert_eq!(sampling_rate(125, -10), 12.5);
+    }
+}
@@ -0,0 +1,214 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use chrono::NaiveDateTime;
+use jp_jma_csv::JpJmaCsvConverter;
+use jp_stera3d_txt::JpStera3dTxtConverter;
+use mini_seed::MiniSeedConverter;
+use peer_at2::PeerAt2Converter;
+use sac::SacConverter;
+
+use crate::{
+    analysis_config_file::{ConversionConfig, From, NameFormat, To},
+    error::{AnalysisErr, AppError, DataConversionErr},
+    record::{Record, Unit},
+};
+
+pub mod jp_jma_csv;
+pub mod jp_stera3d_txt;
+pub mod mini_seed;
+pub mod peer_at2;
+pub mod sac;
+
+pub trait Converter {
+    /// Extension of the converted file, without the leading dot.
+    fn extension(&self) -> &str;
+
+    /// Paths of the files written for `output_path`, which is `output_path` itself unless the format writes one file
+    /// per component.
+    fn output_paths(&self, output_path: &Path) -> Vec<PathBuf> {
+        vec![output_path.to_path_buf()]
+    }
+
+    /// Writes the record to `output_path` in the file format of the `To`.
+    fn write(&self, data: &Record, output_path: &Path) -> Result<(), DataConversionErr>;
+}
+
+pub fn create_converter(conversion: &ConversionConfig) -> Box<dyn Converter> {
+    // toに対応するconverterを呼び出す
+    match &conversion.to {
+        To::JpJmaCsv => Box::new(JpJmaCsvConverter),
+        To::JpStera3dTxt => Box::new(JpStera3dTxtConverter),
+        To::PeerAt2 => Box::new(PeerAt2Converter),
+        To::MiniSeed => Box::new(MiniSeedConverter::new(
+            conversion.mini_seed.clone().unwrap_or_default(),
+        )),
+        To::Sac => Box::new(SacConverter::new(
+            conversion.sac.clone().unwrap_or_default(),
+        )),
+    }
+}
+
+/// Writes the record in `output_unit` to `output_dir` with the converter, and returns the path of the written file.
+///
+/// `written` holds the paths written so far in the run, to which the paths of `Converter::output_paths` are added. A
+/// record whose path is already in it, such as the same station and start time given in two groups, is an error
+/// instead of overwriting the file and its sibling files.
+pub fn convert(
+    converter: &dyn Converter,
+    data: &Record,
+    output_unit: Unit,
+    from: &From,
+    name_format: &NameFormat,
+    output_dir: &Path,
+    written: &mut HashSet<PathBuf>,
+) -> Result<PathBuf, Vec<AppError>> {
+    let output_path = output_dir.join(file_name(
+        name_format,
+        &data.start_time,
+        &data.station.code,
+        &institution_name(from, data),
+        converter.extension(),
+    ));
+    if written.contains(&output_path) {
+        return Err(vec![AnalysisErr::from(DataConversionErr::DuplicateOutput(
+            output_path,
+        ))
+        .into()]);
+    }
+    written.insert(output_path.clone());
+    written.extend(converter.output_paths(&output_path));
+
+    let data = data.to_unit(output_unit).map_err(|e| {
+        vec![AnalysisErr::from(DataConversionErr::Format(
+            output_path.clone(),
+            e.to_string(),
+        ))
+        .into()]
+    })?;
+    converter
+        .write(&data, &output_path)
+        .map_err(|e| vec![AnalysisErr::from(e).into()])?;
+
+    Ok(output_path)
+}
+
+/// Returns the path of a file written next to the converted file, e.g. `*-knet.peak.json` for
+/// `*-knet.csv` with the suffix `peak` and the extension `json`.
+pub(crate) fn sibling_path(converted_path: &Path, suffix: &str, extension: &str) -> PathBuf {
+    let file_stem = converted_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    converted_path.with_file_name(format!("{}.{}.{}", file_stem, suffix, extension))
+}
+
+/// Builds the name of the converted file according to `NameFormat`.
+pub fn file_name(
+    name_format: &NameFormat,
+    initial_time: &NaiveDateTime,
+    site_code: &str,
+    institution_name: &str,
+    extension: &str,
+) -> String {
+    match name_format {
+        NameFormat::YyyymmddHhmmssSnN => format!(
+            "{}-{}-{}.{}",
+            initial_time.format("%Y%m%d-%H%M%S"),
+            site_code,
+            institution_name,
+            extension
+        ),
+    }
+}
+
+// KiK-netは同じ観測点に地中と地表の記録があるため、センサーの位置を機関名に付加して区別する
+fn institution_name(from: &From, data: &Record) -> String {
+    match &data.station.sensor_position {
+        Some(sensor_position) => {
+            format!("{}_{}", from.institution_name(), sensor_position.as_str())
+        }
+        None => from.institution_name().to_string(),
+    }
+}
+
+/// This module contains unit tests for the helpers shared by the converters.
+///
+/// # Test Categories
+///
+/// - `test_file_name`: Tests the file name built from `NameFormat::YyyymmddHhmmssSnN`
+/// - `test_create_converter`: Tests the converter selected for each `To`
+/// - `test_convert_duplicate`: Tests rejection of a second record written to the same path
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::record::test_record;
+    use chrono::NaiveDate;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_file_name() {
+        let initial_time = NaiveDate::from_ymd_opt(2024, 1, 1)
+            .unwrap()
+            .and_hms_opt(16, 10, 18)
+            .unwrap();
+
+        assert_eq!(
+            file_name(
+                &NameFormat::YyyymmddHhmmssSnN,
+                &initial_time,
+                "ISK005",
+                "knet",
+                "csv"
+            ),
+            "20240101-161018-ISK005-knet.csv"
+        );
+    }
+
+    #[test]
+    fn test_create_converter() {
+        let extension = |to: &str| {
+            let conversion: ConversionConfig = toml::from_str(&format!(
+                "name = \"noto\"\nfrom = \"jp_nied_knet\"\nto = \"{}\"\ngroup = []",
+                to
+            ))
+            .unwrap();
+            create_converter(&conversion).extension().to_string()
+        };
+
+        assert_eq!(extension("jp_jma_csv"), "csv");
+        assert_eq!(extension("jp_stera3d_txt"), "txt");
+        assert_eq!(extension("peer_at2"), "at2");
+        assert_eq!(extension("mini_seed"), "mseed");
+        assert_eq!(extension("sac"), "sac");
+    }
+
+    #[test]
+    fn test_convert_duplicate() {
+        let dir = tempdir().unwrap();
+        let mut written: HashSet<PathBuf> = HashSet::new();
+        let data = test_record(vec![1.0], vec![2.0], vec![3.0]);
+        let mut write = |data: &Record| {
+            convert(
+                &JpJmaCsvConverter,
+                data,
+                Unit::Gal,
+                &From::JpNiedKnet,
+                &NameFormat::YyyymmddHhmmssSnN,
+                dir.path(),
+                &mut written,
+            )
+        };
+
+        let output_path = write(&data).unwrap();
+        assert_eq!(
+            write(&data).unwrap_err(),
+            vec![AppError::Analysis(AnalysisErr::Conversion(
+                DataConversionErr::DuplicateOutput(output_path)
+            ))]
+        );
+    }
+}
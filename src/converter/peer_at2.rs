@@ -0,0 +1,160 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    error::DataConversionErr,
+    record::{Component, Record},
+};
+
+use super::{sibling_path, Converter};
+
+pub const EXTENSION: &str = "at2";
+
+// 1行あたりの値の個数
+const VALUES_PER_LINE: usize = 5;
+
+/// Converter for `To::PeerAt2`.
+///
+/// As an AT2 file has a single component, one file is written per component, e.g. `*-knet.ns.at2`.
+pub struct PeerAt2Converter;
+
+impl Converter for PeerAt2Converter {
+    fn extension(&self) -> &str {
+        EXTENSION
+    }
+
+    fn output_paths(&self, output_path: &Path) -> Vec<PathBuf> {
+        Component::ALL
+            .iter()
+            .map(|component| component_path(output_path, *component))
+            .collect()
+    }
+
+    fn write(&self, data: &Record, output_path: &Path) -> Result<(), DataConversionErr> {
+        write_peer_at2(data, output_path)
+    }
+}
+
+/// Returns the path of the file of `component`, e.g. `*-knet.ns.at2` for `*-knet.at2`.
+pub fn component_path(output_path: &Path, component: Component) -> PathBuf {
+    sibling_path(output_path, component.as_str(), EXTENSION)
+}
+
+/// Formats one component of the data in the PEER NGA AT2 layout.
+///
+/// The second line has the start time, the site code and the component, and the values in the unit of the record(g
+/// for `To::PeerAt2`) follow 5 per line.
+///
+/// ```text
+/// PEER NGA STRONG MOTION DATABASE RECORD
+/// 01/01/2024 16:10:09.000, ISK005, NS
+/// ACCELERATION TIME SERIES IN UNITS OF G
+/// NPTS=     7, DT=   0.0100 SEC
+///  1.2345678E-02 -2.3456789E-03  0.0000000E+00  4.5678901E-01 -5.6789012E-05
+///  6.7890123E-06  7.8901234E-07
+/// ```
+pub fn format_peer_at2(data: &Record, component: Component) -> String {
+    let mut at2 = format!(
+        "PEER NGA STRONG MOTION DATABASE RECORD\n{}, {}, {}\n{} TIME SERIES IN UNITS OF {}\nNPTS={:6}, DT={:9.4} SEC\n",
+        data.start_time().format("%m/%d/%Y %H:%M:%S%.3f"),
+        data.station().code,
+        component.as_str().to_uppercase(),
+        data.quantity().as_str().to_uppercase(),
+        data.unit().as_str().to_uppercase(),
+        data.len(),
+        data.delta(),
+    );
+
+    for chunk in data.components().get(component).chunks(VALUES_PER_LINE) {
+        let line: Vec<String> = chunk.iter().map(|&v| format_exponential(v)).collect();
+        at2.push_str(&line.concat());
+        at2.push('\n');
+    }
+
+    at2
+}
+
+// Fortranの E15.7 と同様に、指数部を2桁以上として15文字に右寄せする
+fn format_exponential(value: f64) -> String {
+    let formatted = format!("{:.7E}", value);
+    let (mantissa, exponent) = formatted.split_once('E').unwrap_or((&formatted, "0"));
+    let exponent: i32 = exponent.parse().unwrap_or(0);
+    let sign = if exponent < 0 { '-' } else { '+' };
+
+    format!("{:>15}", format!("{mantissa}E{sign}{:02}", exponent.abs()))
+}
+
+/// Writes each component of the data next to `output_path` as an AT2 file.
+pub fn write_peer_at2(data: &Record, output_path: &Path) -> Result<(), DataConversionErr> {
+    for component in Component::ALL {
+        let path = component_path(output_path, component);
+        std::fs::write(&path, format_peer_at2(data, component))
+            .map_err(|e| DataConversionErr::Io(path.clone(), e.into()))?;
+    }
+
+    Ok(())
+}
+
+/// This module contains unit tests for the PEER AT2 writer.
+///
+/// # Test Categories
+///
+/// - `test_format_peer_at2`: Tests the header and the values of 5 per line in g
+/// - `test_format_exponential`: Tests the mantissa and the exponent of at least two digits
+/// - `test_write_peer_at2`: Tests the paths of the three component files
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::record::{test_record, Unit};
+    use tempfile::tempdir;
+
+    fn record() -> Record {
+        test_record(
+            vec![980.665, -9.80665, 0.0, 0.5, 1.0, 2.0],
+            vec![0.0; 6],
+            vec![1.0; 6],
+        )
+    }
+
+    #[test]
+    fn test_format_peer_at2() {
+        let data = record();
+        let at2 = format_peer_at2(&data.to_unit(Unit::G).unwrap(), Component::Ns);
+        let lines: Vec<&str> = at2.lines().collect();
+
+        assert_eq!(lines[0], "PEER NGA STRONG MOTION DATABASE RECORD");
+        assert_eq!(lines[1], "01/01/2024 16:10:09.000, ISK005, NS");
+        assert_eq!(lines[2], "ACCELERATION TIME SERIES IN UNITS OF G");
+        assert_eq!(lines[3], "NPTS=     6, DT=   0.0100 SEC");
+        assert_eq!(lines.len(), 6);
+        assert_eq!(
+            lines[4],
+            "  1.0000000E+00 -1.0000000E-02  0.0000000E+00  5.0985811E-04  1.0197162E-03"
+        );
+        assert_eq!(lines[5], "  2.0394324E-03");
+    }
+
+    #[test]
+    fn test_format_exponential() {
+        assert_eq!(format_exponential(0.012345678), "  1.2345678E-02");
+        assert_eq!(format_exponential(-123.0), " -1.2300000E+02");
+        assert_eq!(format_exponential(1.5e-120), " 1.5000000E-120");
+    }
+
+    #[test]
+    fn test_write_peer_at2() {
+        let dir = tempdir().unwrap();
+        let output_path = dir.path().join("20240101-161009-ISK005-knet.at2");
+
+        write_peer_at2(&record(), &output_path).unwrap();
+
+        for (path, name) in PeerAt2Converter.output_paths(&output_path).iter().zip([
+            "20240101-161009-ISK005-knet.ns.at2",
+            "20240101-161009-ISK005-knet.ew.at2",
+            "20240101-161009-ISK005-knet.ud.at2",
+        ]) {
+            assert_eq!(path, &dir.path().join(name));
+            assert!(path.is_file());
+        }
+        assert!(!output_path.exists());
+    }
+}
@@ -0,0 +1,315 @@
+use std::path::{Path, PathBuf};
+
+use chrono::{Datelike, NaiveDateTime, TimeDelta, Timelike};
+
+use crate::{
+    analysis_config_file::SacConfig,
+    byte_order::{f32_bytes, i32_bytes},
+    error::DataConversionErr,
+    record::{Component, Quantity, Record},
+    sac::{
+        B, CMPAZ, CMPINC, DELTA, DEPMAX, DEPMEN, DEPMIN, E, EVDP, EVLA, EVLO, HEADER_VERSION, IACC,
+        IB, IDEP, IDISP, IFTYPE, ITIME, IVEL, IZTYPE, KCMPNM, KEVNM, KSTNM, KUSER0, LCALDA, LEVEN,
+        LOVROK, LPSPOL, MAG, NPTS, NUM_FLOATS, NUM_INTS, NVHDR, NZHOUR, NZJDAY, NZMIN, NZMSEC,
+        NZSEC, NZYEAR, O, SAC_HEADER_SIZE, STLA, STLO, STRING_OFFSET, UNDEFINED_FLOAT,
+        UNDEFINED_INT, UNDEFINED_STRING,
+    },
+};
+
+use super::{sibling_path, Converter};
+
+pub const EXTENSION: &str = "sac";
+
+/// Converter for `To::Sac`.
+///
+/// As a SAC file has a single component, one file is written per component, e.g. `*-knet.ns.sac`.
+pub struct SacConverter {
+    pub config: SacConfig,
+}
+
+impl SacConverter {
+    pub fn new(config: SacConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl Converter for SacConverter {
+    fn extension(&self) -> &str {
+        EXTENSION
+    }
+
+    fn output_paths(&self, output_path: &Path) -> Vec<PathBuf> {
+        Component::ALL
+            .iter()
+            .map(|component| component_path(output_path, *component))
+            .collect()
+    }
+
+    fn write(&self, data: &Record, output_path: &Path) -> Result<(), DataConversionErr> {
+        write_sac(data, &self.config, output_path)
+    }
+}
+
+/// Returns the path of the file of `component`, e.g. `*-knet.ns.sac` for `*-knet.sac`.
+pub fn component_path(output_path: &Path, component: Component) -> PathBuf {
+    sibling_path(output_path, component.as_str(), EXTENSION)
+}
+
+/// Encodes one component of the data as a binary SAC file.
+///
+/// The reference time(NZ*) is the start time truncated to milliseconds, from which B and the origin time O are
+/// measured. Fields the record does not have, such as the coordinates of an unknown station, are left undefined.
+/// As IDEP has no field for the unit, the unit of the record is written to KUSER0. The site code is cut to the 8
+/// characters of KSTNM.
+pub fn encode_sac(data: &Record, component: Component, config: &SacConfig) -> Vec<u8> {
+    let values = data.components().get(component);
+    let start_time = data.start_time();
+
+    // NZMSECはミリ秒単位のため、端数はBで表す
+    let reference_time =
+        start_time - TimeDelta::nanoseconds((start_time.nanosecond() % 1_000_000) as i64);
+    let offset_sec = |time: NaiveDateTime| {
+        (time - reference_time)
+            .num_microseconds()
+            .unwrap_or_default() as f64
+            / 1e6
+    };
+    let defined = |value: Option<f64>| {
+        value
+            .filter(|v| v.is_finite())
+            .map_or(UNDEFINED_FLOAT, |v| v as f32)
+    };
+
+    let mut floats = [UNDEFINED_FLOAT; NUM_FLOATS];
+    floats[DELTA] = data.delta() as f32;
+    floats[B] = offset_sec(start_time) as f32;
+    floats[E] =
+        (offset_sec(start_time) + data.delta() * values.len().saturating_sub(1) as f64) as f32;
+    if !values.is_empty() {
+        floats[DEPMIN] = values.iter().copied().fold(f64::INFINITY, f64::min) as f32;
+        floats[DEPMAX] = values.iter().copied().fold(f64::NEG_INFINITY, f64::max) as f32;
+        floats[DEPMEN] = (values.iter().sum::<f64>() / values.len() as f64) as f32;
+    }
+    floats[STLA] = defined(data.station().lat);
+    floats[STLO] = defined(data.station().lon);
+    if let Some(event) = data.event() {
+        floats[O] = defined(event.origin_time.map(offset_sec));
+        floats[EVLA] = defined(event.lat);
+        floats[EVLO] = defined(event.lon);
+        floats[EVDP] = defined(event.depth_km);
+        floats[MAG] = defined(event.magnitude);
+    }
+    // CMPINCは鉛直上向きからの角度
+    let (cmpaz, cmpinc) = match component {
+        Component::Ns => (0.0, 90.0),
+        Component::Ew => (90.0, 90.0),
+        Component::Ud => (0.0, 0.0),
+    };
+    floats[CMPAZ] = cmpaz;
+    floats[CMPINC] = cmpinc;
+
+    let mut ints = [UNDEFINED_INT; NUM_INTS];
+    ints[NZYEAR] = reference_time.year();
+    ints[NZJDAY] = reference_time.ordinal() as i32;
+    ints[NZHOUR] = reference_time.hour() as i32;
+    ints[NZMIN] = reference_time.minute() as i32;
+    ints[NZSEC] = reference_time.second() as i32;
+    ints[NZMSEC] = (reference_time.nanosecond() / 1_000_000) as i32;
+    ints[NVHDR] = HEADER_VERSION;
+    ints[NPTS] = values.len() as i32;
+    ints[IFTYPE] = ITIME;
+    ints[IDEP] = match data.quantity() {
+        Quantity::Acceleration => IACC,
+        Quantity::Velocity => IVEL,
+        Quantity::Displacement => IDISP,
+    };
+    ints[IZTYPE] = IB;
+    ints[LEVEN] = 1;
+    ints[LPSPOL] = 1;
+    ints[LOVROK] = 1;
+    ints[LCALDA] = 1;
+
+    // 文字列ヘッダーは未定義値で埋めた上で、使用するフィールドを書き込む
+    let mut strings = [b' '; SAC_HEADER_SIZE - STRING_OFFSET];
+    for chunk in strings.chunks_mut(8) {
+        chunk[..UNDEFINED_STRING.len()].copy_from_slice(UNDEFINED_STRING.as_bytes());
+    }
+    strings[KEVNM.0 - STRING_OFFSET + 8..KEVNM.0 - STRING_OFFSET + KEVNM.1].fill(b' ');
+    let channel = match component {
+        Component::Ns => &config.channels.ns,
+        Component::Ew => &config.channels.ew,
+        Component::Ud => &config.channels.ud,
+    };
+    for ((offset, len), value) in [
+        (KSTNM, data.station().code.as_str()),
+        (KCMPNM, channel.as_str()),
+        (KUSER0, data.unit().as_str()),
+    ] {
+        let field = &mut strings[offset - STRING_OFFSET..offset - STRING_OFFSET + len];
+        field.fill(b' ');
+        let bytes = value.as_bytes();
+        let len = bytes.len().min(len);
+        field[..len].copy_from_slice(&bytes[..len]);
+    }
+
+    let mut bytes: Vec<u8> = Vec::with_capacity(SAC_HEADER_SIZE + values.len() * 4);
+    for v in floats {
+        bytes.extend(f32_bytes(v, config.endian));
+    }
+    for v in ints {
+        bytes.extend(i32_bytes(v, config.endian));
+    }
+    bytes.extend(strings);
+    for &v in values {
+        bytes.extend(f32_bytes(v as f32, config.endian));
+    }
+
+    bytes
+}
+
+/// Writes each component of the data next to `output_path` as a SAC file.
+pub fn write_sac(
+    data: &Record,
+    config: &SacConfig,
+    output_path: &Path,
+) -> Result<(), DataConversionErr> {
+    for component in Component::ALL {
+        let path = component_path(output_path, component);
+        std::fs::write(&path, encode_sac(data, component, config))
+            .map_err(|e| DataConversionErr::Io(path.clone(), e.into()))?;
+    }
+
+    Ok(())
+}
+
+/// This module contains unit tests for the SAC writer.
+///
+/// # Test Categories
+///
+/// - `test_encode_sac`: Tests the header fields and the samples read back by the SAC reader in both byte orders
+/// - `test_encode_sac_undefined`: Tests the undefined coordinates and event of a record without them
+/// - `test_write_sac`: Tests the paths of the three component files
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis_config_file::ChannelCodes;
+    use crate::byte_order::Endian;
+    use crate::extractor::tw_paleart_sac::parse_sac;
+    use crate::extractor::Orientation;
+    use crate::record::{test_record, Event};
+    use chrono::{NaiveDate, TimeDelta};
+    use tempfile::tempdir;
+
+    fn record(lat: Option<f64>, event: Option<Event>) -> Record {
+        let mut data = test_record(
+            vec![1.0, -2.0, 3.5],
+            vec![4.0, 5.0, 6.0],
+            vec![-7.0, 8.0, 9.0],
+        );
+        data.station.lat = lat;
+        data.event = event;
+        data.start_time += TimeDelta::microseconds(123_500);
+        data
+    }
+
+    #[test]
+    fn test_encode_sac() {
+        let origin_time = NaiveDate::from_ymd_opt(2024, 1, 1)
+            .unwrap()
+            .and_hms_opt(16, 10, 0)
+            .unwrap();
+        let data = record(
+            Some(37.3003),
+            Some(Event {
+                origin_time: Some(origin_time),
+                lat: Some(37.495),
+                lon: Some(137.27),
+                depth_km: Some(16.0),
+                magnitude: Some(7.6),
+            }),
+        );
+        let path = Path::new("test.sac");
+
+        for endian in [Endian::Little, Endian::Big] {
+            let config = SacConfig {
+                endian,
+                channels: ChannelCodes::default(),
+            };
+            let trace = parse_sac(&encode_sac(&data, Component::Ew, &config), path).unwrap();
+            let header = &trace.header;
+
+            assert_eq!(header.endian, endian);
+            assert_eq!(header.delta, 0.01);
+            assert_eq!(header.npts, 3);
+            assert_eq!(header.kstnm, "ISK005");
+            assert_eq!(header.kcmpnm, Some("HNE".to_string()));
+            assert_eq!(header.knetwk, None);
+            assert_eq!(header.stla, Some(37.3003));
+            assert_eq!(header.evla, Some(37.495));
+            assert_eq!(header.evdp, Some(16.0));
+            assert_eq!(header.mag, Some(7.6));
+            assert_eq!(header.orientation(), Some(Orientation::Horizontal(90.0)));
+            assert_eq!(
+                header.reference_time,
+                NaiveDate::from_ymd_opt(2024, 1, 1)
+                    .unwrap()
+                    .and_hms_milli_opt(16, 10, 9, 123)
+                    .unwrap()
+            );
+            assert!((header.b - 0.0005).abs() < 1e-6);
+            assert!((header.o.unwrap() + 9.123).abs() < 1e-5);
+            assert_eq!(header.event().unwrap().origin_time, Some(origin_time));
+            assert_eq!(trace.data, vec![4.0, 5.0, 6.0]);
+        }
+
+        let config = SacConfig::default();
+        let header = parse_sac(&encode_sac(&data, Component::Ud, &config), path)
+            .unwrap()
+            .header;
+        assert_eq!(header.orientation(), Some(Orientation::Up));
+        assert_eq!(header.kcmpnm, Some("HNZ".to_string()));
+    }
+
+    #[test]
+    fn test_encode_sac_undefined() {
+        let bytes = encode_sac(&record(None, None), Component::Ns, &SacConfig::default());
+        let header = parse_sac(&bytes, Path::new("test.sac")).unwrap().header;
+
+        assert_eq!(header.stla, None);
+        assert_eq!(header.stlo, Some(136.7698));
+        assert_eq!(header.o, None);
+        assert_eq!(header.event(), None);
+        assert_eq!(
+            String::from_utf8_lossy(&bytes[KUSER0.0..KUSER0.0 + KUSER0.1]),
+            "gal     "
+        );
+        assert_eq!(
+            String::from_utf8_lossy(&bytes[KEVNM.0..KEVNM.0 + KEVNM.1]),
+            "-12345          "
+        );
+    }
+
+    #[test]
+    fn test_write_sac() {
+        let dir = tempdir().unwrap();
+        let output_path = dir.path().join("20240101-161009-ISK005-knet.sac");
+        let converter = SacConverter::new(SacConfig::default());
+
+        converter
+            .write(&record(Some(37.3003), None), &output_path)
+            .unwrap();
+
+        for (path, name) in converter.output_paths(&output_path).iter().zip([
+            "20240101-161009-ISK005-knet.ns.sac",
+            "20240101-161009-ISK005-knet.ew.sac",
+            "20240101-161009-ISK005-knet.ud.sac",
+        ]) {
+            assert_eq!(path, &dir.path().join(name));
+            assert_eq!(
+                std::fs::metadata(path).unwrap().len() as usize,
+                SAC_HEADER_SIZE + 3 * 4
+            );
+        }
+        assert!(!output_path.exists());
+    }
+}
@@ -102,8 +102,28 @@ pub enum ConfigValidationErr {
     DuplicateAccAxis(String, String, usize),
     #[error("acc_axis does not exist: name:'{0}', id:'{1}'")]
     RequiredAccAxis(String, usize),
+    #[error("acc_axis '{0}' is missing: name:'{1}', id:'{2}'")]
+    MissingAccAxis(String, String, usize),
     #[error("Duplicate names, each NAME must be unique: '{0}'")]
     DuplicateNames(String),
+    #[error("Invalid response_spectrum ({0}): name:'{1}'")]
+    InvalidResponseSpectrum(String, String),
+    #[error("Invalid fourier_spectrum ({0}): name:'{1}'")]
+    InvalidFourierSpectrum(String, String),
+    #[error("Invalid processing step ({0}): name:'{1}'")]
+    InvalidProcessingStep(String, String),
+    #[error("output_unit '{0}' is not supported by '{1}': name:'{2}'")]
+    UnsupportedOutputUnit(String, String, String),
+    #[error("output_unit '{0}' is not a unit of output_quantity '{1}': name:'{2}'")]
+    MismatchedOutputUnit(String, String, String),
+    #[error("Invalid integration ({0}): name:'{1}'")]
+    InvalidIntegration(String, String),
+    #[error("Invalid sensitivity ({0}): name:'{1}'")]
+    InvalidSensitivity(String, String),
+    #[error("Invalid mini_seed ({0}): name:'{1}'")]
+    InvalidMiniSeed(String, String),
+    #[error("Invalid sac ({0}): name:'{1}'")]
+    InvalidSac(String, String),
 }
 
 #[non_exhaustive]
@@ -111,12 +131,18 @@ pub enum ConfigValidationErr {
 pub enum AnalysisErr {
     #[error("Data extraction error> {0}")]
     Extraction(#[from] DataExtractionErr),
+    #[error("Data conversion error> {0}")]
+    Conversion(#[from] DataConversionErr),
+    #[error("Data processing error> {0}")]
+    Processing(#[from] DataProcessingErr),
 }
 
 impl AnalysisErr {
     pub fn exit_code(&self) -> i32 {
         match self {
             Self::Extraction(_) => 6,
+            Self::Conversion(_) => 7,
+            Self::Processing(_) => 8,
         }
     }
 }
@@ -126,6 +152,61 @@ impl AnalysisErr {
 pub enum DataExtractionErr {
     #[error("Invalid data structure: path{0}")]
     InvalidStructure(PathBuf),
+    #[error("Failed to read the file: path'{0}'> {1}")]
+    Io(PathBuf, IoErrWrapper),
+    #[error("Header field '{0}' was not found: path'{1}'")]
+    MissingHeaderField(String, PathBuf),
+    #[error("Header field '{0}' has an invalid value '{1}': path'{2}'")]
+    InvalidHeaderValue(String, String, PathBuf),
+    #[error("Invalid data value '{0}': path'{1}'")]
+    InvalidDataValue(String, PathBuf),
+    #[error("acc_axis does not exist: path'{0}'")]
+    RequiredAccAxis(PathBuf),
+    #[error("The stream '{0}' does not match acc_axis '{1}': path'{2}'")]
+    MismatchedAccAxis(String, String, PathBuf),
+    #[error("The components of the group do not match ({0}): name:'{1}', id:'{2}'")]
+    MismatchedComponents(String, String, usize),
+    #[error("A {0} of {1} s was found in the channel '{2}' at {3}: path'{4}'")]
+    Discontinuity(String, String, String, String, PathBuf),
+    #[error(
+        "The channel '{0}' has {1} s outside the time span shared by the other channels: name:'{2}', id:'{3}'"
+    )]
+    UnsharedSpan(String, String, String, usize),
+}
+
+#[non_exhaustive]
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum DataConversionErr {
+    #[error("Failed to write the file: path'{0}'> {1}")]
+    Io(PathBuf, IoErrWrapper),
+    #[error("Failed to format the file: path'{0}'> {1}")]
+    Format(PathBuf, String),
+    #[error("The file has already been written for another record: path'{0}'")]
+    DuplicateOutput(PathBuf),
+}
+
+#[non_exhaustive]
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum DataProcessingErr {
+    #[error(
+        "The corner frequency '{0}' Hz is not below the Nyquist frequency '{1}' Hz: site code'{2}'"
+    )]
+    CornerAboveNyquist(String, String, String),
+    #[error("The record is too short for the step '{0}': site code'{1}'")]
+    TooShort(String, String),
+    #[error("The {0} requires acceleration, but the record is {1}: site code'{2}'")]
+    NotAcceleration(String, String, String),
+}
+
+#[non_exhaustive]
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum RecordErr {
+    #[error("The components have different lengths: ns:'{0}', ew:'{1}', ud:'{2}'")]
+    MismatchedLength(usize, usize, usize),
+    #[error("The sampling rate must be positive: '{0}'")]
+    InvalidSamplingRate(String),
+    #[error("Cannot convert '{0}' to '{1}', which is a unit of another quantity")]
+    MismatchedQuantity(String, String),
 }
 
 // PartialEq, Eqの実装を行うための、std::io::ErrorをラップするカスタムI/Oエラー型
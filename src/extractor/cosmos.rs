@@ -0,0 +1,661 @@
+use std::path::Path;
+
+use chrono::{NaiveDate, NaiveDateTime, TimeDelta};
+
+use crate::{
+    analysis_config_file::{ConversionConfig, GroupConfig},
+    error::{AnalysisErr, AppError, DataExtractionErr},
+    record::{Components, Quantity, Record, Station, Unit},
+};
+
+use super::{
+    misaligned, orient_components, parse_fixed_width, read_to_string, take_lines, Extractor,
+    Orientation,
+};
+
+// 整数ヘッダーのインデックス(仕様書のInt(n)のn - 1)
+const INT_UNITS: usize = 1;
+const INT_YEAR: usize = 39;
+const INT_MONTH: usize = 41;
+const INT_DAY: usize = 42;
+const INT_HOUR: usize = 43;
+const INT_MINUTE: usize = 44;
+const INT_AZIMUTH: usize = 53;
+
+// 実数ヘッダーのインデックス(仕様書のReal(n)のn - 1)
+const REAL_STATION_LAT: usize = 0;
+const REAL_STATION_LON: usize = 1;
+const REAL_SECOND: usize = 29;
+const REAL_DELTA_MSEC: usize = 61;
+
+// Int(54)で鉛直成分を表す値
+const AZIMUTH_UP: i32 = 400;
+const AZIMUTH_DOWN: i32 = 500;
+
+// 値が不明であることを表す値
+const UNKNOWN_INT: i32 = -999;
+
+/// One data set of a COSMOS file.
+///
+/// A COSMOS file(`*.v0`, `*.v1`, `*.v2`, `*.smc`) consists of one or more data sets, e.g. the acceleration,
+/// velocity and displacement of a channel for Volume 2, each of which has the following layout.
+/// - Text header: the first line tells the number of text lines(`with 13 text lines`), and the fifth line has the
+///   station code(`Code:CE-13921`).
+/// - Integer header following a line such as `100 Integer-header values follow on 10 lines, Format= (10I8)`: units
+///   code(2), start time(40, 42-45) and sensor azimuth(54, where 400 is up and 500 is down).
+/// - Real header following a line such as `100 Real-header values follow on 17 lines, Format= (6F13.6)`: station
+///   coordinates(1, 2), start second(30) and sampling interval in milliseconds(62).
+/// - Comment lines following a line such as `1 Comment line(s) follow, each starting with a "|":`.
+/// - Data following a line such as `8000 acceleration pts, approx 40 secs, units=cm/sec2(04), Format=(8F10.5)`,
+///   and an optional `End-of-data` line.
+///
+/// Header indices are 1-based as in the specification.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CosmosDataSet {
+    pub network_code: String,
+    pub station_code: String,
+    pub station_lat: f64,
+    pub station_lon: f64,
+    pub orientation: Orientation,
+    pub start_time: NaiveDateTime,
+    pub int_header: Vec<i32>,
+    pub real_header: Vec<f64>,
+    pub delta_sec: f64,
+    pub unit: Unit,
+    /// Values in `unit` as written in the file.
+    pub values: Vec<f64>,
+}
+
+pub struct CosmosExtractor {
+    pub unextracted: ConversionConfig,
+}
+
+impl Extractor for CosmosExtractor {
+    fn extract(&self) -> Result<Vec<Record>, Vec<AppError>> {
+        let mut extracted: Vec<Record> = Vec::new();
+        let mut errors: Vec<AppError> = Vec::new();
+
+        for (g_index, group_config) in self.unextracted.group.iter().enumerate() {
+            let id: usize = g_index + 1;
+            match self.extract_group(group_config, id) {
+                Ok(data) => extracted.push(data),
+                Err(e) => errors.extend(e.into_iter().map(|e| AnalysisErr::from(e).into())),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(extracted)
+    }
+}
+
+impl CosmosExtractor {
+    pub fn new(unextracted: ConversionConfig) -> Self {
+        Self { unextracted }
+    }
+
+    // グループ内の全ファイルの加速度のデータセットを1つのComponentsにまとめる
+    fn extract_group(
+        &self,
+        group_config: &GroupConfig,
+        id: usize,
+    ) -> Result<Record, Vec<DataExtractionErr>> {
+        let mut errors: Vec<DataExtractionErr> = Vec::new();
+        let mut data_sets: Vec<CosmosDataSet> = Vec::new();
+
+        for file in &group_config.files {
+            match parse_cosmos_file(&file.path) {
+                Ok(d) => data_sets.extend(d),
+                Err(e) => errors.push(e),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        // Volume 2の速度、変位のデータセットは使わない
+        data_sets.retain(|d| d.unit.quantity() == Quantity::Acceleration);
+
+        let name = &self.unextracted.name;
+        let mismatched = |reason: &str| {
+            vec![DataExtractionErr::MismatchedComponents(
+                reason.to_string(),
+                name.to_string(),
+                id,
+            )]
+        };
+
+        let Some(first) = data_sets.first() else {
+            return Err(mismatched("no acceleration components"));
+        };
+        if data_sets
+            .iter()
+            .any(|d| d.station_code != first.station_code)
+        {
+            return Err(mismatched("station code"));
+        }
+        if data_sets.iter().any(|d| d.delta_sec != first.delta_sec) {
+            return Err(mismatched("sampling interval"));
+        }
+        let start_times: Vec<NaiveDateTime> = data_sets.iter().map(|d| d.start_time).collect();
+        if misaligned(&start_times, 1.0 / first.delta_sec) {
+            return Err(mismatched("start time"));
+        }
+
+        let site_code = first.station_code.clone();
+        let (lat, lon) = (Some(first.station_lat), Some(first.station_lon));
+        let initial_time = first.start_time;
+        let sampling_rate = (1.0 / first.delta_sec) as f32;
+
+        // センサーの方位角からNS, EWに変換する
+        let components: Components = orient_components(
+            data_sets
+                .into_iter()
+                .filter_map(|d| {
+                    let factor = d.unit.factor_to(Unit::Gal)?;
+                    Some((d.orientation, d.values.iter().map(|v| v * factor).collect()))
+                })
+                .collect(),
+        )
+        .map_err(|reason| mismatched(&reason))?;
+
+        Ok(Record {
+            station: Station {
+                code: site_code,
+                lat,
+                lon,
+                sensor_position: None,
+            },
+            event: None,
+            start_time: initial_time,
+            sampling_rate,
+            unit: Unit::Gal,
+            components,
+            processing: Vec::new(),
+        })
+    }
+}
+
+/// Reads a COSMOS file, which contains one or more data sets.
+pub fn parse_cosmos_file(path: &Path) -> Result<Vec<CosmosDataSet>, DataExtractionErr> {
+    let contents = read_to_string(path)?;
+    parse_cosmos(&contents, path)
+}
+
+/// Parses the contents of a COSMOS file into its data sets.
+pub fn parse_cosmos(contents: &str, path: &Path) -> Result<Vec<CosmosDataSet>, DataExtractionErr> {
+    let lines: Vec<&str> = contents.lines().collect();
+    let mut data_sets: Vec<CosmosDataSet> = Vec::new();
+    let mut pos: usize = 0;
+
+    loop {
+        // データセット間の空行と、"End-of-data for Chan 1 acceleration"のような終端行を読み飛ばす
+        while pos < lines.len() && is_separator(lines[pos]) {
+            pos += 1;
+        }
+        if pos >= lines.len() {
+            break;
+        }
+
+        data_sets.push(parse_data_set(&lines, &mut pos, path)?);
+    }
+
+    Ok(data_sets)
+}
+
+fn is_separator(line: &str) -> bool {
+    let line = line.trim().to_lowercase();
+    line.is_empty() || line.starts_with("end-of-data")
+}
+
+fn parse_data_set(
+    lines: &[&str],
+    pos: &mut usize,
+    path: &Path,
+) -> Result<CosmosDataSet, DataExtractionErr> {
+    let text_header_lines = parse_text_line_count(lines[*pos], path)?;
+    let text_header = take_lines(lines, pos, text_header_lines, path)?;
+
+    let int_header: Vec<i32> = read_header_block(lines, pos, "Integer-header", path)?;
+    let real_header: Vec<f64> = read_header_block(lines, pos, "Real-header", path)?;
+
+    // コメント行は"|"で始まり、その行数が先頭の行に書かれている
+    let comment_line = take_lines(lines, pos, 1, path)?[0];
+    let comments = leading_count(comment_line, "Comment", path)?;
+    take_lines(lines, pos, comments, path)?;
+
+    let int_value = |index: usize| {
+        int_header
+            .get(index)
+            .copied()
+            .ok_or_else(|| DataExtractionErr::InvalidStructure(path.to_path_buf()))
+    };
+    let real_value = |index: usize| {
+        real_header
+            .get(index)
+            .copied()
+            .ok_or_else(|| DataExtractionErr::InvalidStructure(path.to_path_buf()))
+    };
+
+    let (network_code, station_code) = parse_station_code(text_header, path)?;
+    let orientation = parse_orientation(int_value(INT_AZIMUTH)?, path)?;
+    let start_time = parse_start_time(
+        [
+            int_value(INT_YEAR)?,
+            int_value(INT_MONTH)?,
+            int_value(INT_DAY)?,
+            int_value(INT_HOUR)?,
+            int_value(INT_MINUTE)?,
+        ],
+        real_value(REAL_SECOND)?,
+        path,
+    )?;
+    let delta_sec = real_value(REAL_DELTA_MSEC)? / 1000.0;
+    if delta_sec <= 0.0 {
+        return Err(DataExtractionErr::InvalidHeaderValue(
+            "sampling interval".to_string(),
+            delta_sec.to_string(),
+            path.into(),
+        ));
+    }
+
+    let data_line = take_lines(lines, pos, 1, path)?[0];
+    let num_of_points = leading_count(data_line, "data", path)?;
+    let unit = unit_from_code(int_value(INT_UNITS)?)
+        .or_else(|| units_label(data_line).and_then(Unit::from_label))
+        .ok_or_else(|| {
+            DataExtractionErr::InvalidHeaderValue(
+                "units".to_string(),
+                units_label(data_line)
+                    .unwrap_or(data_line.trim())
+                    .to_string(),
+                path.into(),
+            )
+        })?;
+    let width = parse_format_width(data_line, path)?;
+    let values = read_values::<f64>(lines, pos, num_of_points, width, path)?;
+
+    Ok(CosmosDataSet {
+        network_code,
+        station_code,
+        station_lat: real_value(REAL_STATION_LAT)?,
+        station_lon: real_value(REAL_STATION_LON)?,
+        orientation,
+        start_time,
+        int_header,
+        real_header,
+        delta_sec,
+        unit,
+        values,
+    })
+}
+
+// 1行目の"(Format v01.20 with 13 text lines)"からテキストヘッダーの行数を取得する
+fn parse_text_line_count(first_line: &str, path: &Path) -> Result<usize, DataExtractionErr> {
+    let words: Vec<&str> = first_line.split_whitespace().collect();
+
+    words
+        .windows(3)
+        .find(|w| w[1].eq_ignore_ascii_case("text") && w[2].to_lowercase().starts_with("lines"))
+        .and_then(|w| w[0].parse::<usize>().ok())
+        .filter(|&count| count > 0)
+        .ok_or_else(|| {
+            DataExtractionErr::InvalidHeaderValue(
+                "text lines".to_string(),
+                first_line.trim().to_string(),
+                path.into(),
+            )
+        })
+}
+
+// "100 Integer-header values follow on 10 lines, Format= (10I8)"に続くヘッダーの値を読み込む
+fn read_header_block<T: std::str::FromStr>(
+    lines: &[&str],
+    pos: &mut usize,
+    label: &str,
+    path: &Path,
+) -> Result<Vec<T>, DataExtractionErr> {
+    let line = take_lines(lines, pos, 1, path)?[0];
+    if !line.contains(label) {
+        return Err(DataExtractionErr::MissingHeaderField(
+            label.to_string(),
+            path.into(),
+        ));
+    }
+
+    let count = leading_count(line, label, path)?;
+    let width = parse_format_width(line, path)?;
+    read_values(lines, pos, count, width, path)
+}
+
+// 行頭に書かれた個数を取得する
+fn leading_count(line: &str, label: &str, path: &Path) -> Result<usize, DataExtractionErr> {
+    line.split_whitespace()
+        .next()
+        .and_then(|count| count.parse::<usize>().ok())
+        .ok_or_else(|| {
+            DataExtractionErr::InvalidHeaderValue(
+                label.to_string(),
+                line.trim().to_string(),
+                path.into(),
+            )
+        })
+}
+
+// "Format=(8F10.5)"、"Format= (10I8)"のようなFortranの書式から1つの値の桁数を取得する
+fn parse_format_width(line: &str, path: &Path) -> Result<usize, DataExtractionErr> {
+    let invalid = || {
+        DataExtractionErr::InvalidHeaderValue(
+            "Format".to_string(),
+            line.trim().to_string(),
+            path.into(),
+        )
+    };
+
+    let format = line
+        .split_once("Format=")
+        .map(|(_, format)| format.trim().trim_start_matches('('))
+        .ok_or_else(invalid)?;
+    // 繰り返し数と型を表す文字(I, F, E)の後ろの数字が桁数
+    let (_, rest) = format
+        .split_once(|c: char| c.is_ascii_alphabetic() && c != 'P' && c != 'p')
+        .ok_or_else(invalid)?;
+    let width: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+
+    width
+        .parse::<usize>()
+        .ok()
+        .filter(|&w| w > 0)
+        .ok_or_else(invalid)
+}
+
+// 指定された個数の固定長の値を読み込む
+fn read_values<T: std::str::FromStr>(
+    lines: &[&str],
+    pos: &mut usize,
+    count: usize,
+    width: usize,
+    path: &Path,
+) -> Result<Vec<T>, DataExtractionErr> {
+    let mut values: Vec<T> = Vec::with_capacity(count);
+    while values.len() < count {
+        let line = take_lines(lines, pos, 1, path)?[0];
+        values.extend(parse_fixed_width::<T>(line, width, path)?);
+    }
+    values.truncate(count);
+
+    Ok(values)
+}
+
+// 5行目の"Code:CE-13921"からネットワークコードと観測点コードを取得する
+fn parse_station_code(
+    text_header: &[&str],
+    path: &Path,
+) -> Result<(String, String), DataExtractionErr> {
+    let code = text_header
+        .iter()
+        .find_map(|line| line.split_once("Code:").map(|(_, rest)| rest))
+        .and_then(|rest| rest.split_whitespace().next())
+        .ok_or_else(|| DataExtractionErr::MissingHeaderField("Code:".to_string(), path.into()))?;
+
+    Ok(match code.split_once('-') {
+        Some((network, station)) => (network.to_string(), station.to_string()),
+        None => (String::new(), code.to_string()),
+    })
+}
+
+// Int(54)の北から時計回りの方位角(度)、または400(上向き)、500(下向き)から成分の向きを取得する
+fn parse_orientation(azimuth: i32, path: &Path) -> Result<Orientation, DataExtractionErr> {
+    match azimuth {
+        AZIMUTH_UP => Ok(Orientation::Up),
+        AZIMUTH_DOWN => Ok(Orientation::Down),
+        0..=360 => Ok(Orientation::Horizontal((azimuth % 360) as f64)),
+        _ => Err(DataExtractionErr::InvalidHeaderValue(
+            "sensor azimuth".to_string(),
+            azimuth.to_string(),
+            path.into(),
+        )),
+    }
+}
+
+// Int(2)の単位コード。カウント値など加速度、速度、変位以外の単位はNoneとする
+fn unit_from_code(code: i32) -> Option<Unit> {
+    match code {
+        2 => Some(Unit::G),
+        4 => Some(Unit::Gal),
+        5 => Some(Unit::CentimetersPerSecond),
+        6 => Some(Unit::Centimeters),
+        _ => None,
+    }
+}
+
+// データ部の先頭行の"units=cm/sec2(04)"から単位の表記を取り出す
+fn units_label(data_line: &str) -> Option<&str> {
+    let (_, rest) = data_line.split_once("units=")?;
+    rest.split(['(', ',', ' ']).next().filter(|s| !s.is_empty())
+}
+
+fn parse_start_time(
+    [year, month, day, hour, minute]: [i32; 5],
+    second: f64,
+    path: &Path,
+) -> Result<NaiveDateTime, DataExtractionErr> {
+    let invalid = || {
+        DataExtractionErr::InvalidHeaderValue(
+            "start time".to_string(),
+            format!("{year}-{month}-{day} {hour}:{minute}:{second}"),
+            path.into(),
+        )
+    };
+
+    if [year, month, day, hour, minute].contains(&UNKNOWN_INT) || !(0.0..60.0).contains(&second) {
+        return Err(invalid());
+    }
+    let minute_start = NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+        .and_then(|date| date.and_hms_opt(hour as u32, minute as u32, 0))
+        .ok_or_else(invalid)?;
+
+    Ok(minute_start + TimeDelta::microseconds((second * 1e6).round() as i64))
+}
+
+/// This module contains unit tests for the COSMOS parser.
+///
+/// # Test Categories
+///
+/// - `test_parse_format_width`: Tests the width of the Fortran formats of the headers and the data
+/// - `test_parse_cosmos_v2`: Tests parsing of the headers of the acceleration and velocity data sets of Volume 2
+/// - `test_parse_cosmos_counts`: Tests rejection of raw counts without a physical unit
+/// - `test_extract_group`: Tests conversion to gal and rotation of the sensor azimuths into NS/EW
+/// - `test_extract_group_misaligned`: Tests rejection of data sets whose start times differ by more than half a sample
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis_config_file::{FileConfig, From, To};
+    use std::{fs::File, io::Write};
+    use tempfile::tempdir;
+
+    fn block(values: &[String], per_line: usize) -> Vec<String> {
+        values
+            .chunks(per_line)
+            .map(|chunk| chunk.concat())
+            .collect()
+    }
+
+    fn data_set_text(azimuth: i32, units: (i32, &str), values: &[f64]) -> String {
+        let mut lines: Vec<String> = vec![
+            "Corrected accelerogram   (Format v01.20 with 13 text lines) Src: 13921.v2".to_string(),
+            "Hector Mine Earthquake       October 16, 1999 09:46 PDT".to_string(),
+            "Hypocenter: 34.594  -116.271  H= 14km  ML=7.1".to_string(),
+            "Origin: 10/16/1999, 09:46:44.1 UTC".to_string(),
+            "Statn No: 05- 13921  Code:CE-13921  CDMG  Riverside - Sunnymead".to_string(),
+        ];
+        lines.resize(13, "-".to_string());
+
+        let mut int_header = [0; 100];
+        int_header[INT_UNITS] = units.0;
+        int_header[INT_YEAR] = 1999;
+        int_header[INT_MONTH] = 10;
+        int_header[INT_DAY] = 16;
+        int_header[INT_HOUR] = 9;
+        int_header[INT_MINUTE] = 46;
+        int_header[INT_AZIMUTH] = azimuth;
+        lines.push(" 100 Integer-header values follow on  10 lines, Format= (10I8)".to_string());
+        lines.extend(block(&int_header.map(|v| format!("{:8}", v)), 10));
+
+        let mut real_header = [0.0; 100];
+        real_header[REAL_STATION_LAT] = 33.937;
+        real_header[REAL_STATION_LON] = -117.347;
+        real_header[REAL_SECOND] = 59.5;
+        real_header[REAL_DELTA_MSEC] = 10.0;
+        lines.push(" 100 Real-header values follow on  17 lines, Format= (1P6E13.5)".to_string());
+        lines.extend(block(&real_header.map(|v| format!("{:13.5E}", v)), 6));
+
+        lines.push("   1 Comment line(s) follow, each starting with a \"|\":".to_string());
+        lines.push("| Sensor: FBA-23".to_string());
+        lines.push(format!(
+            "{:8} acceleration pts, approx   0 secs, units={}({:02}), Format=(8F10.5)",
+            values.len(),
+            units.1,
+            units.0
+        ));
+        lines.extend(block(
+            &values
+                .iter()
+                .map(|v| format!("{:10.5}", v))
+                .collect::<Vec<_>>(),
+            8,
+        ));
+        lines.push("End-of-data for Chan  1 acceleration".to_string());
+
+        lines.join("\n") + "\n"
+    }
+
+    const VALUES: [f64; 10] = [1.0, -2.0, 3.0, -4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.5];
+
+    #[test]
+    fn test_parse_format_width() {
+        let path = Path::new("test.v2");
+        assert_eq!(parse_format_width("Format= (10I8)", path).unwrap(), 8);
+        assert_eq!(parse_format_width("Format=(8F10.5)", path).unwrap(), 10);
+        assert_eq!(parse_format_width("Format= (1P6E13.5)", path).unwrap(), 13);
+        assert!(parse_format_width("Format= ()", path).is_err());
+        assert!(parse_format_width("(8F10.5)", path).is_err());
+    }
+
+    #[test]
+    fn test_parse_cosmos_v2() {
+        let text = [
+            data_set_text(360, (4, "cm/sec2"), &VALUES),
+            data_set_text(360, (5, "cm/sec"), &VALUES[..3]),
+        ]
+        .concat();
+        let data_sets = parse_cosmos(&text, Path::new("test.v2")).unwrap();
+        assert_eq!(data_sets.len(), 2);
+
+        let acc = &data_sets[0];
+        assert_eq!(acc.network_code, "CE");
+        assert_eq!(acc.station_code, "13921");
+        assert_eq!(acc.station_lat, 33.937);
+        assert_eq!(acc.station_lon, -117.347);
+        assert_eq!(acc.orientation, Orientation::Horizontal(0.0));
+        assert_eq!(
+            acc.start_time,
+            NaiveDate::from_ymd_opt(1999, 10, 16)
+                .unwrap()
+                .and_hms_milli_opt(9, 46, 59, 500)
+                .unwrap()
+        );
+        assert_eq!(acc.delta_sec, 0.01);
+        assert_eq!(acc.unit, Unit::Gal);
+        assert_eq!(acc.values, VALUES.to_vec());
+        assert_eq!(acc.int_header.len(), 100);
+        assert_eq!(data_sets[1].unit, Unit::CentimetersPerSecond);
+        assert_eq!(data_sets[1].values, VALUES[..3].to_vec());
+    }
+
+    #[test]
+    fn test_parse_cosmos_counts() {
+        let text = data_set_text(AZIMUTH_UP, (50, "counts"), &VALUES);
+
+        assert!(matches!(
+            parse_cosmos(&text, Path::new("test.v0")),
+            Err(DataExtractionErr::InvalidHeaderValue(field, value, _))
+                if field == "units" && value == "counts"
+        ));
+    }
+
+    #[test]
+    fn test_extract_group() {
+        let dir = tempdir().unwrap();
+        let mut files = Vec::new();
+        // 時計回りに90度回転して設置され、1成分目が東、2成分目が南を向いている
+        for (name, azimuth, units) in [
+            ("13921c1.v2", 90, (2, "g")),
+            ("13921c2.v2", 180, (4, "cm/sec2")),
+            ("13921c3.v2", AZIMUTH_UP, (4, "cm/sec2")),
+        ] {
+            let path = dir.path().join(name);
+            let mut file = File::create(&path).unwrap();
+            write!(file, "{}", data_set_text(azimuth, units, &VALUES)).unwrap();
+            files.push(FileConfig {
+                path,
+                acc_axis: None,
+            });
+        }
+
+        let extractor = CosmosExtractor::new(ConversionConfig::for_test(
+            "hector",
+            From::Cosmos,
+            To::JpStera3dTxt,
+            files,
+        ));
+
+        let extracted = extractor.extract().unwrap();
+        let data = &extracted[0];
+
+        assert_eq!(data.station.code, "13921");
+        assert_eq!(data.sampling_rate, 100.0);
+        assert_eq!(data.unit, Unit::Gal);
+        for (i, v) in VALUES.iter().enumerate() {
+            assert!((data.components.ns[i] + v).abs() < 1e-9);
+            assert!((data.components.ew[i] - v * 980.665).abs() < 1e-9);
+        }
+        assert_eq!(data.components.ud, VALUES.to_vec());
+    }
+
+    #[test]
+    fn test_extract_group_misaligned() {
+        let dir = tempdir().unwrap();
+        let mut files = Vec::new();
+        // 3成分目のみ開始時刻が0.02秒(2サンプル)遅い
+        for (name, azimuth, second) in [
+            ("13921c1.v2", 0, 59.5),
+            ("13921c2.v2", 90, 59.5),
+            ("13921c3.v2", AZIMUTH_UP, 59.52),
+        ] {
+            let path = dir.path().join(name);
+            let text = data_set_text(azimuth, (4, "cm/sec2"), &VALUES)
+                .replace(&format!("{:13.5E}", 59.5), &format!("{:13.5E}", second));
+            std::fs::write(&path, text).unwrap();
+            files.push(FileConfig {
+                path,
+                acc_axis: None,
+            });
+        }
+
+        let extractor = CosmosExtractor::new(ConversionConfig::for_test(
+            "hector",
+            From::Cosmos,
+            To::JpStera3dTxt,
+            files,
+        ));
+
+        assert!(matches!(
+            &extractor.extract_group(&extractor.unextracted.group[0], 1).unwrap_err()[..],
+            [DataExtractionErr::MismatchedComponents(reason, _, _)] if reason == "start time"
+        ));
+    }
+}
@@ -0,0 +1,614 @@
+use std::{collections::BTreeMap, path::Path};
+
+use chrono::{NaiveDateTime, TimeDelta};
+use log::debug;
+
+use crate::{
+    analysis_config_file::{AccAxis, ConversionConfig, FileConfig, GroupConfig, SensorPosition},
+    error::{AnalysisErr, AppError, DataExtractionErr},
+    record::{Components, Event, Record, Station, Unit},
+};
+
+use super::{misaligned, read_to_string, remove_offset, Extractor};
+
+const DATE_TIME_FORMAT: &str = "%Y/%m/%d %H:%M:%S";
+
+// K-NET, KiK-netのロガーはRecord Timeに15秒の遅延を加えて記録するため、実際の記録開始時刻はその15秒前となる
+const RECORD_TIME_DELAY_SEC: i64 = 15;
+
+/// Header block of a K-NET/KiK-net ASCII file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KnetHeader {
+    pub origin_time: NaiveDateTime,
+    pub lat: f64,
+    pub lon: f64,
+    pub depth_km: f64,
+    pub magnitude: f64,
+    pub station_code: String,
+    pub station_lat: f64,
+    pub station_lon: f64,
+    pub station_height_m: f64,
+    pub record_time: NaiveDateTime,
+    pub sampling_freq_hz: f32,
+    pub duration_time_sec: f64,
+    pub direction: String,
+    /// Value of the Scale Factor, e.g. `7845(gal)/8223790` is `7845 / 8223790`.
+    pub scale_factor: f64,
+    pub max_acc_gal: f64,
+    pub last_correction: NaiveDateTime,
+}
+
+impl KnetHeader {
+    /// Start time of the record, with the logger delay of the Record Time removed.
+    pub fn start_time(&self) -> NaiveDateTime {
+        self.record_time - TimeDelta::seconds(RECORD_TIME_DELAY_SEC)
+    }
+}
+
+/// One component of a K-NET/KiK-net record converted to gal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KnetComponent {
+    pub header: KnetHeader,
+    pub acc_values: Vec<f64>,
+}
+
+pub struct JpNiedKnetExtractor {
+    pub unextracted: ConversionConfig,
+}
+
+impl Extractor for JpNiedKnetExtractor {
+    fn extract(&self) -> Result<Vec<Record>, Vec<AppError>> {
+        let mut extracted: Vec<Record> = Vec::new();
+        let mut errors: Vec<AppError> = Vec::new();
+
+        for (g_index, group_config) in self.unextracted.group.iter().enumerate() {
+            let id: usize = g_index + 1;
+            match self.extract_group(group_config, id) {
+                Ok(data) => extracted.extend(data),
+                Err(e) => errors.extend(e.into_iter().map(|e| AnalysisErr::from(e).into())),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(extracted)
+    }
+}
+
+impl JpNiedKnetExtractor {
+    pub fn new(unextracted: ConversionConfig) -> Self {
+        Self { unextracted }
+    }
+
+    // KiK-netの場合、地中と地表のセンサーをそれぞれ別の記録として抽出する(地中、地表の順)
+    fn extract_group(
+        &self,
+        group_config: &GroupConfig,
+        id: usize,
+    ) -> Result<Vec<Record>, Vec<DataExtractionErr>> {
+        let mut files_by_sensor: BTreeMap<Option<SensorPosition>, Vec<&FileConfig>> =
+            BTreeMap::new();
+        for file in &group_config.files {
+            files_by_sensor
+                .entry(file.sensor_position())
+                .or_default()
+                .push(file);
+        }
+
+        let mut extracted: Vec<Record> = Vec::new();
+        let mut errors: Vec<DataExtractionErr> = Vec::new();
+
+        for (sensor, files) in files_by_sensor {
+            match self.extract_record(&files, sensor, id) {
+                Ok(data) => {
+                    if let Some(sensor) = sensor {
+                        debug!("The {} sensor record has been extracted.", sensor.as_str());
+                    }
+                    extracted.push(data);
+                }
+                Err(e) => errors.extend(e),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(extracted)
+    }
+
+    // NS, EW, UDの3ファイルを1つのComponentsにまとめる
+    fn extract_record(
+        &self,
+        files: &[&FileConfig],
+        sensor_position: Option<SensorPosition>,
+        id: usize,
+    ) -> Result<Record, Vec<DataExtractionErr>> {
+        let mut errors: Vec<DataExtractionErr> = Vec::new();
+        let mut ns: Option<KnetComponent> = None;
+        let mut ew: Option<KnetComponent> = None;
+        let mut ud: Option<KnetComponent> = None;
+
+        for file in files {
+            let component = match parse_knet_file(&file.path) {
+                Ok(component) => component,
+                Err(e) => {
+                    errors.push(e);
+                    continue;
+                }
+            };
+
+            match &file.acc_axis {
+                Some(AccAxis::Ns) => ns = Some(component),
+                Some(AccAxis::Ew) => ew = Some(component),
+                Some(AccAxis::Ud) => ud = Some(component),
+                None => errors.push(DataExtractionErr::RequiredAccAxis(file.path.clone())),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        let name = &self.unextracted.name;
+        let (Some(ns), Some(ew), Some(ud)) = (ns, ew, ud) else {
+            return Err(vec![DataExtractionErr::MismatchedComponents(
+                "ns, ew and ud are required".to_string(),
+                name.to_string(),
+                id,
+            )]);
+        };
+
+        validate_components(&ns, &ew, &ud, name, id)?;
+
+        let header = ns.header;
+        let components = Components {
+            ns: ns.acc_values,
+            ew: ew.acc_values,
+            ud: ud.acc_values,
+        };
+
+        Ok(Record {
+            station: Station {
+                code: header.station_code.clone(),
+                lat: Some(header.station_lat),
+                lon: Some(header.station_lon),
+                sensor_position,
+            },
+            event: Some(Event {
+                origin_time: Some(header.origin_time),
+                lat: Some(header.lat),
+                lon: Some(header.lon),
+                depth_km: Some(header.depth_km),
+                magnitude: Some(header.magnitude),
+            }),
+            start_time: header.start_time(),
+            sampling_rate: header.sampling_freq_hz,
+            unit: Unit::Gal,
+            components,
+            processing: Vec::new(),
+        })
+    }
+}
+
+// 同じグループの3成分が同一の観測記録であるかを確認する
+fn validate_components(
+    ns: &KnetComponent,
+    ew: &KnetComponent,
+    ud: &KnetComponent,
+    name: &str,
+    id: usize,
+) -> Result<(), Vec<DataExtractionErr>> {
+    let mut errors: Vec<DataExtractionErr> = Vec::new();
+    let components = [ns, ew, ud];
+
+    if components
+        .iter()
+        .any(|c| c.header.station_code != ns.header.station_code)
+    {
+        errors.push(DataExtractionErr::MismatchedComponents(
+            "Station Code".to_string(),
+            name.to_string(),
+            id,
+        ));
+    }
+
+    if components
+        .iter()
+        .any(|c| c.header.sampling_freq_hz != ns.header.sampling_freq_hz)
+    {
+        errors.push(DataExtractionErr::MismatchedComponents(
+            "Sampling Freq(Hz)".to_string(),
+            name.to_string(),
+            id,
+        ));
+    }
+
+    let start_times = components.map(|c| c.header.start_time());
+    if misaligned(&start_times, ns.header.sampling_freq_hz as f64) {
+        errors.push(DataExtractionErr::MismatchedComponents(
+            "Record Time".to_string(),
+            name.to_string(),
+            id,
+        ));
+    }
+
+    if components
+        .iter()
+        .any(|c| c.acc_values.len() != ns.acc_values.len())
+    {
+        errors.push(DataExtractionErr::MismatchedComponents(
+            "number of data".to_string(),
+            name.to_string(),
+            id,
+        ));
+    }
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    Ok(())
+}
+
+/// Reads a K-NET/KiK-net ASCII file and converts the counts to gal.
+pub fn parse_knet_file(path: &Path) -> Result<KnetComponent, DataExtractionErr> {
+    let contents = read_to_string(path)?;
+    parse_knet(&contents, path)
+}
+
+/// Parses the contents of a K-NET/KiK-net ASCII file.
+///
+/// The counts of the data block are multiplied by the Scale Factor, and then the mean offset is removed.
+pub fn parse_knet(contents: &str, path: &Path) -> Result<KnetComponent, DataExtractionErr> {
+    let lines: Vec<&str> = contents.lines().collect();
+
+    // ヘッダーは"Memo."の行で終わり、その次の行からデータが始まる
+    let data_start = lines
+        .iter()
+        .position(|line| line.trim_start().starts_with("Memo."))
+        .map(|pos| pos + 1)
+        .ok_or_else(|| DataExtractionErr::MissingHeaderField("Memo.".to_string(), path.into()))?;
+    let header_lines = &lines[..data_start];
+
+    let field = |key: &str| header_value(header_lines, key, path);
+    let sampling_freq = field("Sampling Freq(Hz)")?;
+    let sampling_freq_hz: f32 = parse_number(
+        "Sampling Freq(Hz)",
+        sampling_freq.trim_end_matches("Hz"),
+        path,
+    )?;
+    if !(sampling_freq_hz > 0.0 && sampling_freq_hz.is_finite()) {
+        return Err(DataExtractionErr::InvalidHeaderValue(
+            "Sampling Freq(Hz)".to_string(),
+            sampling_freq.to_string(),
+            path.into(),
+        ));
+    }
+
+    let header = KnetHeader {
+        origin_time: parse_date_time("Origin Time", field("Origin Time")?, path)?,
+        lat: parse_number("Lat.", field("Lat.")?, path)?,
+        lon: parse_number("Long.", field("Long.")?, path)?,
+        depth_km: parse_number("Depth. (km)", field("Depth. (km)")?, path)?,
+        magnitude: parse_number("Mag.", field("Mag.")?, path)?,
+        station_code: field("Station Code")?.to_string(),
+        station_lat: parse_number("Station Lat.", field("Station Lat.")?, path)?,
+        station_lon: parse_number("Station Long.", field("Station Long.")?, path)?,
+        station_height_m: parse_number("Station Height(m)", field("Station Height(m)")?, path)?,
+        record_time: parse_date_time("Record Time", field("Record Time")?, path)?,
+        sampling_freq_hz,
+        duration_time_sec: parse_number("Duration Time(s)", field("Duration Time(s)")?, path)?,
+        direction: field("Dir.")?.to_string(),
+        scale_factor: parse_scale_factor(field("Scale Factor")?, path)?,
+        max_acc_gal: parse_number("Max. Acc. (gal)", field("Max. Acc. (gal)")?, path)?,
+        last_correction: parse_date_time("Last Correction", field("Last Correction")?, path)?,
+    };
+
+    let mut acc_values: Vec<f64> = Vec::new();
+    for value in lines[data_start..]
+        .iter()
+        .flat_map(|l| l.split_whitespace())
+    {
+        let count: f64 = value
+            .parse()
+            .map_err(|_| DataExtractionErr::InvalidDataValue(value.to_string(), path.into()))?;
+        acc_values.push(count * header.scale_factor);
+    }
+    remove_offset(&mut acc_values);
+
+    Ok(KnetComponent { header, acc_values })
+}
+
+// 行頭がキーと一致する行から値を取り出す
+fn header_value<'a>(
+    header_lines: &[&'a str],
+    key: &str,
+    path: &Path,
+) -> Result<&'a str, DataExtractionErr> {
+    header_lines
+        .iter()
+        .find_map(|line| line.strip_prefix(key))
+        .map(str::trim)
+        .ok_or_else(|| DataExtractionErr::MissingHeaderField(key.to_string(), path.into()))
+}
+
+fn parse_number<T: std::str::FromStr>(
+    key: &str,
+    value: &str,
+    path: &Path,
+) -> Result<T, DataExtractionErr> {
+    value.trim().parse().map_err(|_| {
+        DataExtractionErr::InvalidHeaderValue(key.to_string(), value.to_string(), path.into())
+    })
+}
+
+fn parse_date_time(
+    key: &str,
+    value: &str,
+    path: &Path,
+) -> Result<NaiveDateTime, DataExtractionErr> {
+    NaiveDateTime::parse_from_str(value, DATE_TIME_FORMAT).map_err(|_| {
+        DataExtractionErr::InvalidHeaderValue(key.to_string(), value.to_string(), path.into())
+    })
+}
+
+// e.g. "7845(gal)/8223790" -> 7845 / 8223790
+fn parse_scale_factor(value: &str, path: &Path) -> Result<f64, DataExtractionErr> {
+    let invalid = || {
+        DataExtractionErr::InvalidHeaderValue(
+            "Scale Factor".to_string(),
+            value.to_string(),
+            path.into(),
+        )
+    };
+
+    let (numerator, denominator) = value.split_once('/').ok_or_else(invalid)?;
+    let numerator: f64 = numerator
+        .trim()
+        .trim_end_matches("(gal)")
+        .parse()
+        .map_err(|_| invalid())?;
+    let denominator: f64 = denominator.trim().parse().map_err(|_| invalid())?;
+
+    if denominator == 0.0 {
+        return Err(invalid());
+    }
+
+    Ok(numerator / denominator)
+}
+
+/// This module contains unit tests for the K-NET/KiK-net ASCII parser.
+///
+/// # Test Categories
+///
+/// - `test_parse_knet_header`: Tests parsing of every header field
+/// - `test_parse_knet_data`: Tests the scale factor and the removal of the mean offset
+/// - `test_parse_knet_invalid_scale_factor`: Tests rejection of a malformed Scale Factor
+/// - `test_parse_knet_missing_header_field`: Tests handling of a missing header field
+/// - `test_parse_knet_invalid_sampling_freq`: Tests rejection of a non-positive Sampling Freq
+/// - `test_extract_group`: Tests merging of the NS, EW and UD files into a single record
+/// - `test_extract_group_kik`: Tests extraction of the borehole and surface sensors of KiK-net
+/// - `test_extract_group_misaligned`: Tests rejection of files whose Record Time differs by more than half a sample
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis_config_file::{From, To};
+    use chrono::NaiveDate;
+    use std::{fs::File, io::Write, path::PathBuf};
+    use tempfile::tempdir;
+
+    fn knet_text(dir: &str, scale_factor: &str, data: &str) -> String {
+        format!(
+            "Origin Time       2024/01/01 16:10:00
+Lat.              37.495
+Long.             137.270
+Depth. (km)       16
+Mag.              7.6
+Station Code      ISK005
+Station Lat.      37.3003
+Station Long.     136.7698
+Station Height(m) 0
+Record Time       2024/01/01 16:10:24
+Sampling Freq(Hz) 100Hz
+Duration Time(s)  0.08
+Dir.              {dir}
+Scale Factor      {scale_factor}
+Max. Acc. (gal)   0.007
+Last Correction   2024/01/01 16:10:09
+Memo.
+{data}"
+        )
+    }
+
+    #[test]
+    fn test_parse_knet_header() {
+        let text = knet_text("N-S", "7845(gal)/8223790", "1 2 3 4 5 6 7 8\n");
+        let header = parse_knet(&text, Path::new("test.NS")).unwrap().header;
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+
+        assert_eq!(header.origin_time, date.and_hms_opt(16, 10, 0).unwrap());
+        assert_eq!(header.lat, 37.495);
+        assert_eq!(header.lon, 137.270);
+        assert_eq!(header.depth_km, 16.0);
+        assert_eq!(header.magnitude, 7.6);
+        assert_eq!(header.station_code, "ISK005");
+        assert_eq!(header.station_lat, 37.3003);
+        assert_eq!(header.station_lon, 136.7698);
+        assert_eq!(header.station_height_m, 0.0);
+        assert_eq!(header.sampling_freq_hz, 100.0);
+        assert_eq!(header.duration_time_sec, 0.08);
+        assert_eq!(header.direction, "N-S");
+        assert_eq!(header.scale_factor, 7845.0 / 8223790.0);
+        assert_eq!(header.max_acc_gal, 0.007);
+        assert_eq!(header.last_correction, date.and_hms_opt(16, 10, 9).unwrap());
+        assert_eq!(header.start_time(), date.and_hms_opt(16, 10, 9).unwrap());
+    }
+
+    #[test]
+    fn test_parse_knet_data() {
+        let text = knet_text("E-W", "1000(gal)/100", "10 20 30 40 50 60 70 80\n90\n");
+        let component = parse_knet(&text, Path::new("test.EW")).unwrap();
+
+        // 平均(50 * 10 = 500 gal)が取り除かれている
+        let expected: Vec<f64> = (1..=9).map(|v| (v as f64 * 10.0 - 50.0) * 10.0).collect();
+        assert_eq!(component.acc_values, expected);
+    }
+
+    #[test]
+    fn test_parse_knet_invalid_scale_factor() {
+        let path = Path::new("test.UD");
+        let text = knet_text("U-D", "7845(gal)", "1 2 3\n");
+
+        assert_eq!(
+            parse_knet(&text, path).unwrap_err(),
+            DataExtractionErr::InvalidHeaderValue(
+                "Scale Factor".to_string(),
+                "7845(gal)".to_string(),
+                path.to_path_buf()
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_knet_missing_header_field() {
+        let path = Path::new("test.NS");
+        let text = knet_text("N-S", "7845(gal)/8223790", "1 2 3\n").replace("Mag.", "");
+
+        assert_eq!(
+            parse_knet(&text, path).unwrap_err(),
+            DataExtractionErr::MissingHeaderField("Mag.".to_string(), path.to_path_buf())
+        );
+    }
+
+    #[test]
+    fn test_parse_knet_invalid_sampling_freq() {
+        let path = Path::new("test.NS");
+        let text = knet_text("N-S", "7845(gal)/8223790", "1 2 3\n")
+            .replace("Sampling Freq(Hz) 100Hz", "Sampling Freq(Hz) 0Hz");
+
+        assert_eq!(
+            parse_knet(&text, path).unwrap_err(),
+            DataExtractionErr::InvalidHeaderValue(
+                "Sampling Freq(Hz)".to_string(),
+                "0Hz".to_string(),
+                path.to_path_buf()
+            )
+        );
+    }
+
+    #[test]
+    fn test_extract_group() {
+        let dir = tempdir().unwrap();
+        let mut files: Vec<FileConfig> = Vec::new();
+
+        for (ext, dir_name, acc_axis, data) in [
+            ("NS", "N-S", AccAxis::Ns, "1 3\n"),
+            ("EW", "E-W", AccAxis::Ew, "2 6\n"),
+            ("UD", "U-D", AccAxis::Ud, "3 9\n"),
+        ] {
+            let path: PathBuf = dir.path().join(format!("ISK0052401011610.{}", ext));
+            let mut file = File::create(&path).unwrap();
+            write!(file, "{}", knet_text(dir_name, "1(gal)/1", data)).unwrap();
+            files.push(FileConfig {
+                path,
+                acc_axis: Some(acc_axis),
+            });
+        }
+
+        let extractor = JpNiedKnetExtractor::new(ConversionConfig::for_test(
+            "noto",
+            From::JpNiedKnet,
+            To::JpJmaCsv,
+            files,
+        ));
+
+        let extracted = extractor.extract().unwrap();
+        assert_eq!(extracted.len(), 1);
+
+        let data = &extracted[0];
+        assert_eq!(data.station.code, "ISK005");
+        assert_eq!(data.station.lat, Some(37.3003));
+        assert_eq!(data.station.lon, Some(136.7698));
+        assert_eq!(data.event.as_ref().unwrap().magnitude, Some(7.6));
+        assert_eq!(data.sampling_rate, 100.0);
+        assert_eq!(data.components.ns, vec![-1.0, 1.0]);
+        assert_eq!(data.components.ew, vec![-2.0, 2.0]);
+        assert_eq!(data.components.ud, vec![-3.0, 3.0]);
+    }
+
+    #[test]
+    fn test_extract_group_kik() {
+        let dir = tempdir().unwrap();
+        let mut files: Vec<FileConfig> = Vec::new();
+
+        for (ext, acc_axis, data) in [
+            ("NS2", AccAxis::Ns, "10 30\n"),
+            ("EW2", AccAxis::Ew, "10 30\n"),
+            ("UD2", AccAxis::Ud, "10 30\n"),
+            ("NS1", AccAxis::Ns, "1 3\n"),
+            ("EW1", AccAxis::Ew, "1 3\n"),
+            ("UD1", AccAxis::Ud, "1 3\n"),
+        ] {
+            let path: PathBuf = dir.path().join(format!("IBRH130103111446.{}", ext));
+            let mut file = File::create(&path).unwrap();
+            write!(file, "{}", knet_text("N-S", "1(gal)/1", data)).unwrap();
+            files.push(FileConfig {
+                path,
+                acc_axis: Some(acc_axis),
+            });
+        }
+
+        let extractor = JpNiedKnetExtractor::new(ConversionConfig::for_test(
+            "tohoku",
+            From::JpNiedKik,
+            To::JpStera3dTxt,
+            files,
+        ));
+
+        let extracted = extractor.extract().unwrap();
+        let ns_values: Vec<Vec<f64>> = extracted
+            .iter()
+            .map(|data| data.components.ns.clone())
+            .collect();
+
+        // 地中、地表の順に抽出される
+        assert_eq!(ns_values, vec![vec![-1.0, 1.0], vec![-10.0, 10.0]]);
+    }
+
+    #[test]
+    fn test_extract_group_misaligned() {
+        let dir = tempdir().unwrap();
+        let mut files: Vec<FileConfig> = Vec::new();
+
+        // UDのみRecord Timeが1秒遅い
+        for (ext, dir_name, acc_axis, record_time) in [
+            ("NS", "N-S", AccAxis::Ns, "2024/01/01 16:10:24"),
+            ("EW", "E-W", AccAxis::Ew, "2024/01/01 16:10:24"),
+            ("UD", "U-D", AccAxis::Ud, "2024/01/01 16:10:25"),
+        ] {
+            let path: PathBuf = dir.path().join(format!("ISK0052401011610.{}", ext));
+            let text = knet_text(dir_name, "1(gal)/1", "1 3\n")
+                .replace("2024/01/01 16:10:24", record_time);
+            std::fs::write(&path, text).unwrap();
+            files.push(FileConfig {
+                path,
+                acc_axis: Some(acc_axis),
+            });
+        }
+
+        let extractor = JpNiedKnetExtractor::new(ConversionConfig::for_test(
+            "noto",
+            From::JpNiedKnet,
+            To::JpJmaCsv,
+            files,
+        ));
+
+        assert!(matches!(
+            &extractor.extract_group(&extractor.unextracted.group[0], 1).unwrap_err()[..],
+            [DataExtractionErr::MismatchedComponents(reason, _, _)] if reason == "Record Time"
+        ));
+    }
+}
@@ -0,0 +1,1050 @@
+use std::{collections::BTreeMap, fmt, path::Path};
+
+use chrono::{NaiveDate, NaiveDateTime, TimeDelta};
+use log::debug;
+
+use crate::{
+    analysis_config_file::{ConversionConfig, GroupConfig},
+    byte_order::{read_f32, read_i32, Endian},
+    error::{AnalysisErr, AppError, DataExtractionErr},
+    record::{Components, Record, Station, Unit},
+};
+
+use super::{orient_components, Extractor, Orientation};
+
+/// Size of the fixed section of the data header in bytes.
+pub const FIXED_HEADER_SIZE: usize = 48;
+
+// 固定ヘッダーのバイト位置
+const QUALITY: usize = 6;
+const STATION: (usize, usize) = (8, 5);
+const LOCATION: (usize, usize) = (13, 2);
+const CHANNEL: (usize, usize) = (15, 3);
+const NETWORK: (usize, usize) = (18, 2);
+const YEAR: usize = 20;
+const DAY: usize = 22;
+const HOUR: usize = 24;
+const MINUTE: usize = 25;
+const SECOND: usize = 26;
+const FRACT: usize = 28;
+const NUM_SAMPLES: usize = 30;
+const RATE_FACTOR: usize = 32;
+const RATE_MULTIPLIER: usize = 34;
+const ACTIVITY_FLAGS: usize = 36;
+const TIME_CORRECTION: usize = 40;
+const DATA_OFFSET: usize = 44;
+const BLOCKETTE_OFFSET: usize = 46;
+
+const QUALITY_INDICATORS: [u8; 4] = [b'D', b'R', b'Q', b'M'];
+
+// 活動フラグのうち、時刻補正が適用済みであることを示すビット
+const TIME_CORRECTION_APPLIED: u8 = 0x02;
+
+// ブロケットの種類
+const BLOCKETTE_100: u16 = 100;
+const BLOCKETTE_1000: u16 = 1000;
+const BLOCKETTE_1001: u16 = 1001;
+
+// レコード長(2のべき乗)の指数の範囲
+const RECORD_LENGTH_EXPONENTS: std::ops::RangeInclusive<u8> = 7..=16;
+
+// Steimの1フレームは16ワードで、先頭のワードが各ワードの種類を示す
+const WORDS_PER_FRAME: usize = 16;
+
+/// Encoding of the data given by Blockette 1000.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Int32,
+    Float32,
+    Steim1,
+    Steim2,
+}
+
+impl Encoding {
+    pub fn from_code(code: u8) -> Option<Self> {
+        match code {
+            3 => Some(Encoding::Int32),
+            4 => Some(Encoding::Float32),
+            10 => Some(Encoding::Steim1),
+            11 => Some(Encoding::Steim2),
+            _ => None,
+        }
+    }
+}
+
+/// Channel identified by the network, station, location and channel codes(NSLC).
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Nslc {
+    pub network: String,
+    pub station: String,
+    pub location: String,
+    pub channel: String,
+}
+
+impl fmt::Display for Nslc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}.{}.{}.{}",
+            self.network, self.station, self.location, self.channel
+        )
+    }
+}
+
+/// One data record of a miniSEED file, whose samples are in counts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MseedRecord {
+    pub nslc: Nslc,
+    /// Time of the first sample, to which the time correction and the microseconds of Blockette 1001 are applied.
+    pub start_time: NaiveDateTime,
+    pub sampling_rate: f64,
+    pub encoding: Encoding,
+    pub samples: Vec<f64>,
+}
+
+impl MseedRecord {
+    /// Time at which the sample following the last one of the record is expected.
+    pub fn end_time(&self) -> NaiveDateTime {
+        self.start_time + seconds(self.samples.len() as f64 / self.sampling_rate)
+    }
+}
+
+/// Continuous samples of one channel merged from its records.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MseedTrace {
+    pub nslc: Nslc,
+    pub start_time: NaiveDateTime,
+    pub sampling_rate: f64,
+    pub samples: Vec<f64>,
+}
+
+pub struct MiniSeedExtractor {
+    pub unextracted: ConversionConfig,
+}
+
+impl Extractor for MiniSeedExtractor {
+    fn extract(&self) -> Result<Vec<Record>, Vec<AppError>> {
+        let mut extracted: Vec<Record> = Vec::new();
+        let mut errors: Vec<AppError> = Vec::new();
+
+        for (g_index, group_config) in self.unextracted.group.iter().enumerate() {
+            let id: usize = g_index + 1;
+            match self.extract_group(group_config, id) {
+                Ok(data) => extracted.push(data),
+                Err(e) => errors.extend(e.into_iter().map(|e| AnalysisErr::from(e).into())),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(extracted)
+    }
+}
+
+impl MiniSeedExtractor {
+    pub fn new(unextracted: ConversionConfig) -> Self {
+        Self { unextracted }
+    }
+
+    // グループ内の全ファイルのレコードをチャンネルごとにまとめ、チャンネルコードからNS/EW/UDに振り分ける
+    fn extract_group(
+        &self,
+        group_config: &GroupConfig,
+        id: usize,
+    ) -> Result<Record, Vec<DataExtractionErr>> {
+        let mut errors: Vec<DataExtractionErr> = Vec::new();
+        let mut records: Vec<(MseedRecord, &Path)> = Vec::new();
+
+        for file in &group_config.files {
+            match parse_mini_seed_file(&file.path) {
+                Ok(parsed) => records.extend(parsed.into_iter().map(|r| (r, file.path.as_path()))),
+                Err(e) => errors.push(e),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        let traces = merge_records(records)?;
+
+        let name = &self.unextracted.name;
+        let mismatched = |reason: &str| {
+            vec![DataExtractionErr::MismatchedComponents(
+                reason.to_string(),
+                name.to_string(),
+                id,
+            )]
+        };
+
+        let Some(first) = traces.first() else {
+            return Err(mismatched("no components"));
+        };
+        if traces.iter().any(|t| {
+            (&t.nslc.network, &t.nslc.station) != (&first.nslc.network, &first.nslc.station)
+        }) {
+            return Err(mismatched("network and station codes"));
+        }
+        if traces
+            .iter()
+            .any(|t| t.sampling_rate != first.sampling_rate)
+        {
+            return Err(mismatched("sampling rate"));
+        }
+
+        // 各チャンネルの開始、終了時刻が異なる場合、共通する時間帯の外側を黙って切り捨てずにエラーとする
+        let sampling_rate = first.sampling_rate;
+        let start_time = traces
+            .iter()
+            .map(|t| t.start_time)
+            .max()
+            .unwrap_or_default();
+        let offsets: Vec<usize> = traces
+            .iter()
+            .map(|t| (elapsed_sec(t.start_time, start_time) * sampling_rate).round() as usize)
+            .collect();
+        let len = traces
+            .iter()
+            .zip(&offsets)
+            .map(|(t, &offset)| t.samples.len().saturating_sub(offset))
+            .min()
+            .unwrap_or_default();
+        if len == 0 {
+            return Err(mismatched("no overlapping time"));
+        }
+        let unshared: Vec<DataExtractionErr> = traces
+            .iter()
+            .filter(|t| t.samples.len() > len)
+            .map(|t| {
+                DataExtractionErr::UnsharedSpan(
+                    t.nslc.to_string(),
+                    format!("{:.4}", (t.samples.len() - len) as f64 / sampling_rate),
+                    name.to_string(),
+                    id,
+                )
+            })
+            .collect();
+        if !unshared.is_empty() {
+            return Err(unshared);
+        }
+
+        let mut oriented: Vec<(Orientation, Vec<f64>)> = Vec::new();
+        for trace in &traces {
+            let Some(orientation) = Orientation::from_channel_code(&trace.nslc.channel) else {
+                return Err(mismatched(&format!("channel code '{}'", trace.nslc)));
+            };
+            debug!(
+                "The channel '{}' has been assigned to {:?}.",
+                trace.nslc, orientation
+            );
+            oriented.push((orientation, trace.samples.clone()));
+        }
+
+        let mut components: Components =
+            orient_components(oriented).map_err(|reason| mismatched(&reason))?;
+
+        // 設定ファイルの感度でカウント値をgalに変換する(from = "mini_seed"では感度の設定が必須)
+        let gal_per_count = match self.unextracted.sensitivity {
+            Some(sensitivity) => sensitivity
+                .gal_per_count()
+                .ok_or_else(|| mismatched("sensitivity in a unit of acceleration"))?,
+            None => 1.0,
+        };
+        for values in [&mut components.ns, &mut components.ew, &mut components.ud] {
+            values.iter_mut().for_each(|v| *v *= gal_per_count);
+        }
+
+        // miniSEEDには観測点の座標が含まれないため、緯度経度は不明とする
+        Ok(Record {
+            station: Station {
+                code: first.nslc.station.clone(),
+                lat: None,
+                lon: None,
+                sensor_position: None,
+            },
+            event: None,
+            start_time,
+            sampling_rate: sampling_rate as f32,
+            unit: Unit::Gal,
+            components,
+            processing: Vec::new(),
+        })
+    }
+}
+
+/// Merges the records of each channel in order of time.
+///
+/// A gap or an overlap of more than half a sample between consecutive records is reported as
+/// `DataExtractionErr::Discontinuity` instead of being concatenated.
+pub fn merge_records(
+    mut records: Vec<(MseedRecord, &Path)>,
+) -> Result<Vec<MseedTrace>, Vec<DataExtractionErr>> {
+    let mut errors: Vec<DataExtractionErr> = Vec::new();
+    let mut by_channel: BTreeMap<Nslc, Vec<(MseedRecord, &Path)>> = BTreeMap::new();
+
+    records.sort_by_key(|(record, _)| record.start_time);
+    for (record, path) in records {
+        by_channel
+            .entry(record.nslc.clone())
+            .or_default()
+            .push((record, path));
+    }
+
+    let mut traces: Vec<MseedTrace> = Vec::new();
+    for (nslc, records) in by_channel {
+        let (first, _) = &records[0];
+        let mut trace = MseedTrace {
+            nslc,
+            start_time: first.start_time,
+            sampling_rate: first.sampling_rate,
+            samples: Vec::new(),
+        };
+        let tolerance_sec = 0.5 / trace.sampling_rate;
+        let mut end_time = first.start_time;
+
+        for (record, path) in records {
+            if record.sampling_rate != trace.sampling_rate {
+                errors.push(DataExtractionErr::InvalidHeaderValue(
+                    format!("sample rate of '{}'", trace.nslc),
+                    record.sampling_rate.to_string(),
+                    path.to_path_buf(),
+                ));
+                continue;
+            }
+
+            let difference_sec = elapsed_sec(end_time, record.start_time);
+            if difference_sec.abs() > tolerance_sec {
+                let kind = if difference_sec > 0.0 {
+                    "gap"
+                } else {
+                    "overlap"
+                };
+                errors.push(DataExtractionErr::Discontinuity(
+                    kind.to_string(),
+                    format!("{:.4}", difference_sec.abs()),
+                    trace.nslc.to_string(),
+                    record
+                        .start_time
+                        .format("%Y-%m-%dT%H:%M:%S%.6f")
+                        .to_string(),
+                    path.to_path_buf(),
+                ));
+            }
+
+            end_time = record.end_time();
+            trace.samples.extend(record.samples);
+        }
+
+        traces.push(trace);
+    }
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    Ok(traces)
+}
+
+/// Reads the data records of a miniSEED file.
+pub fn parse_mini_seed_file(path: &Path) -> Result<Vec<MseedRecord>, DataExtractionErr> {
+    let bytes =
+        std::fs::read(path).map_err(|e| DataExtractionErr::Io(path.to_path_buf(), e.into()))?;
+    parse_mini_seed(&bytes, path)
+}
+
+/// Parses the records of a miniSEED file, skipping those without samples such as log records.
+pub fn parse_mini_seed(bytes: &[u8], path: &Path) -> Result<Vec<MseedRecord>, DataExtractionErr> {
+    let mut records: Vec<MseedRecord> = Vec::new();
+    let mut offset = 0;
+
+    // 末尾がゼロで埋められている場合は読み飛ばす
+    while bytes[offset..].iter().any(|&b| b != 0) {
+        let (record, record_length) = parse_record(&bytes[offset..], path)?;
+        records.extend(record);
+        offset += record_length;
+    }
+
+    Ok(records)
+}
+
+/// Parses one record and returns it with its length in bytes, which is given by Blockette 1000.
+///
+/// The byte order of the header is detected from the year of the start time, and that of the data is given by
+/// Blockette 1000.
+pub fn parse_record(
+    bytes: &[u8],
+    path: &Path,
+) -> Result<(Option<MseedRecord>, usize), DataExtractionErr> {
+    if bytes.len() < FIXED_HEADER_SIZE {
+        return Err(DataExtractionErr::InvalidStructure(path.to_path_buf()));
+    }
+
+    let invalid = |key: &str, value: String| {
+        DataExtractionErr::InvalidHeaderValue(key.to_string(), value, path.into())
+    };
+
+    if !QUALITY_INDICATORS.contains(&bytes[QUALITY]) {
+        return Err(invalid(
+            "data quality indicator",
+            (bytes[QUALITY] as char).to_string(),
+        ));
+    }
+
+    // 開始時刻の年と通日が妥当な値として読める方のバイトオーダーを採用する
+    let endian = [Endian::Big, Endian::Little]
+        .into_iter()
+        .find(|&endian| {
+            (1900..=2100).contains(&read_u16(bytes, YEAR, endian))
+                && (1..=366).contains(&read_u16(bytes, DAY, endian))
+        })
+        .ok_or_else(|| invalid("year", read_u16(bytes, YEAR, Endian::Big).to_string()))?;
+
+    let mut blockette = read_u16(bytes, BLOCKETTE_OFFSET, endian) as usize;
+    let mut blockette_1000: Option<(u8, u8, u8)> = None;
+    let mut actual_rate: Option<f32> = None;
+    let mut microseconds: i8 = 0;
+
+    while blockette != 0 {
+        if blockette < FIXED_HEADER_SIZE || blockette + 8 > bytes.len() {
+            return Err(DataExtractionErr::InvalidStructure(path.to_path_buf()));
+        }
+
+        match read_u16(bytes, blockette, endian) {
+            BLOCKETTE_100 => actual_rate = Some(read_f32(bytes, blockette + 4, endian)),
+            BLOCKETTE_1000 => {
+                blockette_1000 = Some((
+                    bytes[blockette + 4],
+                    bytes[blockette + 5],
+                    bytes[blockette + 6],
+                ))
+            }
+            BLOCKETTE_1001 => microseconds = bytes[blockette + 5] as i8,
+            _ => {}
+        }
+
+        // 次のブロケットは後方にのみ存在する
+        let next = read_u16(bytes, blockette + 2, endian) as usize;
+        if next != 0 && next <= blockette {
+            return Err(DataExtractionErr::InvalidStructure(path.to_path_buf()));
+        }
+        blockette = next;
+    }
+
+    let Some((encoding_code, word_order, exponent)) = blockette_1000 else {
+        return Err(DataExtractionErr::MissingHeaderField(
+            "Blockette 1000".to_string(),
+            path.into(),
+        ));
+    };
+    if !RECORD_LENGTH_EXPONENTS.contains(&exponent) {
+        return Err(invalid("record length", format!("2^{exponent}")));
+    }
+    let record_length = 1_usize << exponent;
+    if bytes.len() < record_length {
+        return Err(DataExtractionErr::InvalidStructure(path.to_path_buf()));
+    }
+
+    let num_samples = read_u16(bytes, NUM_SAMPLES, endian) as usize;
+    let sampling_rate = actual_rate.map(f64::from).unwrap_or_else(|| {
+        sampling_rate(
+            read_i16(bytes, RATE_FACTOR, endian),
+            read_i16(bytes, RATE_MULTIPLIER, endian),
+        )
+    });
+    if num_samples == 0 || sampling_rate <= 0.0 {
+        return Ok((None, record_length));
+    }
+
+    let encoding = Encoding::from_code(encoding_code)
+        .ok_or_else(|| invalid("encoding", encoding_code.to_string()))?;
+
+    let nslc = Nslc {
+        network: read_code(bytes, NETWORK),
+        station: read_code(bytes, STATION),
+        location: read_code(bytes, LOCATION),
+        channel: read_code(bytes, CHANNEL),
+    };
+
+    let (year, day) = (read_u16(bytes, YEAR, endian), read_u16(bytes, DAY, endian));
+    let (hour, minute, second) = (bytes[HOUR], bytes[MINUTE], bytes[SECOND]);
+    let fract = read_u16(bytes, FRACT, endian);
+    let mut start_time = NaiveDate::from_yo_opt(year as i32, day as u32)
+        .and_then(|date| date.and_hms_opt(hour as u32, minute as u32, second as u32))
+        .ok_or_else(|| {
+            invalid(
+                "start time",
+                format!("{year},{day:03},{hour:02}:{minute:02}:{second:02}.{fract:04}"),
+            )
+        })?
+        + TimeDelta::microseconds(fract as i64 * 100 + microseconds as i64);
+    if bytes[ACTIVITY_FLAGS] & TIME_CORRECTION_APPLIED == 0 {
+        start_time +=
+            TimeDelta::microseconds(read_i32(bytes, TIME_CORRECTION, endian) as i64 * 100);
+    }
+
+    let data_offset = read_u16(bytes, DATA_OFFSET, endian) as usize;
+    if !(FIXED_HEADER_SIZE..=record_length).contains(&data_offset) {
+        return Err(invalid("beginning of data", data_offset.to_string()));
+    }
+    let data_endian = if word_order == 0 {
+        Endian::Little
+    } else {
+        Endian::Big
+    };
+    let samples = decode(
+        &bytes[data_offset..record_length],
+        num_samples,
+        encoding,
+        data_endian,
+    )
+    .map_err(|reason| {
+        DataExtractionErr::InvalidDataValue(
+            format!("{nslc} at {start_time}: {reason}"),
+            path.into(),
+        )
+    })?;
+
+    Ok((
+        Some(MseedRecord {
+            nslc,
+            start_time,
+            sampling_rate,
+            encoding,
+            samples,
+        }),
+        record_length,
+    ))
+}
+
+/// Returns the sampling rate(Hz) given by the sample rate factor and multiplier of the fixed header.
+pub(crate) fn sampling_rate(factor: i16, multiplier: i16) -> f64 {
+    let (factor, multiplier) = (factor as f64, multiplier as f64);
+
+    match (factor > 0.0, multiplier > 0.0) {
+        _ if factor == 0.0 || multiplier == 0.0 => 0.0,
+        (true, true) => factor * multiplier,
+        (true, false) => -factor / multiplier,
+        (false, true) => -multiplier / factor,
+        (false, false) => 1.0 / (factor * multiplier),
+    }
+}
+
+/// Decodes `num_samples` samples of the data section.
+pub fn decode(
+    data: &[u8],
+    num_samples: usize,
+    encoding: Encoding,
+    endian: Endian,
+) -> Result<Vec<f64>, String> {
+    match encoding {
+        Encoding::Int32 | Encoding::Float32 => {
+            if data.len() < num_samples * 4 {
+                return Err(format!("{num_samples} samples exceed the record"));
+            }
+            Ok((0..num_samples)
+                .map(|i| match encoding {
+                    Encoding::Int32 => read_i32(data, i * 4, endian) as f64,
+                    _ => read_f32(data, i * 4, endian) as f64,
+                })
+                .collect())
+        }
+        Encoding::Steim1 | Encoding::Steim2 => decode_steim(data, num_samples, encoding, endian),
+    }
+}
+
+// Steim1/Steim2の差分を積算してサンプル値を復元する
+fn decode_steim(
+    data: &[u8],
+    num_samples: usize,
+    encoding: Encoding,
+    endian: Endian,
+) -> Result<Vec<f64>, String> {
+    let words: Vec<u32> = (0..data.len() / 4)
+        .map(|i| read_i32(data, i * 4, endian) as u32)
+        .collect();
+    let mut differences: Vec<i32> = Vec::with_capacity(num_samples);
+    let (mut forward, mut reverse) = (0_i32, 0_i32);
+
+    for (f_index, frame) in words.chunks_exact(WORDS_PER_FRAME).enumerate() {
+        for (w_index, &word) in frame.iter().enumerate().skip(1) {
+            let nibble = (frame[0] >> (30 - 2 * w_index)) & 0b11;
+            let dnib = word >> 30;
+
+            // 最初のフレームの1, 2ワード目は先頭と末尾のサンプル値(積分定数)
+            if f_index == 0 && w_index == 1 {
+                forward = word as i32;
+                continue;
+            }
+            if f_index == 0 && w_index == 2 {
+                reverse = word as i32;
+                continue;
+            }
+
+            let (count, bits) = match (encoding, nibble, dnib) {
+                (_, 0, _) => continue,
+                (_, 1, _) => (4, 8),
+                (Encoding::Steim1, 2, _) => (2, 16),
+                (Encoding::Steim1, 3, _) => (1, 32),
+                (_, 2, 1) => (1, 30),
+                (_, 2, 2) => (2, 15),
+                (_, 2, 3) => (3, 10),
+                (_, 3, 0) => (5, 6),
+                (_, 3, 1) => (6, 5),
+                (_, 3, 2) => (7, 4),
+                _ => return Err(format!("invalid Steim2 code {nibble}/{dnib}")),
+            };
+            differences.extend(unpack(word, count, bits));
+        }
+    }
+
+    if differences.len() < num_samples {
+        return Err(format!(
+            "{} differences were found for {} samples",
+            differences.len(),
+            num_samples
+        ));
+    }
+
+    // 最初の差分は前のレコードの最後のサンプルからの差分のため使用しない
+    let mut samples: Vec<i32> = Vec::with_capacity(num_samples);
+    samples.push(forward);
+    for &difference in &differences[1..num_samples] {
+        let last = samples[samples.len() - 1];
+        samples.push(last.wrapping_add(difference));
+    }
+
+    let last = samples[num_samples - 1];
+    if last != reverse {
+        return Err(format!(
+            "the last sample {last} does not match the reverse integration constant {reverse}"
+        ));
+    }
+
+    Ok(samples.into_iter().map(f64::from).collect())
+}
+
+// ワードを上位ビットから`bits`ビットずつ`count`個の符号付き整数に分割する
+fn unpack(word: u32, count: u32, bits: u32) -> impl Iterator<Item = i32> {
+    (0..count).map(move |i| {
+        let shifted = word >> (bits * (count - 1 - i));
+        // 符号拡張
+        ((shifted << (32 - bits)) as i32) >> (32 - bits)
+    })
+}
+
+// 経過時間(秒)
+fn elapsed_sec(from: NaiveDateTime, to: NaiveDateTime) -> f64 {
+    (to - from).num_microseconds().unwrap_or_default() as f64 / 1e6
+}
+
+fn seconds(sec: f64) -> TimeDelta {
+    TimeDelta::microseconds((sec * 1e6).round() as i64)
+}
+
+fn read_code(bytes: &[u8], (offset, len): (usize, usize)) -> String {
+    String::from_utf8_lossy(&bytes[offset..offset + len])
+        .trim()
+        .to_string()
+}
+
+fn read_u16(bytes: &[u8], offset: usize, endian: Endian) -> u16 {
+    let pair = [bytes[offset], bytes[offset + 1]];
+    match endian {
+        Endian::Little => u16::from_le_bytes(pair),
+        Endian::Big => u16::from_be_bytes(pair),
+    }
+}
+
+fn read_i16(bytes: &[u8], offset: usize, endian: Endian) -> i16 {
+    read_u16(bytes, offset, endian) as i16
+}
+
+/// This module contains unit tests for the miniSEED reader.
+///
+/// # Test Categories
+///
+/// - `test_decode_steim1`: Tests the 8, 16 and 32-bit differences and the reverse integration constant
+/// - `test_decode_steim2`: Tests every difference width of Steim2
+/// - `test_parse_record_little_endian`: Tests INT32 and FLOAT32 records in little endian with a fractional start time
+/// - `test_sampling_rate`: Tests the sample rate factor and multiplier
+/// - `test_extract_group`: Tests merging of records into NS/EW/UD converted with the sensitivity
+/// - `test_extract_group_discontinuity`: Tests reporting of a gap and an overlap
+/// - `test_extract_group_unshared_span`: Tests reporting of channels that cover different time spans
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis_config_file::{FileConfig, From, SensitivityConfig, To};
+    use chrono::{Datelike, Timelike};
+    use tempfile::tempdir;
+
+    struct TestRecord<'a> {
+        channel: &'a str,
+        start_time: NaiveDateTime,
+        endian: Endian,
+        encoding: u8,
+        num_samples: u16,
+        data: Vec<u8>,
+    }
+
+    fn put(bytes: &mut [u8], offset: usize, value: &[u8]) {
+        bytes[offset..offset + value.len()].copy_from_slice(value);
+    }
+
+    fn u16_bytes(value: u16, endian: Endian) -> [u8; 2] {
+        match endian {
+            Endian::Little => value.to_le_bytes(),
+            Endian::Big => value.to_be_bytes(),
+        }
+    }
+
+    fn u32_bytes(value: u32, endian: Endian) -> [u8; 4] {
+        match endian {
+            Endian::Little => value.to_le_bytes(),
+            Endian::Big => value.to_be_bytes(),
+        }
+    }
+
+    // 512バイトのレコード(ブロケット1000は48バイト目、データは64バイト目から)
+    fn record_bytes(record: &TestRecord) -> Vec<u8> {
+        let endian = record.endian;
+        let mut bytes = vec![0_u8; 512];
+        put(&mut bytes, 0, b"000001D ");
+        for ((offset, len), code) in [
+            (STATION, "ABC"),
+            (LOCATION, "00"),
+            (CHANNEL, record.channel),
+            (NETWORK, "XX"),
+        ] {
+            put(&mut bytes, offset, format!("{code:<len$}").as_bytes());
+        }
+
+        let time = record.start_time;
+        put(&mut bytes, YEAR, &u16_bytes(time.year() as u16, endian));
+        put(&mut bytes, DAY, &u16_bytes(time.ordinal() as u16, endian));
+        put(
+            &mut bytes,
+            HOUR,
+            &[time.hour() as u8, time.minute() as u8, time.second() as u8],
+        );
+        put(
+            &mut bytes,
+            FRACT,
+            &u16_bytes((time.nanosecond() / 100_000) as u16, endian),
+        );
+        put(
+            &mut bytes,
+            NUM_SAMPLES,
+            &u16_bytes(record.num_samples, endian),
+        );
+        put(&mut bytes, RATE_FACTOR, &u16_bytes(100, endian));
+        put(&mut bytes, RATE_MULTIPLIER, &u16_bytes(1, endian));
+        bytes[39] = 1;
+        put(&mut bytes, DATA_OFFSET, &u16_bytes(64, endian));
+        put(&mut bytes, BLOCKETTE_OFFSET, &u16_bytes(48, endian));
+
+        put(&mut bytes, 48, &u16_bytes(BLOCKETTE_1000, endian));
+        let word_order = match endian {
+            Endian::Little => 0,
+            Endian::Big => 1,
+        };
+        put(&mut bytes, 52, &[record.encoding, word_order, 9]);
+        put(&mut bytes, 64, &record.data);
+        bytes
+    }
+
+    // 上位ビットから`bits`ビットずつ値を詰める
+    fn pack(values: &[i32], bits: u32, dnib: u32) -> u32 {
+        let mask = if bits == 32 {
+            u32::MAX
+        } else {
+            (1 << bits) - 1
+        };
+        values.iter().enumerate().fold(dnib << 30, |word, (i, &v)| {
+            word | ((v as u32 & mask) << (bits * (values.len() as u32 - 1 - i as u32)))
+        })
+    }
+
+    // 最初のフレームのみのSteimデータ
+    fn steim_bytes(words: &[(u32, u32)], forward: i32, reverse: i32, endian: Endian) -> Vec<u8> {
+        let mut frame = vec![0_u32, forward as u32, reverse as u32];
+        let mut nibbles = 0_u32;
+        for (nibble, word) in words {
+            nibbles |= nibble << (30 - 2 * frame.len());
+            frame.push(*word);
+        }
+        frame[0] = nibbles;
+        frame.resize(WORDS_PER_FRAME, 0);
+
+        frame.iter().flat_map(|&w| u32_bytes(w, endian)).collect()
+    }
+
+    fn integrate(forward: i32, differences: &[i32]) -> Vec<f64> {
+        let mut samples = vec![forward];
+        for d in &differences[1..] {
+            samples.push(samples[samples.len() - 1] + d);
+        }
+        samples.into_iter().map(f64::from).collect()
+    }
+
+    #[test]
+    fn test_decode_steim1() {
+        let words = [
+            (1, pack(&[10, 2, -3, 0], 8, 0)),
+            (2, pack(&[291, -300], 16, 0)),
+            (3, pack(&[-70000], 32, 0)),
+        ];
+        let data = steim_bytes(&words, 10, -70000, Endian::Big);
+
+        assert_eq!(
+            decode(&data, 7, Encoding::Steim1, Endian::Big),
+            Ok(vec![10.0, 12.0, 9.0, 9.0, 300.0, 0.0, -70000.0])
+        );
+
+        let data = steim_bytes(&words, 10, 0, Endian::Big);
+        assert!(decode(&data, 7, Encoding::Steim1, Endian::Big).is_err());
+    }
+
+    #[test]
+    fn test_decode_steim2() {
+        let groups: [(u32, &[i32], u32, u32); 7] = [
+            (1, &[5, 2, -3, 0], 8, 0),
+            (2, &[291, -300, 500], 10, 3),
+            (2, &[-70000], 30, 1),
+            (3, &[1, -1, 2, -2, 3, -3, 4], 4, 2),
+            (3, &[5, -6, 7, -8, 9], 6, 0),
+            (3, &[12, -13, 14, -15, 15, -16], 5, 1),
+            (2, &[1000, -1000], 15, 2),
+        ];
+        let words: Vec<(u32, u32)> = groups
+            .iter()
+            .map(|(nibble, values, bits, dnib)| (*nibble, pack(values, *bits, *dnib)))
+            .collect();
+        let differences: Vec<i32> = groups.iter().flat_map(|g| g.1.to_vec()).collect();
+        let expected = integrate(100, &differences);
+        let reverse = expected[expected.len() - 1] as i32;
+
+        for endian in [Endian::Big, Endian::Little] {
+            let data = steim_bytes(&words, 100, reverse, endian);
+            assert_eq!(
+                decode(&data, differences.len(), Encoding::Steim2, endian),
+                Ok(expected.clone())
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_record_little_endian() {
+        let path = Path::new("test.mseed");
+        let start_time = NaiveDate::from_ymd_opt(2024, 1, 1)
+            .unwrap()
+            .and_hms_micro_opt(16, 10, 9, 123_400)
+            .unwrap();
+        let int32 = TestRecord {
+            channel: "HNZ",
+            start_time,
+            endian: Endian::Little,
+            encoding: 3,
+            num_samples: 3,
+            data: [1_i32, -2, 300000]
+                .iter()
+                .flat_map(|v| v.to_le_bytes())
+                .collect(),
+        };
+        let float32 = TestRecord {
+            encoding: 4,
+            data: [1.5_f32, -2.25, 0.0]
+                .iter()
+                .flat_map(|v| v.to_le_bytes())
+                .collect(),
+            ..int32
+        };
+
+        let (record, record_length) = parse_record(&record_bytes(&int32), path).unwrap();
+        let record = record.unwrap();
+        assert_eq!(record_length, 512);
+        assert_eq!(
+            record.nslc,
+            Nslc {
+                network: "XX".to_string(),
+                station: "ABC".to_string(),
+                location: "00".to_string(),
+                channel: "HNZ".to_string(),
+            }
+        );
+        assert_eq!(record.nslc.to_string(), "XX.ABC.00.HNZ");
+        assert_eq!(record.start_time, start_time);
+        assert_eq!(record.sampling_rate, 100.0);
+        assert_eq!(record.encoding, Encoding::Int32);
+        assert_eq!(record.samples, vec![1.0, -2.0, 300000.0]);
+        assert_eq!(record.end_time(), start_time + TimeDelta::milliseconds(30));
+
+        let (record, _) = parse_record(&record_bytes(&float32), path).unwrap();
+        assert_eq!(record.unwrap().samples, vec![1.5, -2.25, 0.0]);
+
+        let mut bytes = record_bytes(&float32);
+        bytes[53] = 1;
+        bytes[52] = 2;
+        assert!(matches!(
+            parse_record(&bytes, path),
+            Err(DataExtractionErr::InvalidHeaderValue(key, value, _)) if key == "encoding" && value == "2"
+        ));
+    }
+
+    #[test]
+    fn test_sampling_rate() {
+        assert_eq!(sampling_rate(100, 1), 100.0);
+        assert_eq!(sampling_rate(1, -10), 0.1);
+        assert_eq!(sampling_rate(-10, 1), 0.1);
+        assert_eq!(sampling_rate(-10, -2), 0.05);
+        assert_eq!(sampling_rate(0, 1), 0.0);
+    }
+
+    fn extractor(files: Vec<FileConfig>) -> MiniSeedExtractor {
+        MiniSeedExtractor::new(ConversionConfig {
+            sensitivity: Some(SensitivityConfig {
+                counts_per_unit: 100.0,
+                unit: Unit::Gal,
+            }),
+            ..ConversionConfig::for_test("noto", From::MiniSeed, To::JpJmaCsv, files)
+        })
+    }
+
+    // チャンネルごとに3サンプルのINT32レコードを2つ書き込む
+    fn write_channels(path: &Path, channels: &[(&str, [i64; 2])]) {
+        let start_time = NaiveDate::from_ymd_opt(2024, 1, 1)
+            .unwrap()
+            .and_hms_opt(16, 10, 9)
+            .unwrap();
+        let mut bytes: Vec<u8> = Vec::new();
+
+        for (c_index, (channel, offsets_ms)) in channels.iter().enumerate() {
+            for (r_index, offset_ms) in offsets_ms.iter().enumerate() {
+                let base = (c_index * 10 + r_index * 3) as i32;
+                bytes.extend(record_bytes(&TestRecord {
+                    channel,
+                    start_time: start_time + TimeDelta::milliseconds(*offset_ms),
+                    endian: Endian::Big,
+                    encoding: 3,
+                    num_samples: 3,
+                    data: [base, base + 1, base + 2]
+                        .iter()
+                        .flat_map(|v| v.to_be_bytes())
+                        .collect(),
+                }));
+            }
+        }
+
+        std::fs::write(path, bytes).unwrap();
+    }
+
+    #[test]
+    fn test_extract_group() {
+        let dir = tempdir().unwrap();
+        let horizontals = dir.path().join("XX.ABC.00.HN.mseed");
+        let vertical = dir.path().join("XX.ABC.00.HNZ.mseed");
+        write_channels(&horizontals, &[("HNE", [30, 0]), ("HNN", [0, 30])]);
+        write_channels(&vertical, &[("HNZ", [0, 30])]);
+
+        let files = [horizontals, vertical]
+            .into_iter()
+            .map(|path| FileConfig {
+                path,
+                acc_axis: None,
+            })
+            .collect();
+        let extracted = extractor(files).extract().unwrap();
+        let data = &extracted[0];
+
+        assert_eq!(data.station.code, "ABC");
+        assert_eq!(data.sampling_rate, 100.0);
+        assert_eq!(data.start_time.second(), 9);
+        let expected = |base: f64| -> Vec<f64> {
+            [0.0, 1.0, 2.0, 3.0, 4.0, 5.0]
+                .iter()
+                .map(|v| (base + v) / 100.0)
+                .collect()
+        };
+        // HNEは2つ目のレコードが先頭となる
+        let ew: Vec<f64> = [3.0, 4.0, 5.0, 0.0, 1.0, 2.0]
+            .iter()
+            .map(|v| v / 100.0)
+            .collect();
+        for (actual, expected) in [
+            (&data.components.ns, expected(10.0)),
+            (&data.components.ew, ew),
+            (&data.components.ud, expected(0.0)),
+        ] {
+            assert_eq!(actual.len(), expected.len());
+            for (a, e) in actual.iter().zip(&expected) {
+                assert!((a - e).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_extract_group_discontinuity() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("XX.ABC.00.HN.mseed");
+        write_channels(
+            &path,
+            &[("HNE", [0, 30]), ("HNN", [0, 10]), ("HNZ", [0, 50])],
+        );
+
+        let errors = extractor(vec![FileConfig {
+            path: path.clone(),
+            acc_axis: None,
+        }])
+        .extract()
+        .unwrap_err();
+
+        let discontinuity = |kind: &str, channel: &str, at: &str| {
+            AppError::Analysis(AnalysisErr::Extraction(DataExtractionErr::Discontinuity(
+                kind.to_string(),
+                "0.0200".to_string(),
+                format!("XX.ABC.00.{channel}"),
+                format!("2024-01-01T16:10:{at}"),
+                path.clone(),
+            )))
+        };
+        assert_eq!(
+            errors,
+            vec![
+                discontinuity("overlap", "HNN", "09.010000"),
+                discontinuity("gap", "HNZ", "09.050000"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_group_unshared_span() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("XX.ABC.00.HN.mseed");
+        // HNZのみ0.03秒(3サンプル)遅く始まり、遅く終わる
+        write_channels(
+            &path,
+            &[("HNE", [0, 30]), ("HNN", [0, 30]), ("HNZ", [30, 60])],
+        );
+
+        let errors = extractor(vec![FileConfig {
+            path,
+            acc_axis: None,
+        }])
+        .extract()
+        .unwrap_err();
+
+        let unshared = |channel: &str| {
+            AppError::Analysis(AnalysisErr::Extraction(DataExtractionErr::UnsharedSpan(
+                format!("XX.ABC.00.{channel}"),
+                "0.0300".to_string(),
+                "noto".to_string(),
+                1,
+            )))
+        };
+        assert_eq!(
+            errors,
+            vec![unshared("HNE"), unshared("HNN"), unshared("HNZ")]
+        );
+    }
+}
@@ -1,63 +1,280 @@
+use std::path::Path;
+
+use chrono::NaiveDateTime;
+
+use cosmos::CosmosExtractor;
+use jp_nied_knet::JpNiedKnetExtractor;
+use mini_seed::MiniSeedExtractor;
+use nz_geonet_v1a_v2a::NzGeonetV1aV2aExtractor;
+use peer_at2::PeerAt2Extractor;
+use tk_afad_asc::TkAfadAscExtractor;
 use tw_paleart_sac::TwPalertSacExtractor;
+use us_scsn_v2::UsScsnV2Extractor;
 
 use crate::{
     analysis_config_file::{ConversionConfig, From},
-    error::AppError,
+    error::{AppError, DataExtractionErr},
+    record::{Components, Record, Unit},
 };
 
+pub mod cosmos;
 pub mod jp_nied_knet;
+pub mod mini_seed;
 pub mod nz_geonet_v1a_v2a;
+pub mod peer_at2;
 pub mod tk_afad_asc;
 pub mod tw_paleart_sac;
 pub mod us_scsn_v2;
 
 pub trait Extractor {
-    fn extract(&self) -> Result<ExtractedData, Vec<AppError>>;
+    /// Extracts the records of every `GroupConfig` of the conversion.
+    fn extract(&self) -> Result<Vec<Record>, Vec<AppError>>;
 }
 
 pub fn create_extractor(conversion: ConversionConfig) -> Box<dyn Extractor> {
     // fromに対応するextractorを呼び出す
     match &conversion.from {
-        From::JpNiedKnet => todo!(),
-        From::UsScsnV2 => todo!(),
-        From::NzGeonetV1a => todo!(),
-        From::NzGeonetV2a => todo!(),
+        From::JpNiedKnet | From::JpNiedKik => Box::new(JpNiedKnetExtractor::new(conversion)),
+        From::UsScsnV2 => Box::new(UsScsnV2Extractor::new(conversion)),
+        From::NzGeonetV1a | From::NzGeonetV2a => Box::new(NzGeonetV1aV2aExtractor::new(conversion)),
         From::TwPalertSac => Box::new(TwPalertSacExtractor::new(conversion)),
-        From::TkAfadAsc => todo!(),
+        From::TkAfadAsc => Box::new(TkAfadAscExtractor::new(conversion)),
+        From::Cosmos => Box::new(CosmosExtractor::new(conversion)),
+        From::PeerAt2 => Box::new(PeerAt2Extractor::new(conversion)),
+        From::MiniSeed => Box::new(MiniSeedExtractor::new(conversion)),
+    }
+}
+
+/// Reads the whole file to be extracted as a string.
+fn read_to_string(path: &Path) -> Result<String, DataExtractionErr> {
+    std::fs::read_to_string(path).map_err(|e| DataExtractionErr::Io(path.to_path_buf(), e.into()))
+}
+
+/// Removes the mean value (DC offset) from the waveform.
+fn remove_offset(values: &mut [f64]) {
+    if values.is_empty() {
+        return;
+    }
+
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    values.iter_mut().for_each(|v| *v -= mean);
+}
+
+/// Orientation of a component, as described in the header of the source file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Orientation {
+    /// Horizontal component, whose azimuth is in degrees clockwise from north.
+    Horizontal(f64),
+    Up,
+    Down,
+}
+
+impl Orientation {
+    /// Returns the orientation indicated by the last letter of a SEED channel code such as `HNN`, `HNE` and `HNZ`.
+    ///
+    /// `1` and `2` are regarded as north and east, as their azimuths are not given by the code.
+    pub fn from_channel_code(code: &str) -> Option<Self> {
+        match code.trim().to_uppercase().chars().last()? {
+            'N' | '1' => Some(Orientation::Horizontal(0.0)),
+            'E' | '2' => Some(Orientation::Horizontal(90.0)),
+            'Z' | '3' => Some(Orientation::Up),
+            _ => None,
+        }
+    }
+}
+
+// 水平2成分の方位角の直交からのずれの許容値(度)
+const ORTHOGONAL_TOLERANCE_DEG: f64 = 1.0;
+
+/// Builds `Components` from three oriented components.
+///
+/// The vertical component is used as UD(inverted if it points down), and the two horizontal components are rotated
+/// into NS/EW using their azimuths, so that rotated installations are also handled.
+/// Returns the reason as an error if the components are not one vertical and two orthogonal horizontals.
+fn orient_components(components: Vec<(Orientation, Vec<f64>)>) -> Result<Components, String> {
+    let mut horizontals: Vec<(f64, Vec<f64>)> = Vec::new();
+    let mut verticals: Vec<Vec<f64>> = Vec::new();
+
+    for (orientation, values) in components {
+        match orientation {
+            Orientation::Horizontal(azimuth) => horizontals.push((azimuth, values)),
+            Orientation::Up => verticals.push(values),
+            Orientation::Down => verticals.push(values.iter().map(|v| -v).collect()),
+        }
+    }
+
+    if horizontals.len() != 2 || verticals.len() != 1 {
+        return Err(format!(
+            "one vertical and two horizontal components are required, but found {} vertical and {} horizontal",
+            verticals.len(),
+            horizontals.len()
+        ));
+    }
+
+    let ud = verticals.remove(0);
+    let (az1, h1) = &horizontals[0];
+    let (az2, h2) = &horizontals[1];
+
+    if h1.len() != h2.len() || h1.len() != ud.len() {
+        return Err("number of data".to_string());
     }
+
+    let (az1, az2) = (az1.to_radians(), az2.to_radians());
+    if (az1 - az2).cos().abs() > ORTHOGONAL_TOLERANCE_DEG.to_radians().sin() {
+        return Err("the azimuths of the horizontal components are not orthogonal".to_string());
+    }
+
+    // 各水平成分を北向き、東向きの成分に分解して足し合わせる
+    let ns = h1
+        .iter()
+        .zip(h2)
+        .map(|(v1, v2)| v1 * az1.cos() + v2 * az2.cos())
+        .collect();
+    let ew = h1
+        .iter()
+        .zip(h2)
+        .map(|(v1, v2)| v1 * az1.sin() + v2 * az2.sin())
+        .collect();
+
+    Ok(Components { ns, ew, ud })
 }
 
-pub enum ExtractedData {
-    JpStera3dTxt(JpStera3dTxtData),
-    JpJmaCsv(JpJmaCsvData),
+/// Returns whether the start times of the components differ by more than half a sample, in which case their samples
+/// cannot be merged one by one.
+fn misaligned(start_times: &[NaiveDateTime], sampling_rate: f64) -> bool {
+    let (Some(earliest), Some(latest)) = (start_times.iter().min(), start_times.iter().max())
+    else {
+        return false;
+    };
+
+    let diff_sec = (*latest - *earliest).num_microseconds().unwrap_or(i64::MAX) as f64 / 1e6;
+    diff_sec * sampling_rate > 0.5
 }
 
-#[derive(Debug, Clone, PartialEq)]
-pub struct JpStera3dTxtData {
-    num_of_elements: u32,
-    acc_values: Acceleration,
-    common: CommonValue,
+/// Splits a line of a fixed-width Fortran format(e.g. `8F10.3`) into values.
+fn parse_fixed_width<T: std::str::FromStr>(
+    line: &str,
+    width: usize,
+    path: &Path,
+) -> Result<Vec<T>, DataExtractionErr> {
+    let chars: Vec<char> = line.trim_end().chars().collect();
+
+    chars
+        .chunks(width)
+        .map(|chunk| chunk.iter().collect::<String>())
+        .filter(|field| !field.trim().is_empty())
+        .map(|field| {
+            field
+                .trim()
+                .parse()
+                .map_err(|_| DataExtractionErr::InvalidDataValue(field, path.to_path_buf()))
+        })
+        .collect()
 }
 
-#[derive(Debug, Clone, PartialEq)]
-pub struct JpJmaCsvData {
-    site_code: String,
-    lat: f64,
-    lon: f64,
-    unit_type: String,
-    initial_time: String,
-    common: CommonValue,
+/// Takes `count` lines from `pos` and advances `pos`, or fails if the file ends before that.
+fn take_lines<'a, 'b>(
+    lines: &'b [&'a str],
+    pos: &mut usize,
+    count: usize,
+    path: &Path,
+) -> Result<&'b [&'a str], DataExtractionErr> {
+    let taken = lines
+        .get(*pos..*pos + count)
+        .ok_or_else(|| DataExtractionErr::InvalidStructure(path.to_path_buf()))?;
+    *pos += count;
+
+    Ok(taken)
 }
 
-#[derive(Debug, Clone, PartialEq)]
-pub struct CommonValue {
-    sampling_rate: f32,
-    acc_values: Acceleration,
+/// Returns the factor converting the acceleration unit written in the source file to gal.
+fn gal_factor(unit: &str) -> Option<f64> {
+    Unit::from_label(unit).and_then(|unit| unit.factor_to(Unit::Gal))
 }
 
-#[derive(Debug, Clone, PartialEq)]
-pub struct Acceleration {
-    ns: Vec<f64>,
-    ew: Vec<f64>,
-    ud: Vec<f64>,
+/// This module contains unit tests for the helpers shared by the extractors.
+///
+/// # Test Categories
+///
+/// - `test_orient_components_reversed`: Tests inversion of components pointing south, west and down
+/// - `test_orient_components_rotated`: Tests rotation of horizontals installed off north/east
+/// - `test_orient_components_not_orthogonal`: Tests rejection of non-orthogonal horizontals
+/// - `test_misaligned`: Tests the tolerance of half a sample between the start times of the components
+/// - `test_parse_fixed_width`: Tests splitting of fixed-width values without separators
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: &[f64], expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected) {
+            assert!(
+                (a - e).abs() < 1e-9,
+                "expected {:?}, got {:?}",
+                expected,
+                actual
+            );
+        }
+    }
+
+    #[test]
+    fn test_orient_components_reversed() {
+        let acc = orient_components(vec![
+            (Orientation::Down, vec![1.0, -2.0]),
+            (Orientation::Horizontal(270.0), vec![3.0, -4.0]),
+            (Orientation::Horizontal(180.0), vec![5.0, -6.0]),
+        ])
+        .unwrap();
+
+        assert_close(&acc.ns, &[-5.0, 6.0]);
+        assert_close(&acc.ew, &[-3.0, 4.0]);
+        assert_close(&acc.ud, &[-1.0, 2.0]);
+    }
+
+    #[test]
+    fn test_orient_components_rotated() {
+        // 北から30度回転して設置された観測点
+        let (sin, cos) = 30.0_f64.to_radians().sin_cos();
+        let acc = orient_components(vec![
+            (Orientation::Horizontal(30.0), vec![cos, -sin]),
+            (Orientation::Horizontal(120.0), vec![-sin, -cos]),
+            (Orientation::Up, vec![0.0, 0.0]),
+        ])
+        .unwrap();
+
+        assert_close(&acc.ns, &[1.0, 0.0]);
+        assert_close(&acc.ew, &[0.0, -1.0]);
+    }
+
+    #[test]
+    fn test_orient_components_not_orthogonal() {
+        let result = orient_components(vec![
+            (Orientation::Horizontal(0.0), vec![1.0]),
+            (Orientation::Horizontal(45.0), vec![1.0]),
+            (Orientation::Up, vec![1.0]),
+        ]);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_misaligned() {
+        let start = chrono::NaiveDate::from_ymd_opt(2024, 1, 1)
+            .unwrap()
+            .and_hms_opt(16, 10, 9)
+            .unwrap();
+        let shifted = |ms: i64| start + chrono::TimeDelta::milliseconds(ms);
+
+        assert!(!misaligned(&[], 100.0));
+        assert!(!misaligned(&[start, shifted(4), start], 100.0));
+        assert!(misaligned(&[start, shifted(6), start], 100.0));
+        assert!(misaligned(&[shifted(-6), start], 100.0));
+    }
+
+    #[test]
+    fn test_parse_fixed_width() {
+        let values: Vec<f64> =
+            parse_fixed_width("  -1.250-123.500   3.000", 8, Path::new("test")).unwrap();
+        assert_eq!(values, vec![-1.25, -123.5, 3.0]);
+    }
 }
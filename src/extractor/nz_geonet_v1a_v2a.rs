@@ -0,0 +1,689 @@
+use std::path::Path;
+
+use chrono::{NaiveDate, NaiveDateTime, TimeDelta};
+
+use crate::{
+    analysis_config_file::{ConversionConfig, From, GroupConfig},
+    error::{AnalysisErr, AppError, DataExtractionErr},
+    record::{Components, Event, Record, Station, Unit},
+};
+
+use super::{
+    misaligned, orient_components, parse_fixed_width, read_to_string, take_lines, Extractor,
+    Orientation,
+};
+
+// 1成分あたりのヘッダーの行数
+const TEXT_HEADER_LINES: usize = 16;
+const INT_HEADER_LINES: usize = 4;
+const FLOAT_HEADER_LINES: usize = 6;
+
+// ヘッダー、データともに1行10個、1つ8文字の固定長書式
+const COLUMN_WIDTH: usize = 8;
+const VALUES_PER_LINE: usize = 10;
+
+// 整数ヘッダーのインデックス
+const INT_YEAR: usize = 0;
+const INT_MONTH: usize = 1;
+const INT_DAY: usize = 2;
+const INT_HOUR: usize = 3;
+const INT_MINUTE: usize = 4;
+const INT_ORIGIN_YEAR: usize = 8;
+const INT_ORIGIN_MONTH: usize = 9;
+const INT_ORIGIN_DAY: usize = 10;
+const INT_ORIGIN_HOUR: usize = 11;
+const INT_ORIGIN_MINUTE: usize = 12;
+const INT_NUM_OF_ACC: usize = 33;
+const INT_NUM_OF_VEL: usize = 34;
+const INT_NUM_OF_DISP: usize = 35;
+
+// 実数ヘッダーのインデックス
+const FLOAT_SECOND: usize = 0;
+const FLOAT_ORIGIN_SECOND: usize = 1;
+const FLOAT_DELTA: usize = 5;
+const FLOAT_STATION_LAT: usize = 10;
+const FLOAT_STATION_LON: usize = 11;
+const FLOAT_EVENT_LAT: usize = 12;
+const FLOAT_EVENT_LON: usize = 13;
+const FLOAT_EVENT_DEPTH: usize = 14;
+const FLOAT_MAGNITUDE: usize = 15;
+
+// GeoNetの加速度はmm/s^2で記録されている
+const MM_PER_SEC2_TO_GAL: f64 = 0.1;
+
+/// One component of a GeoNet V1A(uncorrected) or V2A(processed) volume file.
+///
+/// A volume file consists of three components, each of which has the following layout.
+/// - Text header(16 lines): station(`Site`) and component orientation(`Component S00E`, `Component UP`).
+/// - Integer header(40 values, 4 lines): start time(0-4), origin time of the earthquake(8-12, 0 if unknown) and number
+///   of acceleration, velocity and displacement points(33-35).
+/// - Float header(60 values, 6 lines): start second(0), origin second(1), sampling interval(5), station
+///   coordinates(10, 11), hypocenter(12, 13), depth in km(14) and magnitude(15).
+/// - Acceleration(mm/s^2) block, followed by velocity(mm/s) and displacement(mm) blocks only for V2A. Only the
+///   acceleration is read, as the velocity and displacement are integrated from it when needed.
+///
+/// Every value is written 10 per line with 8 columns each.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeonetComponent {
+    pub station_code: String,
+    pub station_lat: f64,
+    pub station_lon: f64,
+    pub orientation: Orientation,
+    pub start_time: NaiveDateTime,
+    /// Earthquake of the record, or `None` if the origin time is not written.
+    pub event: Option<Event>,
+    pub int_header: Vec<i32>,
+    pub float_header: Vec<f64>,
+    pub delta_sec: f64,
+    /// Acceleration in gal.
+    pub acc_values: Vec<f64>,
+}
+
+pub struct NzGeonetV1aV2aExtractor {
+    pub unextracted: ConversionConfig,
+}
+
+impl Extractor for NzGeonetV1aV2aExtractor {
+    fn extract(&self) -> Result<Vec<Record>, Vec<AppError>> {
+        let mut extracted: Vec<Record> = Vec::new();
+        let mut errors: Vec<AppError> = Vec::new();
+
+        for (g_index, group_config) in self.unextracted.group.iter().enumerate() {
+            let id: usize = g_index + 1;
+            match self.extract_group(group_config, id) {
+                Ok(data) => extracted.push(data),
+                Err(e) => errors.extend(e.into_iter().map(|e| AnalysisErr::from(e).into())),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(extracted)
+    }
+}
+
+impl NzGeonetV1aV2aExtractor {
+    pub fn new(unextracted: ConversionConfig) -> Self {
+        Self { unextracted }
+    }
+
+    // グループ内の全ファイルの成分を1つのComponentsにまとめる
+    fn extract_group(
+        &self,
+        group_config: &GroupConfig,
+        id: usize,
+    ) -> Result<Record, Vec<DataExtractionErr>> {
+        let processed = self.unextracted.from == From::NzGeonetV2a;
+        let mut errors: Vec<DataExtractionErr> = Vec::new();
+        let mut components: Vec<GeonetComponent> = Vec::new();
+
+        for file in &group_config.files {
+            match parse_geonet_file(&file.path, processed) {
+                Ok(c) => components.extend(c),
+                Err(e) => errors.push(e),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        let name = &self.unextracted.name;
+        let mismatched = |reason: &str| {
+            vec![DataExtractionErr::MismatchedComponents(
+                reason.to_string(),
+                name.to_string(),
+                id,
+            )]
+        };
+
+        let Some(first) = components.first() else {
+            return Err(mismatched("no components"));
+        };
+        if components
+            .iter()
+            .any(|c| c.station_code != first.station_code)
+        {
+            return Err(mismatched("station code"));
+        }
+        if components.iter().any(|c| c.delta_sec != first.delta_sec) {
+            return Err(mismatched("sampling interval"));
+        }
+        let start_times: Vec<NaiveDateTime> = components.iter().map(|c| c.start_time).collect();
+        if misaligned(&start_times, 1.0 / first.delta_sec) {
+            return Err(mismatched("start time"));
+        }
+
+        let site_code = first.station_code.clone();
+        let (lat, lon) = (Some(first.station_lat), Some(first.station_lon));
+        let initial_time = first.start_time;
+        let sampling_rate = (1.0 / first.delta_sec) as f32;
+        let event = first.event.clone();
+
+        // 回転して設置された観測点も、方位角からNS, EWに変換される
+        let components: Components = orient_components(
+            components
+                .into_iter()
+                .map(|c| (c.orientation, c.acc_values))
+                .collect(),
+        )
+        .map_err(|reason| mismatched(&reason))?;
+
+        Ok(Record {
+            station: Station {
+                code: site_code,
+                lat,
+                lon,
+                sensor_position: None,
+            },
+            event,
+            start_time: initial_time,
+            sampling_rate,
+            unit: Unit::Gal,
+            components,
+            processing: Vec::new(),
+        })
+    }
+}
+
+/// Reads a GeoNet volume file, which contains three components.
+///
+/// `processed` is `true` for V2A, whose components also have velocity and displacement blocks to be skipped.
+pub fn parse_geonet_file(
+    path: &Path,
+    processed: bool,
+) -> Result<Vec<GeonetComponent>, DataExtractionErr> {
+    let contents = read_to_string(path)?;
+    parse_geonet(&contents, processed, path)
+}
+
+/// Parses the contents of a GeoNet volume file into its components.
+pub fn parse_geonet(
+    contents: &str,
+    processed: bool,
+    path: &Path,
+) -> Result<Vec<GeonetComponent>, DataExtractionErr> {
+    let lines: Vec<&str> = contents.lines().collect();
+    let mut components: Vec<GeonetComponent> = Vec::new();
+    let mut pos: usize = 0;
+
+    loop {
+        while pos < lines.len() && lines[pos].trim().is_empty() {
+            pos += 1;
+        }
+        if pos >= lines.len() {
+            break;
+        }
+
+        components.push(parse_component(&lines, &mut pos, processed, path)?);
+    }
+
+    Ok(components)
+}
+
+fn parse_component(
+    lines: &[&str],
+    pos: &mut usize,
+    processed: bool,
+    path: &Path,
+) -> Result<GeonetComponent, DataExtractionErr> {
+    let text_header = take_lines(lines, pos, TEXT_HEADER_LINES, path)?;
+
+    let mut int_header: Vec<i32> = Vec::new();
+    for line in take_lines(lines, pos, INT_HEADER_LINES, path)? {
+        int_header.extend(parse_fixed_width::<i32>(line, COLUMN_WIDTH, path)?);
+    }
+
+    let mut float_header: Vec<f64> = Vec::new();
+    for line in take_lines(lines, pos, FLOAT_HEADER_LINES, path)? {
+        float_header.extend(parse_fixed_width::<f64>(line, COLUMN_WIDTH, path)?);
+    }
+
+    let int_value = |index: usize| {
+        int_header
+            .get(index)
+            .copied()
+            .ok_or_else(|| DataExtractionErr::InvalidStructure(path.to_path_buf()))
+    };
+    let float_value = |index: usize| {
+        float_header
+            .get(index)
+            .copied()
+            .ok_or_else(|| DataExtractionErr::InvalidStructure(path.to_path_buf()))
+    };
+
+    let station_code = text_field(text_header, "Site", path)?.to_string();
+    let orientation = parse_orientation(text_field(text_header, "Component", path)?, path)?;
+    let start_time = parse_start_time(
+        [
+            int_value(INT_YEAR)?,
+            int_value(INT_MONTH)?,
+            int_value(INT_DAY)?,
+            int_value(INT_HOUR)?,
+            int_value(INT_MINUTE)?,
+        ],
+        float_value(FLOAT_SECOND)?,
+        path,
+    )?;
+    let event = match int_value(INT_ORIGIN_YEAR)? {
+        0 => None,
+        year => Some(Event {
+            origin_time: Some(parse_start_time(
+                [
+                    year,
+                    int_value(INT_ORIGIN_MONTH)?,
+                    int_value(INT_ORIGIN_DAY)?,
+                    int_value(INT_ORIGIN_HOUR)?,
+                    int_value(INT_ORIGIN_MINUTE)?,
+                ],
+                float_value(FLOAT_ORIGIN_SECOND)?,
+                path,
+            )?),
+            lat: Some(float_value(FLOAT_EVENT_LAT)?),
+            lon: Some(float_value(FLOAT_EVENT_LON)?),
+            depth_km: Some(float_value(FLOAT_EVENT_DEPTH)?),
+            magnitude: Some(float_value(FLOAT_MAGNITUDE)?),
+        }),
+    };
+    let delta_sec = float_value(FLOAT_DELTA)?;
+    if delta_sec <= 0.0 {
+        return Err(DataExtractionErr::InvalidHeaderValue(
+            "sampling interval".to_string(),
+            delta_sec.to_string(),
+            path.into(),
+        ));
+    }
+
+    let acc_values: Vec<f64> = read_block(lines, pos, int_value(INT_NUM_OF_ACC)?, path)?
+        .iter()
+        .map(|v| v * MM_PER_SEC2_TO_GAL)
+        .collect();
+    // 速度、変位は加速度から積分できるため、読み飛ばす
+    if processed {
+        skip_block(lines, pos, int_value(INT_NUM_OF_VEL)?, path)?;
+        skip_block(lines, pos, int_value(INT_NUM_OF_DISP)?, path)?;
+    }
+
+    Ok(GeonetComponent {
+        station_code,
+        station_lat: float_value(FLOAT_STATION_LAT)?,
+        station_lon: float_value(FLOAT_STATION_LON)?,
+        orientation,
+        start_time,
+        event,
+        int_header,
+        float_header,
+        delta_sec,
+        acc_values,
+    })
+}
+
+// テキストヘッダーから"Site KIKS"のように行頭のラベルに続く最初の値を取り出す
+fn text_field<'a>(
+    text_header: &[&'a str],
+    label: &str,
+    path: &Path,
+) -> Result<&'a str, DataExtractionErr> {
+    text_header
+        .iter()
+        .find_map(|line| line.trim_start().strip_prefix(label))
+        .and_then(|rest| rest.split_whitespace().next())
+        .ok_or_else(|| DataExtractionErr::MissingHeaderField(label.to_string(), path.into()))
+}
+
+// "N18E"、"S00E"、"N90W"のような方位を、北から時計回りの方位角に変換する
+fn parse_orientation(value: &str, path: &Path) -> Result<Orientation, DataExtractionErr> {
+    let invalid = || {
+        DataExtractionErr::InvalidHeaderValue(
+            "Component".to_string(),
+            value.to_string(),
+            path.into(),
+        )
+    };
+
+    let upper = value.to_uppercase();
+    match upper.as_str() {
+        "UP" | "Z" => return Ok(Orientation::Up),
+        "DOWN" | "DN" => return Ok(Orientation::Down),
+        _ => {}
+    }
+
+    let mut chars = upper.chars();
+    let (Some(from), Some(to)) = (chars.next(), chars.next_back()) else {
+        return Err(invalid());
+    };
+    let angle: f64 = chars.as_str().parse().map_err(|_| invalid())?;
+
+    let azimuth = match (from, to) {
+        ('N', 'E') => angle,
+        ('N', 'W') => 360.0 - angle,
+        ('S', 'E') => 180.0 - angle,
+        ('S', 'W') => 180.0 + angle,
+        _ => return Err(invalid()),
+    };
+
+    Ok(Orientation::Horizontal(azimuth % 360.0))
+}
+
+fn parse_start_time(
+    [year, month, day, hour, minute]: [i32; 5],
+    second: f64,
+    path: &Path,
+) -> Result<NaiveDateTime, DataExtractionErr> {
+    let invalid = || {
+        DataExtractionErr::InvalidHeaderValue(
+            "start time".to_string(),
+            format!("{year}-{month}-{day} {hour}:{minute}:{second}"),
+            path.into(),
+        )
+    };
+
+    let minute_start = NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+        .and_then(|date| date.and_hms_opt(hour as u32, minute as u32, 0))
+        .ok_or_else(invalid)?;
+    if !(0.0..60.0).contains(&second) {
+        return Err(invalid());
+    }
+
+    Ok(minute_start + TimeDelta::milliseconds((second * 1000.0).round() as i64))
+}
+
+// 1行10個のデータを指定された個数だけ読み込む
+fn read_block(
+    lines: &[&str],
+    pos: &mut usize,
+    num_of_points: i32,
+    path: &Path,
+) -> Result<Vec<f64>, DataExtractionErr> {
+    let num_of_points = block_len(num_of_points, path)?;
+
+    let mut values: Vec<f64> = Vec::with_capacity(num_of_points);
+    while values.len() < num_of_points {
+        let line = take_lines(lines, pos, 1, path)?[0];
+        values.extend(parse_fixed_width::<f64>(line, COLUMN_WIDTH, path)?);
+    }
+    values.truncate(num_of_points);
+
+    Ok(values)
+}
+
+// 1行10個のデータを読まずに、その行数だけ進める
+fn skip_block(
+    lines: &[&str],
+    pos: &mut usize,
+    num_of_points: i32,
+    path: &Path,
+) -> Result<(), DataExtractionErr> {
+    let num_of_lines = block_len(num_of_points, path)?.div_ceil(VALUES_PER_LINE);
+    take_lines(lines, pos, num_of_lines, path)?;
+
+    Ok(())
+}
+
+fn block_len(num_of_points: i32, path: &Path) -> Result<usize, DataExtractionErr> {
+    usize::try_from(num_of_points).map_err(|_| {
+        DataExtractionErr::InvalidHeaderValue(
+            "number of points".to_string(),
+            num_of_points.to_string(),
+            path.into(),
+        )
+    })
+}
+
+/// This module contains unit tests for the GeoNet V1A/V2A parser.
+///
+/// # Test Categories
+///
+/// - `test_parse_orientation`: Tests conversion of quadrant bearings into azimuths
+/// - `test_parse_geonet_v1a`: Tests parsing of the headers, the event and acceleration blocks of V1A
+/// - `test_parse_geonet_v2a`: Tests skipping of the velocity and displacement blocks of V2A
+/// - `test_parse_geonet_without_event`: Tests that a header without the origin time has no event
+/// - `test_extract_group_rotated`: Tests rotation of an installation that is not aligned to north
+/// - `test_extract_group_misaligned`: Tests rejection of components whose start times differ by more than half a
+///   sample
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis_config_file::{FileConfig, To};
+    use std::{fs::File, io::Write};
+    use tempfile::tempdir;
+
+    fn block(values: &[f64], precision: usize) -> Vec<String> {
+        values
+            .chunks(10)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .map(|v| format!("{:8.*}", precision, v))
+                    .collect::<Vec<_>>()
+                    .concat()
+            })
+            .collect()
+    }
+
+    fn component_text(orientation: &str, acc_values: &[f64], processed: bool) -> String {
+        let mut lines: Vec<String> = vec![
+            "GeoNet Uncorrected accelerogram  20161113_110256_KIKS_20".to_string(),
+            "Site KIKS  Kaikoura Primary School".to_string(),
+            format!("Component {orientation}"),
+        ];
+        lines.resize(TEXT_HEADER_LINES, "-".to_string());
+
+        let mut int_header = [0; 40];
+        int_header[INT_YEAR] = 2016;
+        int_header[INT_MONTH] = 11;
+        int_header[INT_DAY] = 13;
+        int_header[INT_HOUR] = 11;
+        int_header[INT_MINUTE] = 2;
+        int_header[INT_ORIGIN_YEAR] = 2016;
+        int_header[INT_ORIGIN_MONTH] = 11;
+        int_header[INT_ORIGIN_DAY] = 13;
+        int_header[INT_ORIGIN_HOUR] = 11;
+        int_header[INT_ORIGIN_MINUTE] = 2;
+        int_header[INT_NUM_OF_ACC] = acc_values.len() as i32;
+        if processed {
+            int_header[INT_NUM_OF_VEL] = 3;
+            int_header[INT_NUM_OF_DISP] = 2;
+        }
+        lines.extend(int_header.chunks(10).map(|chunk| {
+            chunk
+                .iter()
+                .map(|v| format!("{:8}", v))
+                .collect::<Vec<_>>()
+                .concat()
+        }));
+
+        let mut float_header = [0.0; 60];
+        float_header[FLOAT_SECOND] = 56.5;
+        float_header[FLOAT_ORIGIN_SECOND] = 56.0;
+        float_header[FLOAT_DELTA] = 0.005;
+        float_header[FLOAT_STATION_LAT] = -42.4252;
+        float_header[FLOAT_STATION_LON] = 173.6833;
+        float_header[FLOAT_EVENT_LAT] = -42.6928;
+        float_header[FLOAT_EVENT_LON] = 173.0217;
+        float_header[FLOAT_EVENT_DEPTH] = 15.1;
+        float_header[FLOAT_MAGNITUDE] = 7.8;
+        lines.extend(block(&float_header, 4));
+
+        lines.extend(block(acc_values, 1));
+        if processed {
+            lines.extend(block(&[1.0, 2.0, 3.0], 3));
+            lines.extend(block(&[4.0, 5.0], 3));
+        }
+
+        lines.join("\n") + "\n"
+    }
+
+    const VALUES: [f64; 12] = [
+        10.0, 20.0, -30.0, 40.0, 50.0, 60.0, 70.0, 80.0, 90.0, 100.0, -110.0, 120.0,
+    ];
+
+    #[test]
+    fn test_parse_orientation() {
+        let path = Path::new("test.V1A");
+        let cases = [
+            ("N00E", Orientation::Horizontal(0.0)),
+            ("N90E", Orientation::Horizontal(90.0)),
+            ("S00E", Orientation::Horizontal(180.0)),
+            ("S30W", Orientation::Horizontal(210.0)),
+            ("N28W", Orientation::Horizontal(332.0)),
+            ("UP", Orientation::Up),
+        ];
+
+        for (value, expected) in cases {
+            assert_eq!(parse_orientation(value, path).unwrap(), expected);
+        }
+        assert!(parse_orientation("E10N", path).is_err());
+    }
+
+    #[test]
+    fn test_parse_geonet_v1a() {
+        let text = [
+            component_text("N28W", &VALUES, false),
+            component_text("N62E", &VALUES, false),
+            component_text("UP", &VALUES, false),
+        ]
+        .concat();
+        let components = parse_geonet(&text, false, Path::new("test.V1A")).unwrap();
+        assert_eq!(components.len(), 3);
+
+        let first = &components[0];
+        assert_eq!(first.station_code, "KIKS");
+        assert_eq!(first.station_lat, -42.4252);
+        assert_eq!(first.station_lon, 173.6833);
+        assert_eq!(first.orientation, Orientation::Horizontal(332.0));
+        assert_eq!(
+            first.start_time,
+            NaiveDate::from_ymd_opt(2016, 11, 13)
+                .unwrap()
+                .and_hms_milli_opt(11, 2, 56, 500)
+                .unwrap()
+        );
+        assert_eq!(
+            first.event,
+            Some(Event {
+                origin_time: Some(
+                    NaiveDate::from_ymd_opt(2016, 11, 13)
+                        .unwrap()
+                        .and_hms_opt(11, 2, 56)
+                        .unwrap()
+                ),
+                lat: Some(-42.6928),
+                lon: Some(173.0217),
+                depth_km: Some(15.1),
+                magnitude: Some(7.8),
+            })
+        );
+        assert_eq!(first.delta_sec, 0.005);
+        assert_eq!(
+            first.acc_values,
+            VALUES.iter().map(|v| v * 0.1).collect::<Vec<f64>>()
+        );
+        assert_eq!(components[2].orientation, Orientation::Up);
+    }
+
+    #[test]
+    fn test_parse_geonet_v2a() {
+        let text = [
+            component_text("S00E", &VALUES, true),
+            component_text("N90E", &VALUES, true),
+            component_text("UP", &VALUES, true),
+        ]
+        .concat();
+        let components = parse_geonet(&text, true, Path::new("test.V2A")).unwrap();
+
+        assert_eq!(components.len(), 3);
+        assert_eq!(components[1].orientation, Orientation::Horizontal(90.0));
+        assert_eq!(components[2].acc_values.len(), VALUES.len());
+    }
+
+    #[test]
+    fn test_parse_geonet_without_event() {
+        // 整数ヘッダーのインデックス8(地震の発生年)を0とする
+        let text = component_text("UP", &VALUES, false).replacen(
+            "       0    2016",
+            "       0       0",
+            1,
+        );
+        let components = parse_geonet(&text, false, Path::new("test.V1A")).unwrap();
+
+        assert_eq!(components[0].event, None);
+    }
+
+    #[test]
+    fn test_extract_group_rotated() {
+        // 北から28度西に回転して設置された観測点で、真北方向に揺れた記録
+        let (sin, cos) = 28.0_f64.to_radians().sin_cos();
+        let north: Vec<f64> = VALUES.to_vec();
+        let h1: Vec<f64> = north.iter().map(|v| v * cos).collect();
+        let h2: Vec<f64> = north.iter().map(|v| v * sin).collect();
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("20161113_110256_KIKS_20.V1A");
+        let mut file = File::create(&path).unwrap();
+        write!(
+            file,
+            "{}",
+            [
+                component_text("N28W", &h1, false),
+                component_text("N62E", &h2, false),
+                component_text("UP", &VALUES, false),
+            ]
+            .concat()
+        )
+        .unwrap();
+
+        let extractor = NzGeonetV1aV2aExtractor::new(ConversionConfig::for_test(
+            "kaikoura",
+            From::NzGeonetV1a,
+            To::JpStera3dTxt,
+            vec![FileConfig {
+                path,
+                acc_axis: None,
+            }],
+        ));
+
+        let extracted = extractor.extract().unwrap();
+        let data = &extracted[0];
+
+        assert_eq!(data.sampling_rate, 200.0);
+        for (ns, expected) in data.components.ns.iter().zip(&north) {
+            // 書式の精度(小数点以下1桁)による誤差を許容する
+            assert!((ns - expected * 0.1).abs() < 0.01);
+        }
+        for ew in &data.components.ew {
+            assert!(ew.abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn test_extract_group_misaligned() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("20161113_110256_KIKS_20.V1A");
+        // UDのみ記録開始が0.01秒(2サンプル)遅い
+        let text = [
+            component_text("N00E", &VALUES, false),
+            component_text("N90E", &VALUES, false),
+            component_text("UP", &VALUES, false).replacen(" 56.5000", " 56.5100", 1),
+        ]
+        .concat();
+        std::fs::write(&path, text).unwrap();
+
+        let extractor = NzGeonetV1aV2aExtractor::new(ConversionConfig::for_test(
+            "kaikoura",
+            From::NzGeonetV1a,
+            To::JpStera3dTxt,
+            vec![FileConfig {
+                path,
+                acc_axis: None,
+            }],
+        ));
+
+        assert!(matches!(
+            &extractor.extract_group(&extractor.unextracted.group[0], 1).unwrap_err()[..],
+            [DataExtractionErr::MismatchedComponents(reason, _, _)] if reason == "start time"
+        ));
+    }
+}
@@ -0,0 +1,422 @@
+use std::path::Path;
+
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime};
+
+use crate::{
+    analysis_config_file::{AccAxis, ConversionConfig, GroupConfig},
+    error::{AnalysisErr, AppError, DataExtractionErr},
+    record::{Components, Record, Station, Unit},
+};
+
+use super::{gal_factor, read_to_string, Extractor};
+
+// ヘッダーの行数
+const HEADER_LINES: usize = 4;
+
+// 2桁の年を1900年代として扱う境界
+const TWO_DIGIT_YEAR_PIVOT: i32 = 2030;
+
+/// Header of a PEER NGA AT2 file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct At2Header {
+    /// Start time, which is the date of the event at 00:00 if the file has no time.
+    pub start_time: NaiveDateTime,
+    /// Station name, which is the field following the date in the second line.
+    pub station: String,
+    pub npts: usize,
+    pub dt_sec: f64,
+    pub units: String,
+}
+
+/// One component of an AT2 record converted to gal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct At2Component {
+    pub header: At2Header,
+    pub acc_values: Vec<f64>,
+}
+
+pub struct PeerAt2Extractor {
+    pub unextracted: ConversionConfig,
+}
+
+impl Extractor for PeerAt2Extractor {
+    fn extract(&self) -> Result<Vec<Record>, Vec<AppError>> {
+        let mut extracted: Vec<Record> = Vec::new();
+        let mut errors: Vec<AppError> = Vec::new();
+
+        for (g_index, group_config) in self.unextracted.group.iter().enumerate() {
+            let id: usize = g_index + 1;
+            match self.extract_group(group_config, id) {
+                Ok(data) => extracted.push(data),
+                Err(e) => errors.extend(e.into_iter().map(|e| AnalysisErr::from(e).into())),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(extracted)
+    }
+}
+
+impl PeerAt2Extractor {
+    pub fn new(unextracted: ConversionConfig) -> Self {
+        Self { unextracted }
+    }
+
+    // 成分ごとのAT2ファイルを、acc_axisに従って1つのComponentsにまとめる
+    fn extract_group(
+        &self,
+        group_config: &GroupConfig,
+        id: usize,
+    ) -> Result<Record, Vec<DataExtractionErr>> {
+        let mut errors: Vec<DataExtractionErr> = Vec::new();
+        let mut ns: Option<At2Component> = None;
+        let mut ew: Option<At2Component> = None;
+        let mut ud: Option<At2Component> = None;
+
+        for file in &group_config.files {
+            let component = match parse_at2_file(&file.path) {
+                Ok(component) => component,
+                Err(e) => {
+                    errors.push(e);
+                    continue;
+                }
+            };
+
+            // AT2の成分名は方位角などで表されるため、設定ファイルのacc_axisに従う
+            match &file.acc_axis {
+                Some(AccAxis::Ns) => ns = Some(component),
+                Some(AccAxis::Ew) => ew = Some(component),
+                Some(AccAxis::Ud) => ud = Some(component),
+                None => errors.push(DataExtractionErr::RequiredAccAxis(file.path.clone())),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        let name = &self.unextracted.name;
+        let mismatched = |reason: &str| {
+            vec![DataExtractionErr::MismatchedComponents(
+                reason.to_string(),
+                name.to_string(),
+                id,
+            )]
+        };
+
+        let (Some(ns), Some(ew), Some(ud)) = (ns, ew, ud) else {
+            return Err(mismatched("ns, ew and ud are required"));
+        };
+        let components = [&ns, &ew, &ud];
+        if components
+            .iter()
+            .any(|c| c.header.station != ns.header.station)
+        {
+            return Err(mismatched("station"));
+        }
+        if components
+            .iter()
+            .any(|c| c.header.dt_sec != ns.header.dt_sec)
+        {
+            return Err(mismatched("DT"));
+        }
+        if components
+            .iter()
+            .any(|c| c.acc_values.len() != ns.acc_values.len())
+        {
+            return Err(mismatched("NPTS"));
+        }
+
+        let header = ns.header;
+        let components = Components {
+            ns: ns.acc_values,
+            ew: ew.acc_values,
+            ud: ud.acc_values,
+        };
+
+        // AT2には観測点の座標が書かれていないため、緯度経度は不明とする
+        Ok(Record {
+            station: Station {
+                code: header.station,
+                lat: None,
+                lon: None,
+                sensor_position: None,
+            },
+            event: None,
+            start_time: header.start_time,
+            sampling_rate: (1.0 / header.dt_sec) as f32,
+            unit: Unit::Gal,
+            components,
+            processing: Vec::new(),
+        })
+    }
+}
+
+/// Reads an AT2 file and converts the values to gal.
+pub fn parse_at2_file(path: &Path) -> Result<At2Component, DataExtractionErr> {
+    let contents = read_to_string(path)?;
+    parse_at2(&contents, path)
+}
+
+/// Parses the contents of an AT2 file, whose 4-line header is followed by the values of free width.
+///
+/// Both the NGA-West2 header(`NPTS=  3000, DT=   .0100 SEC`) and the older one(`3000   .0100   NPTS, DT`) are
+/// accepted in the fourth line.
+pub fn parse_at2(contents: &str, path: &Path) -> Result<At2Component, DataExtractionErr> {
+    let lines: Vec<&str> = contents.lines().collect();
+    if lines.len() < HEADER_LINES {
+        return Err(DataExtractionErr::InvalidStructure(path.to_path_buf()));
+    }
+
+    let invalid = |key: &str, value: &str| {
+        DataExtractionErr::InvalidHeaderValue(
+            key.to_string(),
+            value.trim().to_string(),
+            path.into(),
+        )
+    };
+
+    let (start_time, station) =
+        parse_event_line(lines[1]).ok_or_else(|| invalid("date", lines[1]))?;
+
+    let units = lines[2]
+        .to_uppercase()
+        .split_once("UNITS OF")
+        .and_then(|(_, units)| units.split_whitespace().next().map(str::to_lowercase))
+        .ok_or_else(|| DataExtractionErr::MissingHeaderField("UNITS".to_string(), path.into()))?;
+    let factor = gal_factor(&units).ok_or_else(|| invalid("UNITS", &units))?;
+
+    let (npts, dt_sec) = parse_npts_dt(lines[3]).ok_or_else(|| invalid("NPTS, DT", lines[3]))?;
+    if dt_sec <= 0.0 {
+        return Err(invalid("DT", &dt_sec.to_string()));
+    }
+
+    let raw_values: Vec<&str> = lines[HEADER_LINES..]
+        .iter()
+        .flat_map(|line| line.split_whitespace())
+        .collect();
+    if raw_values.len() < npts {
+        return Err(DataExtractionErr::InvalidStructure(path.to_path_buf()));
+    }
+
+    let acc_values = raw_values[..npts]
+        .iter()
+        .map(|value| {
+            value
+                .parse::<f64>()
+                .map(|v| v * factor)
+                .map_err(|_| DataExtractionErr::InvalidDataValue(value.to_string(), path.into()))
+        })
+        .collect::<Result<Vec<f64>, DataExtractionErr>>()?;
+
+    Ok(At2Component {
+        header: At2Header {
+            start_time,
+            station,
+            npts,
+            dt_sec,
+            units,
+        },
+        acc_values,
+    })
+}
+
+// 2行目(例: "IMPERIAL VALLEY 05/19/40, EL CENTRO ARRAY #9, 180")から日時と観測点名を取り出す
+fn parse_event_line(line: &str) -> Option<(NaiveDateTime, String)> {
+    let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+
+    fields.iter().enumerate().find_map(|(i, field)| {
+        let tokens: Vec<&str> = field.split_whitespace().collect();
+        let (pos, date) = tokens.iter().enumerate().find_map(|(pos, token)| {
+            let date = match NaiveDate::parse_from_str(token, "%m/%d/%y") {
+                // 強震記録は1930年代以降のため、2桁の年の30から99は1900年代とする
+                Ok(date) if date.year() >= TWO_DIGIT_YEAR_PIVOT => {
+                    date.with_year(date.year() - 100)
+                }
+                Ok(date) => Some(date),
+                Err(_) => NaiveDate::parse_from_str(token, "%m/%d/%Y").ok(),
+            };
+            date.map(|date| (pos, date))
+        })?;
+
+        let time = tokens
+            .get(pos + 1)
+            .and_then(|token| {
+                ["%H:%M:%S%.f", "%H:%M", "%H%M"]
+                    .iter()
+                    .find_map(|format| NaiveTime::parse_from_str(token, format).ok())
+            })
+            .unwrap_or_default();
+        let station = fields.get(i + 1).unwrap_or(&"").to_string();
+
+        Some((date.and_time(time), station))
+    })
+}
+
+// 4行目からNPTSとDTを取り出す
+fn parse_npts_dt(line: &str) -> Option<(usize, f64)> {
+    let upper = line.to_uppercase();
+
+    if upper.contains("NPTS=") {
+        let value = |key: &str| {
+            upper
+                .split(',')
+                .find_map(|field| field.trim().strip_prefix(key))
+                .and_then(|value| value.split_whitespace().next())
+        };
+        return Some((value("NPTS=")?.parse().ok()?, value("DT=")?.parse().ok()?));
+    }
+
+    let mut tokens = upper.split_whitespace();
+    Some((tokens.next()?.parse().ok()?, tokens.next()?.parse().ok()?))
+}
+
+/// This module contains unit tests for the PEER NGA AT2 parser.
+///
+/// # Test Categories
+///
+/// - `test_parse_at2`: Tests parsing of the NGA-West2 header and the values converted from g to gal
+/// - `test_parse_at2_old_header`: Tests parsing of the older `NPTS, DT` header
+/// - `test_parse_event_line`: Tests the date with two- and four-digit years, the optional time and the station
+/// - `test_extract_round_trip`: Tests extraction of the files written by `To::PeerAt2` and their conversion to JMA CSV
+///   without the station coordinates
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis_config_file::{FileConfig, From, To};
+    use crate::converter::jp_jma_csv::format_jp_jma_csv;
+    use crate::converter::peer_at2::{component_path, write_peer_at2};
+    use crate::record::{test_record, Component};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_parse_at2() {
+        let text = "PEER NGA STRONG MOTION DATABASE RECORD
+IMPERIAL VALLEY 05/19/40, EL CENTRO ARRAY #9, 180
+ACCELERATION TIME SERIES IN UNITS OF G
+NPTS=     7, DT=   .0100 SEC
+  .1000000E-01  -.2000000E-02   .0000000E+00  1.0000000E+00   .5000000E+00
+ -.1000000E+00   .3000000E-01
+";
+        let component = parse_at2(text, Path::new("test.at2")).unwrap();
+
+        assert_eq!(
+            component.header,
+            At2Header {
+                start_time: NaiveDate::from_ymd_opt(1940, 5, 19)
+                    .unwrap()
+                    .and_hms_opt(0, 0, 0)
+                    .unwrap(),
+                station: "EL CENTRO ARRAY #9".to_string(),
+                npts: 7,
+                dt_sec: 0.01,
+                units: "g".to_string(),
+            }
+        );
+        assert_eq!(component.acc_values.len(), 7);
+        assert!((component.acc_values[3] - 980.665).abs() < 1e-9);
+        assert!((component.acc_values[5] + 98.0665).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_parse_at2_old_header() {
+        let text = "PACIFIC ENGINEERING AND ANALYSIS STRONG-MOTION DATA
+ IMPERIAL VALLEY 05/19/40 0439, EL CENTRO ARRAY #9, 270
+ ACCELERATION TIME HISTORY IN UNITS OF G
+  3   .02000    NPTS, DT
+ .1 .2 .3
+";
+        let component = parse_at2(text, Path::new("test.at2")).unwrap();
+
+        assert_eq!(component.header.npts, 3);
+        assert_eq!(component.header.dt_sec, 0.02);
+        assert_eq!(
+            component.header.start_time,
+            NaiveDate::from_ymd_opt(1940, 5, 19)
+                .unwrap()
+                .and_hms_opt(4, 39, 0)
+                .unwrap()
+        );
+
+        let text = text.replace("  3   .02000", "  4   .02000");
+        assert_eq!(
+            parse_at2(&text, Path::new("test.at2")),
+            Err(DataExtractionErr::InvalidStructure("test.at2".into()))
+        );
+    }
+
+    #[test]
+    fn test_parse_event_line() {
+        let (start_time, station) =
+            parse_event_line("01/01/2024 16:10:09.500, ISK005, NS").unwrap();
+        assert_eq!(
+            start_time,
+            NaiveDate::from_ymd_opt(2024, 1, 1)
+                .unwrap()
+                .and_hms_milli_opt(16, 10, 9, 500)
+                .unwrap()
+        );
+        assert_eq!(station, "ISK005");
+
+        let (start_time, _) = parse_event_line("NORTHRIDGE 01/17/94 1231, SYLMAR, 360").unwrap();
+        assert_eq!(start_time.year(), 1994);
+        let (start_time, _) = parse_event_line("CHI-CHI 09/20/99, TCU065, E").unwrap();
+        assert_eq!(start_time.year(), 1999);
+        let (start_time, _) = parse_event_line("TOTTORI 10/06/00, TTR008, NS").unwrap();
+        assert_eq!(start_time.year(), 2000);
+
+        assert_eq!(parse_event_line("NORTHRIDGE, SYLMAR, 360"), None);
+    }
+
+    #[test]
+    fn test_extract_round_trip() {
+        let dir = tempdir().unwrap();
+        let output_path = dir.path().join("20240101-161009-ISK005-knet.at2");
+        let written = test_record(
+            vec![1.0, -2.0, 3.0],
+            vec![4.0, 5.0, -6.0],
+            vec![0.5, 0.0, -0.5],
+        );
+        write_peer_at2(&written.to_unit(Unit::G).unwrap(), &output_path).unwrap();
+
+        let files = [
+            (Component::Ns, AccAxis::Ns),
+            (Component::Ew, AccAxis::Ew),
+            (Component::Ud, AccAxis::Ud),
+        ]
+        .into_iter()
+        .map(|(component, acc_axis)| FileConfig {
+            path: component_path(&output_path, component),
+            acc_axis: Some(acc_axis),
+        })
+        .collect();
+        let extractor = PeerAt2Extractor::new(ConversionConfig::for_test(
+            "noto",
+            From::PeerAt2,
+            To::JpJmaCsv,
+            files,
+        ));
+
+        let extracted = extractor.extract().unwrap();
+        assert_eq!(extracted.len(), 1);
+        let data = &extracted[0];
+        assert_eq!(data.station().code, "ISK005");
+        assert_eq!((data.station().lat, data.station().lon), (None, None));
+        assert_eq!(data.start_time(), written.start_time);
+        assert_eq!(data.sampling_rate(), 100.0);
+        for component in Component::ALL {
+            let expected = written.components().get(component);
+            let actual = data.components().get(component);
+            for (a, e) in actual.iter().zip(expected) {
+                assert!((a - e).abs() < 1e-6 * e.abs().max(1.0));
+            }
+        }
+
+        let csv = format_jp_jma_csv(data);
+        assert!(csv.starts_with(" SITE CODE= ISK005\n LAT.= \n LON.= \n SAMPLING RATE= 100Hz\n"));
+        assert!(!csv.contains("NaN"));
+    }
+}
@@ -0,0 +1,504 @@
+use std::path::Path;
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+
+use crate::{
+    analysis_config_file::{AccAxis, ConversionConfig, GroupConfig},
+    error::{AnalysisErr, AppError, DataExtractionErr},
+    record::{Components, Event, Record, Station, Unit},
+};
+
+use super::{gal_factor, misaligned, read_to_string, Extractor, Orientation};
+
+/// Header of an AFAD ASCII file, which is written in the ESM format as `KEY: value` lines.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AfadHeader {
+    pub event_time: NaiveDateTime,
+    pub event_lat: Option<f64>,
+    pub event_lon: Option<f64>,
+    pub event_depth_km: Option<f64>,
+    /// Moment magnitude, or the local magnitude if the moment magnitude is not written.
+    pub magnitude: Option<f64>,
+    pub network: Option<String>,
+    pub station_code: String,
+    pub station_lat: f64,
+    pub station_lon: f64,
+    /// Time of the first sample, which falls back to the event time if it is not written.
+    pub start_time: NaiveDateTime,
+    pub sampling_interval_sec: f64,
+    pub units: String,
+    /// Stream code such as `HNE`, `HNN` and `HNZ`.
+    pub stream: String,
+}
+
+impl AfadHeader {
+    /// Returns the axis indicated by the last letter of the stream code.
+    pub fn acc_axis(&self) -> Option<AccAxis> {
+        match Orientation::from_channel_code(&self.stream)? {
+            Orientation::Horizontal(azimuth) if azimuth == 0.0 => Some(AccAxis::Ns),
+            Orientation::Horizontal(azimuth) if azimuth == 90.0 => Some(AccAxis::Ew),
+            Orientation::Up => Some(AccAxis::Ud),
+            _ => None,
+        }
+    }
+}
+
+/// One component of an AFAD record converted to gal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AfadComponent {
+    pub header: AfadHeader,
+    pub acc_values: Vec<f64>,
+}
+
+pub struct TkAfadAscExtractor {
+    pub unextracted: ConversionConfig,
+}
+
+impl Extractor for TkAfadAscExtractor {
+    fn extract(&self) -> Result<Vec<Record>, Vec<AppError>> {
+        let mut extracted: Vec<Record> = Vec::new();
+        let mut errors: Vec<AppError> = Vec::new();
+
+        for (g_index, group_config) in self.unextracted.group.iter().enumerate() {
+            let id: usize = g_index + 1;
+            match self.extract_group(group_config, id) {
+                Ok(data) => extracted.push(data),
+                Err(e) => errors.extend(e.into_iter().map(|e| AnalysisErr::from(e).into())),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(extracted)
+    }
+}
+
+impl TkAfadAscExtractor {
+    pub fn new(unextracted: ConversionConfig) -> Self {
+        Self { unextracted }
+    }
+
+    // HNN, HNE, HNZの3ファイルを1つのComponentsにまとめる
+    fn extract_group(
+        &self,
+        group_config: &GroupConfig,
+        id: usize,
+    ) -> Result<Record, Vec<DataExtractionErr>> {
+        let mut errors: Vec<DataExtractionErr> = Vec::new();
+        let mut ns: Option<AfadComponent> = None;
+        let mut ew: Option<AfadComponent> = None;
+        let mut ud: Option<AfadComponent> = None;
+
+        for file in &group_config.files {
+            let component = match parse_afad_file(&file.path) {
+                Ok(component) => component,
+                Err(e) => {
+                    errors.push(e);
+                    continue;
+                }
+            };
+
+            let Some(acc_axis) = &file.acc_axis else {
+                errors.push(DataExtractionErr::RequiredAccAxis(file.path.clone()));
+                continue;
+            };
+
+            // STREAMが示す成分と、設定ファイルのacc_axisが一致しているか
+            if component.header.acc_axis().as_ref() != Some(acc_axis) {
+                errors.push(DataExtractionErr::MismatchedAccAxis(
+                    component.header.stream.clone(),
+                    acc_axis.as_str().to_string(),
+                    file.path.clone(),
+                ));
+                continue;
+            }
+
+            match acc_axis {
+                AccAxis::Ns => ns = Some(component),
+                AccAxis::Ew => ew = Some(component),
+                AccAxis::Ud => ud = Some(component),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        let name = &self.unextracted.name;
+        let mismatched = |reason: &str| {
+            vec![DataExtractionErr::MismatchedComponents(
+                reason.to_string(),
+                name.to_string(),
+                id,
+            )]
+        };
+
+        let (Some(ns), Some(ew), Some(ud)) = (ns, ew, ud) else {
+            return Err(mismatched("ns, ew and ud are required"));
+        };
+        let components = [&ns, &ew, &ud];
+        if components
+            .iter()
+            .any(|c| c.header.station_code != ns.header.station_code)
+        {
+            return Err(mismatched("STATION_CODE"));
+        }
+        if components
+            .iter()
+            .any(|c| c.header.sampling_interval_sec != ns.header.sampling_interval_sec)
+        {
+            return Err(mismatched("SAMPLING_INTERVAL_S"));
+        }
+        let start_times = components.map(|c| c.header.start_time);
+        if misaligned(&start_times, 1.0 / ns.header.sampling_interval_sec) {
+            return Err(mismatched("DATE_TIME_FIRST_SAMPLE"));
+        }
+        if components
+            .iter()
+            .any(|c| c.acc_values.len() != ns.acc_values.len())
+        {
+            return Err(mismatched("number of data"));
+        }
+
+        let header = ns.header;
+        let components = Components {
+            ns: ns.acc_values,
+            ew: ew.acc_values,
+            ud: ud.acc_values,
+        };
+
+        Ok(Record {
+            station: Station {
+                code: header.station_code,
+                lat: Some(header.station_lat),
+                lon: Some(header.station_lon),
+                sensor_position: None,
+            },
+            event: Some(Event {
+                origin_time: Some(header.event_time),
+                lat: header.event_lat,
+                lon: header.event_lon,
+                depth_km: header.event_depth_km,
+                magnitude: header.magnitude,
+            }),
+            start_time: header.start_time,
+            sampling_rate: (1.0 / header.sampling_interval_sec) as f32,
+            unit: Unit::Gal,
+            components,
+            processing: Vec::new(),
+        })
+    }
+}
+
+/// Reads an AFAD ASCII file and converts the values to gal.
+pub fn parse_afad_file(path: &Path) -> Result<AfadComponent, DataExtractionErr> {
+    let contents = read_to_string(path)?;
+    parse_afad(&contents, path)
+}
+
+/// Parses the contents of an AFAD ASCII file, whose header is followed by one value per line.
+pub fn parse_afad(contents: &str, path: &Path) -> Result<AfadComponent, DataExtractionErr> {
+    let mut header_lines: Vec<(&str, &str)> = Vec::new();
+    let mut raw_values: Vec<&str> = Vec::new();
+
+    for line in contents.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        // ヘッダーが終わるまでは"KEY: value"の形式
+        match line.split_once(':') {
+            Some((key, value)) if raw_values.is_empty() => {
+                header_lines.push((key.trim(), value.trim()))
+            }
+            _ => raw_values.push(line),
+        }
+    }
+
+    let field = |key: &str| {
+        header_lines
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| *v)
+            .filter(|v| !v.is_empty())
+            .ok_or_else(|| DataExtractionErr::MissingHeaderField(key.to_string(), path.into()))
+    };
+    let invalid = |key: &str, value: &str| {
+        DataExtractionErr::InvalidHeaderValue(key.to_string(), value.to_string(), path.into())
+    };
+    let number = |key: &str| -> Result<f64, DataExtractionErr> {
+        let value = field(key)?;
+        value.parse().map_err(|_| invalid(key, value))
+    };
+    // 震源やマグニチュードは空欄のことがあるため、値が書かれている場合のみ読み込む
+    let optional_number = |key: &str| -> Result<Option<f64>, DataExtractionErr> {
+        field(key).ok().map(|_| number(key)).transpose()
+    };
+
+    let event_date = field("EVENT_DATE_YYYYMMDD")?;
+    let event_time = field("EVENT_TIME_HHMMSS")?;
+    let event_time = NaiveDate::parse_from_str(event_date, "%Y%m%d")
+        .map_err(|_| invalid("EVENT_DATE_YYYYMMDD", event_date))?
+        .and_time(
+            NaiveTime::parse_from_str(event_time, "%H%M%S%.f")
+                .map_err(|_| invalid("EVENT_TIME_HHMMSS", event_time))?,
+        );
+
+    let start_time = match field("DATE_TIME_FIRST_SAMPLE_YYYYMMDD_HHMMSS") {
+        Ok(value) => NaiveDateTime::parse_from_str(value, "%Y%m%d_%H%M%S%.f")
+            .map_err(|_| invalid("DATE_TIME_FIRST_SAMPLE_YYYYMMDD_HHMMSS", value))?,
+        Err(_) => event_time,
+    };
+
+    let sampling_interval_sec = number("SAMPLING_INTERVAL_S")?;
+    if sampling_interval_sec <= 0.0 {
+        return Err(invalid(
+            "SAMPLING_INTERVAL_S",
+            field("SAMPLING_INTERVAL_S")?,
+        ));
+    }
+
+    let header = AfadHeader {
+        event_time,
+        event_lat: optional_number("EVENT_LATITUDE_DEGREE")?,
+        event_lon: optional_number("EVENT_LONGITUDE_DEGREE")?,
+        event_depth_km: optional_number("EVENT_DEPTH_KM")?,
+        magnitude: match optional_number("MAGNITUDE_W")? {
+            Some(magnitude) => Some(magnitude),
+            None => optional_number("MAGNITUDE_L")?,
+        },
+        network: field("NETWORK").ok().map(str::to_string),
+        station_code: field("STATION_CODE")?.to_string(),
+        station_lat: number("STATION_LATITUDE_DEGREE")?,
+        station_lon: number("STATION_LONGITUDE_DEGREE")?,
+        start_time,
+        sampling_interval_sec,
+        units: field("UNITS")?.to_string(),
+        stream: field("STREAM")?.to_string(),
+    };
+
+    let factor = gal_factor(&header.units).ok_or_else(|| invalid("UNITS", &header.units))?;
+    let acc_values = raw_values
+        .iter()
+        .map(|value| {
+            value
+                .parse::<f64>()
+                .map(|v| v * factor)
+                .map_err(|_| DataExtractionErr::InvalidDataValue(value.to_string(), path.into()))
+        })
+        .collect::<Result<Vec<f64>, DataExtractionErr>>()?;
+
+    Ok(AfadComponent { header, acc_values })
+}
+
+/// This module contains unit tests for the AFAD ASCII parser.
+///
+/// # Test Categories
+///
+/// - `test_parse_afad`: Tests parsing of the header and the one-value-per-line body
+/// - `test_parse_afad_units`: Tests conversion of the values written in m/s^2 into gal
+/// - `test_parse_afad_missing_event`: Tests the event fields left blank and the local magnitude
+/// - `test_extract_group`: Tests the station and the event of the record built from three components
+/// - `test_extract_group_mismatched_acc_axis`: Tests detection of a STREAM that differs from acc_axis
+/// - `test_extract_group_misaligned`: Tests rejection of first samples more than half a sample apart
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis_config_file::{FileConfig, From, To};
+    use tempfile::tempdir;
+
+    fn afad_text(stream: &str, units: &str, values: &[f64]) -> String {
+        let body: Vec<String> = values.iter().map(|v| format!("{:.6}", v)).collect();
+        format!(
+            "EVENT_NAME: Kahramanmaras
+EVENT_ID: TK-2023-0062
+EVENT_DATE_YYYYMMDD: 20230206
+EVENT_TIME_HHMMSS: 011734
+EVENT_LATITUDE_DEGREE: 37.288
+EVENT_LONGITUDE_DEGREE: 37.043
+EVENT_DEPTH_KM: 8.6
+MAGNITUDE_W: 7.7
+NETWORK: TK
+STATION_CODE: 4614
+STATION_NAME:
+STATION_LATITUDE_DEGREE: 37.48
+STATION_LONGITUDE_DEGREE: 37.28
+DATE_TIME_FIRST_SAMPLE_YYYYMMDD_HHMMSS: 20230206_011742.500
+SAMPLING_INTERVAL_S: 0.010000
+NDATA: {}
+UNITS: {units}
+STREAM: {stream}
+USER1:
+{}
+",
+            values.len(),
+            body.join("\n")
+        )
+    }
+
+    #[test]
+    fn test_parse_afad() {
+        let text = afad_text("HNE", "cm/s^2", &[1.5, -2.0, 0.25]);
+        let component = parse_afad(&text, Path::new("test.asc")).unwrap();
+        let date = NaiveDate::from_ymd_opt(2023, 2, 6).unwrap();
+
+        assert_eq!(
+            component.header,
+            AfadHeader {
+                event_time: date.and_hms_opt(1, 17, 34).unwrap(),
+                event_lat: Some(37.288),
+                event_lon: Some(37.043),
+                event_depth_km: Some(8.6),
+                magnitude: Some(7.7),
+                network: Some("TK".to_string()),
+                station_code: "4614".to_string(),
+                station_lat: 37.48,
+                station_lon: 37.28,
+                start_time: date.and_hms_milli_opt(1, 17, 42, 500).unwrap(),
+                sampling_interval_sec: 0.01,
+                units: "cm/s^2".to_string(),
+                stream: "HNE".to_string(),
+            }
+        );
+        assert_eq!(component.header.acc_axis(), Some(AccAxis::Ew));
+        assert_eq!(component.acc_values, vec![1.5, -2.0, 0.25]);
+    }
+
+    #[test]
+    fn test_parse_afad_units() {
+        let text = afad_text("HNZ", "m/s^2", &[0.01, -0.02]);
+        let component = parse_afad(&text, Path::new("test.asc")).unwrap();
+
+        assert_eq!(component.acc_values, vec![1.0, -2.0]);
+    }
+
+    #[test]
+    fn test_parse_afad_missing_event() {
+        let text = afad_text("HNN", "cm/s^2", &[0.0])
+            .replace("EVENT_DEPTH_KM: 8.6", "EVENT_DEPTH_KM:")
+            .replace("MAGNITUDE_W: 7.7", "MAGNITUDE_W:\nMAGNITUDE_L: 7.4");
+        let header = parse_afad(&text, Path::new("test.asc")).unwrap().header;
+
+        assert_eq!(header.event_depth_km, None);
+        assert_eq!(header.magnitude, Some(7.4));
+
+        let text =
+            afad_text("HNN", "cm/s^2", &[0.0]).replace("MAGNITUDE_W: 7.7", "MAGNITUDE_W: M7");
+        assert!(matches!(
+            parse_afad(&text, Path::new("test.asc")).unwrap_err(),
+            DataExtractionErr::InvalidHeaderValue(key, _, _) if key == "MAGNITUDE_W"
+        ));
+    }
+
+    #[test]
+    fn test_extract_group() {
+        let dir = tempdir().unwrap();
+        let mut files: Vec<FileConfig> = Vec::new();
+        for (stream, acc_axis) in [
+            ("HNN", AccAxis::Ns),
+            ("HNE", AccAxis::Ew),
+            ("HNZ", AccAxis::Ud),
+        ] {
+            let path = dir.path().join(format!("20230206011734_4614_{stream}.asc"));
+            std::fs::write(&path, afad_text(stream, "cm/s^2", &[1.0, 2.0])).unwrap();
+            files.push(FileConfig {
+                path,
+                acc_axis: Some(acc_axis),
+            });
+        }
+
+        let extractor = TkAfadAscExtractor::new(ConversionConfig::for_test(
+            "kahramanmaras",
+            From::TkAfadAsc,
+            To::JpJmaCsv,
+            files,
+        ));
+        let extracted = extractor.extract().unwrap();
+        let data = &extracted[0];
+
+        assert_eq!(data.station.code, "4614");
+        assert_eq!(data.sampling_rate, 100.0);
+        assert_eq!(
+            data.event,
+            Some(Event {
+                origin_time: NaiveDate::from_ymd_opt(2023, 2, 6)
+                    .unwrap()
+                    .and_hms_opt(1, 17, 34),
+                lat: Some(37.288),
+                lon: Some(37.043),
+                depth_km: Some(8.6),
+                magnitude: Some(7.7),
+            })
+        );
+    }
+
+    #[test]
+    fn test_extract_group_mismatched_acc_axis() {
+        let dir = tempdir().unwrap();
+        let mut files: Vec<FileConfig> = Vec::new();
+
+        // HNEのファイルにnsが設定されている
+        for (stream, acc_axis) in [
+            ("HNE", AccAxis::Ns),
+            ("HNN", AccAxis::Ew),
+            ("HNZ", AccAxis::Ud),
+        ] {
+            let path = dir.path().join(format!("20230206011734_4614_{stream}.asc"));
+            std::fs::write(&path, afad_text(stream, "cm/s^2", &[1.0, 2.0])).unwrap();
+            files.push(FileConfig {
+                path,
+                acc_axis: Some(acc_axis),
+            });
+        }
+        let mismatched_path = files[0].path.clone();
+
+        let extractor = TkAfadAscExtractor::new(ConversionConfig::for_test(
+            "kahramanmaras",
+            From::TkAfadAsc,
+            To::JpJmaCsv,
+            files,
+        ));
+
+        let Err(errors) = extractor.extract() else {
+            panic!("Expected 'MismatchedAccAxis' error");
+        };
+        assert!(errors.contains(&AppError::Analysis(AnalysisErr::Extraction(
+            DataExtractionErr::MismatchedAccAxis(
+                "HNE".to_string(),
+                "ns".to_string(),
+                mismatched_path
+            )
+        ))));
+    }
+
+    #[test]
+    fn test_extract_group_misaligned() {
+        let dir = tempdir().unwrap();
+        let mut files: Vec<FileConfig> = Vec::new();
+        // UDのみ最初のサンプルが0.02秒(2サンプル)遅い
+        for (stream, acc_axis, first_sample) in [
+            ("HNN", AccAxis::Ns, "011742.500"),
+            ("HNE", AccAxis::Ew, "011742.500"),
+            ("HNZ", AccAxis::Ud, "011742.520"),
+        ] {
+            let path = dir.path().join(format!("20230206011734_4614_{stream}.asc"));
+            let text = afad_text(stream, "cm/s^2", &[1.0, 2.0]).replace("011742.500", first_sample);
+            std::fs::write(&path, text).unwrap();
+            files.push(FileConfig {
+                path,
+                acc_axis: Some(acc_axis),
+            });
+        }
+
+        let extractor = TkAfadAscExtractor::new(ConversionConfig::for_test(
+            "kahramanmaras",
+            From::TkAfadAsc,
+            To::JpJmaCsv,
+            files,
+        ));
+
+        assert!(matches!(
+            &extractor.extract_group(&extractor.unextracted.group[0], 1).unwrap_err()[..],
+            [DataExtractionErr::MismatchedComponents(reason, _, _)]
+                if reason == "DATE_TIME_FIRST_SAMPLE"
+        ));
+    }
+}
@@ -1,15 +1,131 @@
-use crate::{analysis_config_file::ConversionConfig, error::AppError};
+use std::path::Path;
 
-use super::{ExtractedData, Extractor};
+use chrono::{NaiveDate, NaiveDateTime, TimeDelta};
+
+use crate::{
+    analysis_config_file::{ConversionConfig, GroupConfig},
+    byte_order::{read_f32, read_i32, Endian},
+    error::{AnalysisErr, AppError, DataExtractionErr},
+    record::{Components, Event, Quantity, Record, Station, Unit},
+    sac::{
+        read_string, B, CMPAZ, CMPINC, DELTA, EVDP, EVLA, EVLO, FLOAT_OFFSET, IACC, IDEP,
+        INT_OFFSET, IUNKN, KCMPNM, KNETWK, KSTNM, KUSER0, MAG, NPTS, NVHDR, NZHOUR, NZJDAY, NZMIN,
+        NZMSEC, NZSEC, NZYEAR, O, SAC_HEADER_SIZE, STLA, STLO, SUPPORTED_NVHDR, UNDEFINED_FLOAT,
+        UNDEFINED_INT,
+    },
+};
+
+use super::{misaligned, orient_components, Extractor, Orientation};
+
+/// Header fields of a SAC file that are used by naifuru. Undefined(`-12345`) values are `None`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SacHeader {
+    pub endian: Endian,
+    pub delta: f32,
+    pub b: f32,
+    pub o: Option<f32>,
+    pub stla: Option<f32>,
+    pub stlo: Option<f32>,
+    pub evla: Option<f32>,
+    pub evlo: Option<f32>,
+    pub evdp: Option<f32>,
+    pub mag: Option<f32>,
+    pub cmpaz: Option<f32>,
+    pub cmpinc: Option<f32>,
+    /// Reference time built from NZYEAR, NZJDAY, NZHOUR, NZMIN, NZSEC and NZMSEC.
+    pub reference_time: NaiveDateTime,
+    pub nvhdr: i32,
+    pub npts: i32,
+    pub idep: Option<i32>,
+    pub kstnm: String,
+    /// Unit of the dependent variable, which naifuru writes here as IDEP has no field for it.
+    pub kuser0: Option<String>,
+    pub kcmpnm: Option<String>,
+    pub knetwk: Option<String>,
+}
+
+impl SacHeader {
+    /// Time of the first sample, which is the reference time shifted by B.
+    pub fn start_time(&self) -> NaiveDateTime {
+        self.reference_time + TimeDelta::microseconds((self.b as f64 * 1e6).round() as i64)
+    }
+
+    /// Returns the event given by O, EVLA, EVLO, EVDP and MAG, or `None` if all of them are undefined.
+    pub fn event(&self) -> Option<Event> {
+        let event = Event {
+            origin_time: self.o.map(|o| {
+                self.reference_time + TimeDelta::microseconds((o as f64 * 1e6).round() as i64)
+            }),
+            lat: self.evla.map(f64::from),
+            lon: self.evlo.map(f64::from),
+            depth_km: self.evdp.map(f64::from),
+            magnitude: self.mag.map(f64::from),
+        };
+
+        Some(event).filter(|event| *event != Event::default())
+    }
+
+    /// Returns the unit of acceleration given by KUSER0, or gal as recorded by P-Alert if it is undefined.
+    ///
+    /// Returns the invalid field and its value if IDEP is not acceleration or KUSER0 is not a unit of acceleration.
+    pub fn unit(&self) -> Result<Unit, (&str, String)> {
+        if let Some(idep) = self.idep.filter(|&idep| idep != IUNKN && idep != IACC) {
+            return Err(("IDEP", idep.to_string()));
+        }
+
+        match &self.kuser0 {
+            Some(label) => Unit::from_label(label)
+                .filter(|unit| unit.quantity() == Quantity::Acceleration)
+                .ok_or(("KUSER0", label.clone())),
+            None => Ok(Unit::Gal),
+        }
+    }
+
+    /// Returns the orientation given by CMPAZ/CMPINC, or by the last letter of KCMPNM if they are undefined.
+    pub fn orientation(&self) -> Option<Orientation> {
+        if let (Some(cmpaz), Some(cmpinc)) = (self.cmpaz, self.cmpinc) {
+            // CMPINCは鉛直上向きからの角度
+            return match cmpinc.round() as i32 {
+                0 => Some(Orientation::Up),
+                180 => Some(Orientation::Down),
+                90 => Some(Orientation::Horizontal(cmpaz as f64)),
+                _ => None,
+            };
+        }
+
+        Orientation::from_channel_code(self.kcmpnm.as_ref()?)
+    }
+}
+
+/// One trace of a SAC file. P-Alert records acceleration in gal, or in counts if a sensitivity is configured.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SacTrace {
+    pub header: SacHeader,
+    pub data: Vec<f32>,
+}
 
 pub struct TwPalertSacExtractor {
     pub unextracted: ConversionConfig,
 }
 
 impl Extractor for TwPalertSacExtractor {
-    fn extract(&self) -> Result<ExtractedData, Vec<AppError>> {
-        // Match文で、Toごとに抽出を切り替える
-        todo!()
+    fn extract(&self) -> Result<Vec<Record>, Vec<AppError>> {
+        let mut extracted: Vec<Record> = Vec::new();
+        let mut errors: Vec<AppError> = Vec::new();
+
+        for (g_index, group_config) in self.unextracted.group.iter().enumerate() {
+            let id: usize = g_index + 1;
+            match self.extract_group(group_config, id) {
+                Ok(data) => extracted.push(data),
+                Err(e) => errors.extend(e.into_iter().map(|e| AnalysisErr::from(e).into())),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(extracted)
     }
 }
 
@@ -17,4 +133,565 @@ impl TwPalertSacExtractor {
     pub fn new(unextracted: ConversionConfig) -> Self {
         Self { unextracted }
     }
+
+    // 成分ごとのSACファイルを、ヘッダーの成分情報から1つのComponentsにまとめる
+    fn extract_group(
+        &self,
+        group_config: &GroupConfig,
+        id: usize,
+    ) -> Result<Record, Vec<DataExtractionErr>> {
+        let mut errors: Vec<DataExtractionErr> = Vec::new();
+        let mut traces: Vec<(Orientation, Unit, SacTrace)> = Vec::new();
+        let sensitivity = self.unextracted.sensitivity;
+
+        for file in &group_config.files {
+            let trace = match parse_sac_file(&file.path) {
+                Ok(trace) => trace,
+                Err(e) => {
+                    errors.push(e);
+                    continue;
+                }
+            };
+
+            // 感度が設定されている場合はカウント値とみなし、KUSER0の単位は使わない
+            let unit = match sensitivity {
+                Some(_) => Ok(Unit::Gal),
+                None => trace.header.unit(),
+            };
+            let unit = match unit {
+                Ok(unit) => unit,
+                Err((key, value)) => {
+                    errors.push(DataExtractionErr::InvalidHeaderValue(
+                        key.to_string(),
+                        value,
+                        file.path.clone(),
+                    ));
+                    continue;
+                }
+            };
+
+            match trace.header.orientation() {
+                Some(orientation) => traces.push((orientation, unit, trace)),
+                None => errors.push(DataExtractionErr::InvalidHeaderValue(
+                    "CMPAZ/CMPINC/KCMPNM".to_string(),
+                    format!(
+                        "{:?}/{:?}/{:?}",
+                        trace.header.cmpaz, trace.header.cmpinc, trace.header.kcmpnm
+                    ),
+                    file.path.clone(),
+                )),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        let name = &self.unextracted.name;
+        let mismatched = |reason: &str| {
+            vec![DataExtractionErr::MismatchedComponents(
+                reason.to_string(),
+                name.to_string(),
+                id,
+            )]
+        };
+
+        let Some(&(_, unit, ref first)) = traces.first() else {
+            return Err(mismatched("no components"));
+        };
+        if traces
+            .iter()
+            .any(|(_, _, t)| t.header.kstnm != first.header.kstnm)
+        {
+            return Err(mismatched("KSTNM"));
+        }
+        if traces
+            .iter()
+            .any(|(_, _, t)| t.header.delta != first.header.delta)
+        {
+            return Err(mismatched("DELTA"));
+        }
+        if traces.iter().any(|&(_, u, _)| u != unit) {
+            return Err(mismatched("KUSER0"));
+        }
+        let start_times: Vec<NaiveDateTime> = traces
+            .iter()
+            .map(|(_, _, t)| t.header.start_time())
+            .collect();
+        if misaligned(&start_times, 1.0 / first.header.delta as f64) {
+            return Err(mismatched("start time"));
+        }
+
+        let site_code = first.header.kstnm.clone();
+        let lat = first.header.stla.map(f64::from);
+        let lon = first.header.stlo.map(f64::from);
+        let initial_time = first.header.start_time();
+        let sampling_rate = 1.0 / first.header.delta;
+        let event = first.header.event();
+
+        let gal_per_count = match sensitivity {
+            Some(sensitivity) => sensitivity
+                .gal_per_count()
+                .ok_or_else(|| mismatched("sensitivity in a unit of acceleration"))?,
+            None => 1.0,
+        };
+        let components: Components = orient_components(
+            traces
+                .into_iter()
+                .map(|(orientation, _, t)| {
+                    (
+                        orientation,
+                        t.data.iter().map(|&v| v as f64 * gal_per_count).collect(),
+                    )
+                })
+                .collect(),
+        )
+        .map_err(|reason| mismatched(&reason))?;
+
+        Ok(Record {
+            station: Station {
+                code: site_code,
+                lat,
+                lon,
+                sensor_position: None,
+            },
+            event,
+            start_time: initial_time,
+            sampling_rate,
+            unit,
+            components,
+            processing: Vec::new(),
+        })
+    }
+}
+
+/// Reads a binary SAC file.
+pub fn parse_sac_file(path: &Path) -> Result<SacTrace, DataExtractionErr> {
+    let bytes =
+        std::fs::read(path).map_err(|e| DataExtractionErr::Io(path.to_path_buf(), e.into()))?;
+    parse_sac(&bytes, path)
+}
+
+/// Parses a binary SAC file, whose byte order is detected from NVHDR.
+pub fn parse_sac(bytes: &[u8], path: &Path) -> Result<SacTrace, DataExtractionErr> {
+    if bytes.len() < SAC_HEADER_SIZE {
+        return Err(DataExtractionErr::InvalidStructure(path.to_path_buf()));
+    }
+
+    // NVHDRが正しいバージョンとして読める方のバイトオーダーを採用する
+    let endian = [Endian::Little, Endian::Big]
+        .into_iter()
+        .find(|&endian| SUPPORTED_NVHDR.contains(&read_i32(bytes, INT_OFFSET + NVHDR * 4, endian)))
+        .ok_or_else(|| {
+            DataExtractionErr::InvalidHeaderValue(
+                "NVHDR".to_string(),
+                read_i32(bytes, INT_OFFSET + NVHDR * 4, Endian::Little).to_string(),
+                path.into(),
+            )
+        })?;
+
+    let float = |index: usize| read_f32(bytes, FLOAT_OFFSET + index * 4, endian);
+    let int = |index: usize| read_i32(bytes, INT_OFFSET + index * 4, endian);
+    let defined_float = |index: usize| Some(float(index)).filter(|&v| v != UNDEFINED_FLOAT);
+    let invalid = |key: &str, value: String| {
+        DataExtractionErr::InvalidHeaderValue(key.to_string(), value, path.into())
+    };
+
+    let delta = float(DELTA);
+    if delta <= 0.0 || delta == UNDEFINED_FLOAT {
+        return Err(invalid("DELTA", delta.to_string()));
+    }
+    let npts = int(NPTS);
+    let num_of_points = usize::try_from(npts).map_err(|_| invalid("NPTS", npts.to_string()))?;
+
+    let [year, jday, hour, min, sec, msec] =
+        [NZYEAR, NZJDAY, NZHOUR, NZMIN, NZSEC, NZMSEC].map(int);
+    if year == UNDEFINED_INT {
+        return Err(invalid("NZYEAR", year.to_string()));
+    }
+    let reference_time = NaiveDate::from_yo_opt(year, jday as u32)
+        .and_then(|date| date.and_hms_milli_opt(hour as u32, min as u32, sec as u32, msec as u32))
+        .ok_or_else(|| {
+            invalid(
+                "NZYEAR/NZJDAY/NZHOUR/NZMIN/NZSEC/NZMSEC",
+                format!("{year}/{jday}/{hour}/{min}/{sec}/{msec}"),
+            )
+        })?;
+
+    let header = SacHeader {
+        endian,
+        delta,
+        b: defined_float(B).unwrap_or_default(),
+        o: defined_float(O),
+        stla: defined_float(STLA),
+        stlo: defined_float(STLO),
+        evla: defined_float(EVLA),
+        evlo: defined_float(EVLO),
+        evdp: defined_float(EVDP),
+        mag: defined_float(MAG),
+        cmpaz: defined_float(CMPAZ),
+        cmpinc: defined_float(CMPINC),
+        reference_time,
+        nvhdr: int(NVHDR),
+        npts,
+        idep: Some(int(IDEP)).filter(|&v| v != UNDEFINED_INT),
+        kstnm: read_string(bytes, KSTNM).unwrap_or_default(),
+        kuser0: read_string(bytes, KUSER0),
+        kcmpnm: read_string(bytes, KCMPNM),
+        knetwk: read_string(bytes, KNETWK),
+    };
+
+    let data_end = SAC_HEADER_SIZE + num_of_points * 4;
+    if bytes.len() < data_end {
+        return Err(DataExtractionErr::InvalidStructure(path.to_path_buf()));
+    }
+    let data: Vec<f32> = (SAC_HEADER_SIZE..data_end)
+        .step_by(4)
+        .map(|offset| read_f32(bytes, offset, endian))
+        .collect();
+
+    Ok(SacTrace { header, data })
+}
+
+/// This module contains unit tests for the SAC reader.
+///
+/// # Test Categories
+///
+/// - `test_parse_sac_endian`: Tests parsing of the same trace written in little and big endian
+/// - `test_parse_sac_invalid_nvhdr`: Tests rejection of an unknown header version
+/// - `test_orientation`: Tests the orientation given by CMPAZ/CMPINC and by KCMPNM
+/// - `test_extract_group`: Tests merging of the HLN, HLE and HLZ traces into a single record
+/// - `test_extract_group_misaligned`: Tests rejection of traces whose B differs by more than half a sample
+/// - `test_extract_group_unit`: Tests the unit read from IDEP/KUSER0 of written SAC files and the rejection of velocity
+/// - `test_extract_group_sensitivity`: Tests conversion of the counts to gal with the sensitivity
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        analysis_config_file::{FileConfig, From, SacConfig, SensitivityConfig, To},
+        converter::sac::encode_sac,
+        record::{test_record, Component},
+    };
+    use tempfile::tempdir;
+
+    struct TestTrace<'a> {
+        kcmpnm: &'a str,
+        cmpaz: f32,
+        cmpinc: f32,
+        data: &'a [f32],
+    }
+
+    fn sac_bytes(trace: &TestTrace, endian: Endian) -> Vec<u8> {
+        let mut floats = [UNDEFINED_FLOAT; 70];
+        floats[DELTA] = 0.01;
+        floats[B] = 1.5;
+        floats[STLA] = 23.97;
+        floats[STLO] = 121.6;
+        floats[CMPAZ] = trace.cmpaz;
+        floats[CMPINC] = trace.cmpinc;
+
+        let mut ints = [UNDEFINED_INT; 40];
+        ints[NZYEAR] = 2024;
+        ints[NZJDAY] = 93;
+        ints[NZHOUR] = 23;
+        ints[NZMIN] = 58;
+        ints[NZSEC] = 9;
+        ints[NZMSEC] = 250;
+        ints[NVHDR] = 6;
+        ints[NPTS] = trace.data.len() as i32;
+
+        let mut strings = [b' '; 192];
+        for (offset, value) in [
+            (KSTNM.0, "W21D"),
+            (KCMPNM.0, trace.kcmpnm),
+            (KNETWK.0, "TW"),
+        ] {
+            let start = offset - 440;
+            strings[start..start + value.len()].copy_from_slice(value.as_bytes());
+        }
+
+        let mut bytes: Vec<u8> = Vec::new();
+        for v in floats.iter().chain(trace.data) {
+            bytes.extend(match endian {
+                Endian::Little => v.to_le_bytes(),
+                Endian::Big => v.to_be_bytes(),
+            });
+        }
+        let data_bytes = bytes.split_off(280);
+        for v in ints {
+            bytes.extend(match endian {
+                Endian::Little => v.to_le_bytes(),
+                Endian::Big => v.to_be_bytes(),
+            });
+        }
+        bytes.extend(strings);
+        bytes.extend(data_bytes);
+        bytes
+    }
+
+    const NS_TRACE: TestTrace = TestTrace {
+        kcmpnm: "HLN",
+        cmpaz: 0.0,
+        cmpinc: 90.0,
+        data: &[1.0, -2.0, 3.5],
+    };
+
+    #[test]
+    fn test_parse_sac_endian() {
+        let path = Path::new("test.sac");
+        let little = parse_sac(&sac_bytes(&NS_TRACE, Endian::Little), path).unwrap();
+        let big = parse_sac(&sac_bytes(&NS_TRACE, Endian::Big), path).unwrap();
+
+        assert_eq!(little.header.endian, Endian::Little);
+        assert_eq!(big.header.endian, Endian::Big);
+        assert_eq!(
+            SacHeader {
+                endian: Endian::Little,
+                ..big.header.clone()
+            },
+            little.header
+        );
+
+        let header = little.header;
+        assert_eq!(header.delta, 0.01);
+        assert_eq!(header.npts, 3);
+        assert_eq!(header.stla, Some(23.97));
+        assert_eq!(header.evla, None);
+        assert_eq!(header.kstnm, "W21D");
+        assert_eq!(header.kcmpnm, Some("HLN".to_string()));
+        assert_eq!(header.knetwk, Some("TW".to_string()));
+        assert_eq!(
+            header.start_time(),
+            NaiveDate::from_yo_opt(2024, 93)
+                .unwrap()
+                .and_hms_milli_opt(23, 58, 10, 750)
+                .unwrap()
+        );
+        assert_eq!(little.data, vec![1.0, -2.0, 3.5]);
+        assert_eq!(big.data, little.data);
+    }
+
+    #[test]
+    fn test_parse_sac_invalid_nvhdr() {
+        let path = Path::new("test.sac");
+        let mut bytes = sac_bytes(&NS_TRACE, Endian::Little);
+        bytes[INT_OFFSET + NVHDR * 4..INT_OFFSET + NVHDR * 4 + 4].copy_from_slice(&[0, 0, 0, 1]);
+
+        assert!(matches!(
+            parse_sac(&bytes, path).unwrap_err(),
+            DataExtractionErr::InvalidHeaderValue(key, _, _) if key == "NVHDR"
+        ));
+    }
+
+    #[test]
+    fn test_orientation() {
+        let path = Path::new("test.sac");
+        let trace = |kcmpnm, cmpaz, cmpinc| {
+            let test_trace = TestTrace {
+                kcmpnm,
+                cmpaz,
+                cmpinc,
+                data: &[0.0],
+            };
+            parse_sac(&sac_bytes(&test_trace, Endian::Little), path).unwrap()
+        };
+
+        assert_eq!(
+            trace("HLE", 90.0, 90.0).header.orientation(),
+            Some(Orientation::Horizontal(90.0))
+        );
+        assert_eq!(
+            trace("HLZ", 0.0, 0.0).header.orientation(),
+            Some(Orientation::Up)
+        );
+        assert_eq!(
+            trace("HLE", UNDEFINED_FLOAT, UNDEFINED_FLOAT)
+                .header
+                .orientation(),
+            Some(Orientation::Horizontal(90.0))
+        );
+        assert_eq!(
+            trace("HLX", UNDEFINED_FLOAT, UNDEFINED_FLOAT)
+                .header
+                .orientation(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_extract_group() {
+        let dir = tempdir().unwrap();
+        let traces = [
+            (
+                "HLE",
+                Endian::Big,
+                TestTrace {
+                    kcmpnm: "HLE",
+                    cmpaz: 90.0,
+                    cmpinc: 90.0,
+                    data: &[4.0, 5.0, 6.0],
+                },
+            ),
+            ("HLN", Endian::Little, NS_TRACE),
+            (
+                "HLZ",
+                Endian::Little,
+                TestTrace {
+                    kcmpnm: "HLZ",
+                    cmpaz: UNDEFINED_FLOAT,
+                    cmpinc: UNDEFINED_FLOAT,
+                    data: &[7.0, 8.0, 9.0],
+                },
+            ),
+        ];
+
+        let mut files: Vec<FileConfig> = Vec::new();
+        for (name, endian, trace) in traces {
+            let path = dir.path().join(format!("W21D.{name}.sac"));
+            std::fs::write(&path, sac_bytes(&trace, endian)).unwrap();
+            files.push(FileConfig {
+                path,
+                acc_axis: None,
+            });
+        }
+
+        let extractor = TwPalertSacExtractor::new(ConversionConfig::for_test(
+            "hualien",
+            From::TwPalertSac,
+            To::JpJmaCsv,
+            files,
+        ));
+
+        let extracted = extractor.extract().unwrap();
+        let data = &extracted[0];
+
+        assert_eq!(data.station.code, "W21D");
+        assert_eq!(data.event, None);
+        assert_eq!(data.sampling_rate, 100.0);
+        for (actual, expected) in [
+            (&data.components.ns, [1.0, -2.0, 3.5]),
+            (&data.components.ew, [4.0, 5.0, 6.0]),
+            (&data.components.ud, [7.0, 8.0, 9.0]),
+        ] {
+            for (a, e) in actual.iter().zip(expected) {
+                assert!((a - e).abs() < 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn test_extract_group_misaligned() {
+        let dir = tempdir().unwrap();
+        let extract = |ew_b: f32| {
+            let mut files: Vec<FileConfig> = Vec::new();
+            for (kcmpnm, cmpaz, cmpinc) in
+                [("HLN", 0.0, 90.0), ("HLE", 90.0, 90.0), ("HLZ", 0.0, 0.0)]
+            {
+                let trace = TestTrace {
+                    kcmpnm,
+                    cmpaz,
+                    cmpinc,
+                    data: &[1.0, 2.0, 3.0],
+                };
+                let mut bytes = sac_bytes(&trace, Endian::Little);
+                if kcmpnm == "HLE" {
+                    bytes[FLOAT_OFFSET + B * 4..FLOAT_OFFSET + B * 4 + 4]
+                        .copy_from_slice(&ew_b.to_le_bytes());
+                }
+                let path = dir.path().join(format!("W21D.{kcmpnm}.sac"));
+                std::fs::write(&path, bytes).unwrap();
+                files.push(FileConfig {
+                    path,
+                    acc_axis: None,
+                });
+            }
+
+            let extractor = TwPalertSacExtractor::new(ConversionConfig::for_test(
+                "hualien",
+                From::TwPalertSac,
+                To::JpJmaCsv,
+                files,
+            ));
+            extractor.extract_group(&extractor.unextracted.group[0], 1)
+        };
+
+        // DELTAは0.01秒のため、0.005秒を超えるずれは許容しない
+        assert!(extract(1.504).is_ok());
+        assert!(matches!(
+            &extract(1.52).unwrap_err()[..],
+            [DataExtractionErr::MismatchedComponents(reason, _, _)] if reason == "start time"
+        ));
+    }
+
+    fn assert_components(data: &Record, expected: [[f64; 2]; 3]) {
+        let components = &data.components;
+        for (actual, expected) in [&components.ns, &components.ew, &components.ud]
+            .into_iter()
+            .zip(expected)
+        {
+            for (a, e) in actual.iter().zip(expected) {
+                assert!((a - e).abs() < 1e-6);
+            }
+        }
+    }
+
+    // 記録の3成分をSACファイルとして書き込み、1つのグループとして読み込む
+    fn extract_written(
+        data: &Record,
+        sensitivity: Option<SensitivityConfig>,
+    ) -> Result<Record, Vec<DataExtractionErr>> {
+        let dir = tempdir().unwrap();
+        let mut files: Vec<FileConfig> = Vec::new();
+        for component in [Component::Ns, Component::Ew, Component::Ud] {
+            let path = dir
+                .path()
+                .join(format!("ISK005.{}.sac", component.as_str()));
+            std::fs::write(&path, encode_sac(data, component, &SacConfig::default())).unwrap();
+            files.push(FileConfig {
+                path,
+                acc_axis: None,
+            });
+        }
+
+        let extractor = TwPalertSacExtractor::new(ConversionConfig {
+            sensitivity,
+            ..ConversionConfig::for_test("noto", From::TwPalertSac, To::JpJmaCsv, files)
+        });
+        extractor.extract_group(&extractor.unextracted.group[0], 1)
+    }
+
+    #[test]
+    fn test_extract_group_unit() {
+        let written = |unit: Unit| Record {
+            unit,
+            ..test_record(vec![1.0, 2.0], vec![3.0, 4.0], vec![5.0, 6.0])
+        };
+
+        let data = extract_written(&written(Unit::MetersPerSecondSquared), None).unwrap();
+        assert_eq!(data.unit, Unit::MetersPerSecondSquared);
+        assert_components(&data, [[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]]);
+
+        let errors = extract_written(&written(Unit::CentimetersPerSecond), None).unwrap_err();
+        assert_eq!(errors.len(), 3);
+        assert!(errors.iter().all(|e| matches!(
+            e,
+            DataExtractionErr::InvalidHeaderValue(key, value, _) if key == "IDEP" && value == "7"
+        )));
+    }
+
+    #[test]
+    fn test_extract_group_sensitivity() {
+        let sensitivity = SensitivityConfig {
+            counts_per_unit: 4.0,
+            unit: Unit::MetersPerSecondSquared,
+        };
+        let counts = Record {
+            unit: Unit::MetersPerSecondSquared,
+            ..test_record(vec![1.0, 2.0], vec![3.0, 4.0], vec![5.0, 6.0])
+        };
+
+        let data = extract_written(&counts, Some(sensitivity)).unwrap();
+        assert_eq!(data.unit, Unit::Gal);
+        assert_components(&data, [[25.0, 50.0], [75.0, 100.0], [125.0, 150.0]]);
+    }
 }
@@ -0,0 +1,756 @@
+use std::path::Path;
+
+use chrono::NaiveDateTime;
+
+use crate::{
+    analysis_config_file::{ConversionConfig, GroupConfig},
+    error::{AnalysisErr, AppError, DataExtractionErr},
+    record::{Components, Event, Record, Station, Unit},
+};
+
+use super::{
+    gal_factor, misaligned, orient_components, parse_fixed_width, read_to_string, take_lines,
+    Extractor, Orientation,
+};
+
+// 1成分あたりのヘッダーの行数
+const TEXT_HEADER_LINES: usize = 25;
+const INT_HEADER_LINES: usize = 7;
+const REAL_HEADER_LINES: usize = 10;
+
+// 整数ヘッダーは(16I5)、実数ヘッダーは(10F8.3)の固定長書式
+const INT_HEADER_WIDTH: usize = 5;
+const REAL_HEADER_WIDTH: usize = 8;
+
+const DATE_TIME_FORMATS: [&str; 2] = ["%m/%d/%y %H:%M:%S%.f", "%m/%d/%Y %H:%M:%S%.f"];
+
+/// Event information written in the text header of an SCSN V2 file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScsnV2Event {
+    pub origin_time: NaiveDateTime,
+    pub lat: f64,
+    pub lon: f64,
+    pub depth_km: f64,
+    pub magnitude: f64,
+}
+
+/// One component of an SCSN V2 corrected accelerogram.
+///
+/// A V2 file consists of three components, each of which has the following layout.
+/// - Text header(25 lines): channel orientation(`Chan  1: 360 Deg`, `Chan  3: Up`), event(`Hypocenter:`, `Origin:`),
+///   station(`Code:`, `Coords:`) and `Rcrd start time:`.
+/// - Integer header(100 values of `16I5`, 7 lines) and real header(100 values of `10F8.3`, 10 lines).
+/// - Acceleration, velocity and displacement sections, each of which starts with a line such as
+///   `8000 points of accel data equally spaced at .010 sec, in cm/sec2. (8f10.3)`. Only the acceleration is read,
+///   as the velocity and displacement are integrated from it when needed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScsnV2Component {
+    pub network_code: String,
+    pub station_code: String,
+    pub station_lat: f64,
+    pub station_lon: f64,
+    pub orientation: Orientation,
+    pub start_time: NaiveDateTime,
+    pub event: Option<ScsnV2Event>,
+    pub int_header: Vec<i32>,
+    pub real_header: Vec<f64>,
+    pub delta_sec: f64,
+    /// Acceleration in gal.
+    pub acc_values: Vec<f64>,
+}
+
+pub struct UsScsnV2Extractor {
+    pub unextracted: ConversionConfig,
+}
+
+impl Extractor for UsScsnV2Extractor {
+    fn extract(&self) -> Result<Vec<Record>, Vec<AppError>> {
+        let mut extracted: Vec<Record> = Vec::new();
+        let mut errors: Vec<AppError> = Vec::new();
+
+        for (g_index, group_config) in self.unextracted.group.iter().enumerate() {
+            let id: usize = g_index + 1;
+            match self.extract_group(group_config, id) {
+                Ok(data) => extracted.push(data),
+                Err(e) => errors.extend(e.into_iter().map(|e| AnalysisErr::from(e).into())),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(extracted)
+    }
+}
+
+impl UsScsnV2Extractor {
+    pub fn new(unextracted: ConversionConfig) -> Self {
+        Self { unextracted }
+    }
+
+    // グループ内の全ファイルの成分を1つのComponentsにまとめる
+    fn extract_group(
+        &self,
+        group_config: &GroupConfig,
+        id: usize,
+    ) -> Result<Record, Vec<DataExtractionErr>> {
+        let mut errors: Vec<DataExtractionErr> = Vec::new();
+        let mut components: Vec<ScsnV2Component> = Vec::new();
+
+        for file in &group_config.files {
+            match parse_scsn_v2_file(&file.path) {
+                Ok(c) => components.extend(c),
+                Err(e) => errors.push(e),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        let name = &self.unextracted.name;
+        let mismatched = |reason: &str| {
+            vec![DataExtractionErr::MismatchedComponents(
+                reason.to_string(),
+                name.to_string(),
+                id,
+            )]
+        };
+
+        let Some(first) = components.first() else {
+            return Err(mismatched("no components"));
+        };
+        if components
+            .iter()
+            .any(|c| c.station_code != first.station_code)
+        {
+            return Err(mismatched("station code"));
+        }
+        if components.iter().any(|c| c.delta_sec != first.delta_sec) {
+            return Err(mismatched("sampling interval"));
+        }
+        let start_times: Vec<NaiveDateTime> = components.iter().map(|c| c.start_time).collect();
+        if misaligned(&start_times, 1.0 / first.delta_sec) {
+            return Err(mismatched("Rcrd start time"));
+        }
+
+        let site_code = first.station_code.clone();
+        let (lat, lon) = (Some(first.station_lat), Some(first.station_lon));
+        let initial_time = first.start_time;
+        let sampling_rate = (1.0 / first.delta_sec) as f32;
+        let event = first.event.as_ref().map(|event| Event {
+            origin_time: Some(event.origin_time),
+            lat: Some(event.lat),
+            lon: Some(event.lon),
+            depth_km: Some(event.depth_km),
+            magnitude: Some(event.magnitude),
+        });
+
+        let components: Components = orient_components(
+            components
+                .into_iter()
+                .map(|c| (c.orientation, c.acc_values))
+                .collect(),
+        )
+        .map_err(|reason| mismatched(&reason))?;
+
+        Ok(Record {
+            station: Station {
+                code: site_code,
+                lat,
+                lon,
+                sensor_position: None,
+            },
+            event,
+            start_time: initial_time,
+            sampling_rate,
+            unit: Unit::Gal,
+            components,
+            processing: Vec::new(),
+        })
+    }
+}
+
+/// Reads an SCSN V2 file, which contains three components.
+pub fn parse_scsn_v2_file(path: &Path) -> Result<Vec<ScsnV2Component>, DataExtractionErr> {
+    let contents = read_to_string(path)?;
+    parse_scsn_v2(&contents, path)
+}
+
+/// Parses the contents of an SCSN V2 file into its components.
+pub fn parse_scsn_v2(
+    contents: &str,
+    path: &Path,
+) -> Result<Vec<ScsnV2Component>, DataExtractionErr> {
+    let lines: Vec<&str> = contents.lines().collect();
+    let mut components: Vec<ScsnV2Component> = Vec::new();
+    let mut pos: usize = 0;
+
+    loop {
+        // 成分間の空行と、"End of data"のような終端行を読み飛ばす
+        while pos < lines.len() && is_separator(lines[pos]) {
+            pos += 1;
+        }
+        if pos >= lines.len() {
+            break;
+        }
+
+        components.push(parse_component(&lines, &mut pos, path)?);
+    }
+
+    Ok(components)
+}
+
+fn is_separator(line: &str) -> bool {
+    let line = line.trim().to_lowercase();
+    line.is_empty() || line.starts_with("/&") || line.starts_with("end of data")
+}
+
+fn parse_component(
+    lines: &[&str],
+    pos: &mut usize,
+    path: &Path,
+) -> Result<ScsnV2Component, DataExtractionErr> {
+    let text_header = take_lines(lines, pos, TEXT_HEADER_LINES, path)?;
+
+    let mut int_header: Vec<i32> = Vec::new();
+    for line in take_lines(lines, pos, INT_HEADER_LINES, path)? {
+        int_header.extend(parse_fixed_width::<i32>(line, INT_HEADER_WIDTH, path)?);
+    }
+
+    let mut real_header: Vec<f64> = Vec::new();
+    for line in take_lines(lines, pos, REAL_HEADER_LINES, path)? {
+        real_header.extend(parse_fixed_width::<f64>(line, REAL_HEADER_WIDTH, path)?);
+    }
+
+    let field = |label: &str| text_field(text_header, label, path);
+
+    // "CI-PAS"のようにネットワークコードと観測点コードが結合されている
+    let code = field("Code:")?
+        .split_whitespace()
+        .next()
+        .unwrap_or_default();
+    let (network_code, station_code) = match code.split_once('-') {
+        Some((network, station)) => (network.to_string(), station.to_string()),
+        None => (String::new(), code.to_string()),
+    };
+    let (station_lat, station_lon) = parse_coords("Coords:", field("Coords:")?, path)?;
+    let orientation = parse_orientation(text_header, path)?;
+    let start_time = parse_date_time("Rcrd start time:", field("Rcrd start time:")?, path)?;
+    let event = parse_event(text_header, path);
+
+    let accel = parse_data_section(lines, pos, "accel", path)?;
+    // 速度、変位は加速度から積分できるため、読み飛ばす
+    skip_data_section(lines, pos, "veloc", path)?;
+    skip_data_section(lines, pos, "displ", path)?;
+
+    let acc_factor = gal_factor(&accel.header.unit).ok_or_else(|| {
+        DataExtractionErr::InvalidHeaderValue(
+            "unit".to_string(),
+            accel.header.unit.clone(),
+            path.into(),
+        )
+    })?;
+
+    Ok(ScsnV2Component {
+        network_code,
+        station_code,
+        station_lat,
+        station_lon,
+        orientation,
+        start_time,
+        event,
+        int_header,
+        real_header,
+        delta_sec: accel.header.delta_sec,
+        acc_values: accel.values.iter().map(|v| v * acc_factor).collect(),
+    })
+}
+
+// テキストヘッダーから"Code:"のようなラベルに続く値を取り出す
+fn text_field<'a>(
+    text_header: &[&'a str],
+    label: &str,
+    path: &Path,
+) -> Result<&'a str, DataExtractionErr> {
+    text_header
+        .iter()
+        .find_map(|line| line.find(label).map(|i| line[i + label.len()..].trim()))
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| DataExtractionErr::MissingHeaderField(label.to_string(), path.into()))
+}
+
+// "Chan  1: 360 Deg"、"Chan  3: Up"から成分の向きを取得する
+fn parse_orientation(text_header: &[&str], path: &Path) -> Result<Orientation, DataExtractionErr> {
+    let invalid = |value: &str| {
+        DataExtractionErr::InvalidHeaderValue("Chan".to_string(), value.to_string(), path.into())
+    };
+
+    let value = text_header
+        .iter()
+        .find_map(|line| {
+            // 小文字化でバイト長が変わる文字があるため、元の行でASCIIとして大小文字を区別せずに探す
+            line.as_bytes()
+                .windows(4)
+                .position(|w| w.eq_ignore_ascii_case(b"chan"))
+                .and_then(|i| line[i..].split_once(':'))
+                .and_then(|(_, rest)| rest.split_whitespace().next())
+        })
+        .ok_or_else(|| DataExtractionErr::MissingHeaderField("Chan".to_string(), path.into()))?;
+
+    match value.to_lowercase().as_str() {
+        "up" => Ok(Orientation::Up),
+        "down" | "dn" => Ok(Orientation::Down),
+        azimuth => azimuth
+            .parse::<f64>()
+            .map(Orientation::Horizontal)
+            .map_err(|_| invalid(value)),
+    }
+}
+
+// "34.148N 118.171W"のような緯度経度を符号付きの度に変換する
+fn parse_coords(label: &str, value: &str, path: &Path) -> Result<(f64, f64), DataExtractionErr> {
+    let invalid =
+        || DataExtractionErr::InvalidHeaderValue(label.to_string(), value.to_string(), path.into());
+
+    let mut tokens = value.split_whitespace();
+    let lat = parse_signed_degree(tokens.next().ok_or_else(invalid)?).ok_or_else(invalid)?;
+    let lon = parse_signed_degree(tokens.next().ok_or_else(invalid)?).ok_or_else(invalid)?;
+
+    Ok((lat, lon))
+}
+
+fn parse_signed_degree(token: &str) -> Option<f64> {
+    let token = token.trim_end_matches(',');
+    let (number, sign) = match token.chars().last()? {
+        'N' | 'n' | 'E' | 'e' => (&token[..token.len() - 1], 1.0),
+        'S' | 's' | 'W' | 'w' => (&token[..token.len() - 1], -1.0),
+        _ => (token, 1.0),
+    };
+
+    number.parse::<f64>().ok().map(|v| v * sign)
+}
+
+// "1/17/94, 12:31:02.000 GMT"のような日時を解析する
+fn parse_date_time(
+    label: &str,
+    value: &str,
+    path: &Path,
+) -> Result<NaiveDateTime, DataExtractionErr> {
+    let date_time: Vec<&str> = value
+        .split_whitespace()
+        .take(2)
+        .map(|t| t.trim_end_matches(','))
+        .collect();
+    let date_time = date_time.join(" ");
+
+    DATE_TIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(&date_time, format).ok())
+        .ok_or_else(|| {
+            DataExtractionErr::InvalidHeaderValue(label.to_string(), value.to_string(), path.into())
+        })
+}
+
+// "Hypocenter: 34.213N 118.537W  H=18km  ML=6.4"と"Origin: 01/17/94, 12:30:55.4 GMT"から震源情報を取得する
+// 震源情報は出力に必須ではないため、記載がない場合はNoneとする
+fn parse_event(text_header: &[&str], path: &Path) -> Option<ScsnV2Event> {
+    let hypocenter = text_field(text_header, "Hypocenter:", path).ok()?;
+    let origin = text_field(text_header, "Origin:", path).ok()?;
+
+    let (lat, lon) = parse_coords("Hypocenter:", hypocenter, path).ok()?;
+    let mut depth_km: Option<f64> = None;
+    let mut magnitude: Option<f64> = None;
+    for token in hypocenter.split_whitespace().skip(2) {
+        if let Some((key, value)) = token.trim_end_matches(',').split_once('=') {
+            if key.eq_ignore_ascii_case("h") {
+                depth_km = value.trim_end_matches("km").parse().ok();
+            } else if magnitude.is_none() {
+                magnitude = value.parse().ok();
+            }
+        }
+    }
+
+    Some(ScsnV2Event {
+        origin_time: parse_date_time("Origin:", origin, path).ok()?,
+        lat,
+        lon,
+        depth_km: depth_km?,
+        magnitude: magnitude?,
+    })
+}
+
+struct DataSectionHeader {
+    num_of_points: usize,
+    delta_sec: f64,
+    unit: String,
+    values_per_line: usize,
+    width: usize,
+}
+
+struct DataSection {
+    header: DataSectionHeader,
+    values: Vec<f64>,
+}
+
+// "8000 points of accel data equally spaced at .010 sec, in cm/sec2. (8f10.3)"で始まるデータ部を読み込む
+fn parse_data_section(
+    lines: &[&str],
+    pos: &mut usize,
+    kind: &str,
+    path: &Path,
+) -> Result<DataSection, DataExtractionErr> {
+    let header = parse_data_section_header(lines, pos, kind, path)?;
+
+    let mut values: Vec<f64> = Vec::with_capacity(header.num_of_points);
+    while values.len() < header.num_of_points {
+        let line = take_lines(lines, pos, 1, path)?[0];
+        values.extend(parse_fixed_width::<f64>(line, header.width, path)?);
+    }
+    values.truncate(header.num_of_points);
+
+    Ok(DataSection { header, values })
+}
+
+// データ部の値は読まずに、その行数だけ進める
+fn skip_data_section(
+    lines: &[&str],
+    pos: &mut usize,
+    kind: &str,
+    path: &Path,
+) -> Result<(), DataExtractionErr> {
+    let header = parse_data_section_header(lines, pos, kind, path)?;
+    take_lines(
+        lines,
+        pos,
+        header.num_of_points.div_ceil(header.values_per_line),
+        path,
+    )?;
+
+    Ok(())
+}
+
+fn parse_data_section_header(
+    lines: &[&str],
+    pos: &mut usize,
+    kind: &str,
+    path: &Path,
+) -> Result<DataSectionHeader, DataExtractionErr> {
+    let header = take_lines(lines, pos, 1, path)?[0];
+    let lower = header.to_lowercase();
+    let invalid = || {
+        DataExtractionErr::InvalidHeaderValue(
+            kind.to_string(),
+            header.trim().to_string(),
+            path.into(),
+        )
+    };
+
+    if !lower.contains(&format!("points of {} data", kind)) {
+        return Err(invalid());
+    }
+
+    let num_of_points: usize = lower
+        .split_whitespace()
+        .next()
+        .and_then(|v| v.parse().ok())
+        .ok_or_else(invalid)?;
+    let delta_sec: f64 = lower
+        .split_once("spaced at")
+        .and_then(|(_, rest)| rest.split_whitespace().next())
+        .and_then(|v| v.parse().ok())
+        .filter(|&delta_sec: &f64| delta_sec > 0.0 && delta_sec.is_finite())
+        .ok_or_else(invalid)?;
+    let unit: String = lower
+        .split_once(" in ")
+        .and_then(|(_, rest)| rest.split_whitespace().next())
+        .map(|v| v.trim_end_matches(['.', ',']).to_string())
+        .ok_or_else(invalid)?;
+    // "(8f10.3)"は1行に幅10の値が8個
+    let (values_per_line, width): (usize, usize) = lower
+        .rsplit_once('(')
+        .and_then(|(_, format)| format.split_once('f'))
+        .and_then(|(count, width)| {
+            Some((
+                count.parse().ok().filter(|&count| count > 0)?,
+                width.split('.').next()?.parse().ok()?,
+            ))
+        })
+        .ok_or_else(invalid)?;
+
+    Ok(DataSectionHeader {
+        num_of_points,
+        delta_sec,
+        unit,
+        values_per_line,
+        width,
+    })
+}
+
+/// This module contains unit tests for the SCSN V2 parser.
+///
+/// # Test Categories
+///
+/// - `test_parse_scsn_v2`: Tests parsing of the headers and data sections of three components
+/// - `test_parse_scsn_v2_invalid_data_section`: Tests rejection of an unexpected data section
+/// - `test_parse_scsn_v2_invalid_delta`: Tests rejection of a zero or NaN sampling interval
+/// - `test_parse_scsn_v2_units`: Tests conversion of the acceleration to gal and rejection of a velocity unit
+/// - `test_parse_orientation`: Tests the orientation of a header line with non-ASCII characters before "Chan"
+/// - `test_extract_group`: Tests mapping of the component azimuths onto NS, EW and UD
+/// - `test_extract_group_misaligned`: Tests rejection of components whose start times differ by more than half a
+///   sample
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis_config_file::{FileConfig, From, To};
+    use chrono::NaiveDate;
+    use std::{fs::File, io::Write};
+    use tempfile::tempdir;
+
+    fn data_section(kind: &str, unit: &str, values: &[f64]) -> String {
+        let mut section = format!(
+            "{:8} points of {} data equally spaced at  .010 sec, in {}.  (8f10.3)\n",
+            values.len(),
+            kind,
+            unit
+        );
+        for chunk in values.chunks(8) {
+            let line = chunk
+                .iter()
+                .map(|v| format!("{:10.3}", v))
+                .collect::<Vec<_>>();
+            section.push_str(&line.concat());
+            section.push('\n');
+        }
+        section
+    }
+
+    fn component_text(chan: usize, orientation: &str, values: &[f64]) -> String {
+        let mut text_header: Vec<String> = vec![
+            format!("Corrected accelerogram     14383980        Chan  {chan}: {orientation}"),
+            "Inglewood Earthquake           May 18, 2009 03:39 GMT".to_string(),
+            "Hypocenter: 33.938N 118.336W  H=13km  ML=4.7".to_string(),
+            "Origin: 05/18/09, 03:39:36.0 GMT".to_string(),
+            "Statn No: 00001  Code:CI-PAS  Caltech Seismological Laboratory".to_string(),
+            "Coords: 34.148N 118.171W".to_string(),
+            "Recorder: FBA-23".to_string(),
+            "Rcrd start time: 5/18/09, 03:39:40.000 GMT".to_string(),
+        ];
+        text_header.resize(TEXT_HEADER_LINES, "-".to_string());
+
+        let int_header: Vec<String> = (0..100)
+            .collect::<Vec<i32>>()
+            .chunks(16)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .map(|v| format!("{:5}", v))
+                    .collect::<Vec<_>>()
+                    .concat()
+            })
+            .collect();
+        let real_header: Vec<String> = (0..100)
+            .map(|v| v as f64 * 0.5)
+            .collect::<Vec<f64>>()
+            .chunks(10)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .map(|v| format!("{:8.3}", v))
+                    .collect::<Vec<_>>()
+                    .concat()
+            })
+            .collect();
+
+        format!(
+            "{}\n{}\n{}\n{}{}{}End of data for channel {}\n",
+            text_header.join("\n"),
+            int_header.join("\n"),
+            real_header.join("\n"),
+            data_section("accel", "cm/sec2", values),
+            data_section("veloc", "cm/sec", &[0.1; 9]),
+            data_section("displ", "cm", &[0.01; 9]),
+            chan
+        )
+    }
+
+    fn assert_close(actual: &[f64], expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected) {
+            assert!(
+                (a - e).abs() < 1e-9,
+                "expected {:?}, got {:?}",
+                expected,
+                actual
+            );
+        }
+    }
+
+    const NS_VALUES: [f64; 9] = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0];
+    const EW_VALUES: [f64; 9] = [-1.0, -2.0, -3.0, -4.0, -5.0, -6.0, -7.0, -8.0, -9.0];
+    const UD_VALUES: [f64; 9] = [0.5; 9];
+
+    fn scsn_v2_text() -> String {
+        [
+            component_text(1, "360 Deg", &NS_VALUES),
+            component_text(2, "90 Deg", &EW_VALUES),
+            component_text(3, "Up", &UD_VALUES),
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn test_parse_scsn_v2() {
+        let components = parse_scsn_v2(&scsn_v2_text(), Path::new("test.v2")).unwrap();
+        assert_eq!(components.len(), 3);
+
+        let first = &components[0];
+        assert_eq!(first.network_code, "CI");
+        assert_eq!(first.station_code, "PAS");
+        assert_eq!(first.station_lat, 34.148);
+        assert_eq!(first.station_lon, -118.171);
+        assert_eq!(first.orientation, Orientation::Horizontal(360.0));
+        assert_eq!(
+            first.start_time,
+            NaiveDate::from_ymd_opt(2009, 5, 18)
+                .unwrap()
+                .and_hms_opt(3, 39, 40)
+                .unwrap()
+        );
+        assert_eq!(
+            first.event,
+            Some(ScsnV2Event {
+                origin_time: NaiveDate::from_ymd_opt(2009, 5, 18)
+                    .unwrap()
+                    .and_hms_opt(3, 39, 36)
+                    .unwrap(),
+                lat: 33.938,
+                lon: -118.336,
+                depth_km: 13.0,
+                magnitude: 4.7,
+            })
+        );
+        assert_eq!(first.int_header, (0..100).collect::<Vec<i32>>());
+        assert_eq!(first.real_header[99], 49.5);
+        assert_eq!(first.delta_sec, 0.01);
+        assert_eq!(first.acc_values, NS_VALUES.to_vec());
+
+        assert_eq!(components[1].orientation, Orientation::Horizontal(90.0));
+        assert_eq!(components[2].orientation, Orientation::Up);
+    }
+
+    #[test]
+    fn test_parse_scsn_v2_units() {
+        let path = Path::new("test.v2");
+        let text = scsn_v2_text().replace("in cm/sec2.", "in m/sec2.");
+        let components = parse_scsn_v2(&text, path).unwrap();
+
+        assert_close(&components[0].acc_values, &NS_VALUES.map(|v| v * 100.0));
+
+        let text = scsn_v2_text().replacen("in cm/sec2.", "in cm/sec.", 1);
+        assert!(matches!(
+            parse_scsn_v2(&text, path).unwrap_err(),
+            DataExtractionErr::InvalidHeaderValue(key, value, _) if key == "unit" && value == "cm/sec"
+        ));
+    }
+
+    #[test]
+    fn test_parse_scsn_v2_invalid_data_section() {
+        let path = Path::new("test.v2");
+        let text = scsn_v2_text().replacen("points of veloc data", "points of accel data", 1);
+
+        assert!(matches!(
+            parse_scsn_v2(&text, path).unwrap_err(),
+            DataExtractionErr::InvalidHeaderValue(kind, _, _) if kind == "veloc"
+        ));
+    }
+
+    #[test]
+    fn test_parse_scsn_v2_invalid_delta() {
+        let path = Path::new("test.v2");
+
+        // 0やNaNの間隔はサンプリング周波数がinfやNaNになる
+        for delta in ["0.000", "NaN"] {
+            let text = scsn_v2_text().replacen(" .010 sec", &format!("{} sec", delta), 1);
+            assert!(matches!(
+                parse_scsn_v2(&text, path).unwrap_err(),
+                DataExtractionErr::InvalidHeaderValue(kind, value, _)
+                    if kind == "accel" && value.contains(&format!("spaced at {} sec", delta))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_parse_orientation() {
+        let path = Path::new("test.v2");
+
+        // 'İ'は小文字化で2バイトから3バイトになる
+        for (line, expected) in [
+            ("İİİİİİİİ CHAN: Up", Orientation::Up),
+            ("Çİğli  Chan  1: 360 Deg", Orientation::Horizontal(360.0)),
+        ] {
+            assert_eq!(parse_orientation(&[line], path).unwrap(), expected);
+        }
+        assert!(matches!(
+            parse_orientation(&["İzmir"], path).unwrap_err(),
+            DataExtractionErr::MissingHeaderField(..)
+        ));
+    }
+
+    #[test]
+    fn test_extract_group() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("14383980.PAS.v2");
+        let mut file = File::create(&path).unwrap();
+        write!(file, "{}", scsn_v2_text()).unwrap();
+
+        let extractor = UsScsnV2Extractor::new(ConversionConfig::for_test(
+            "inglewood",
+            From::UsScsnV2,
+            To::JpJmaCsv,
+            vec![FileConfig {
+                path,
+                acc_axis: None,
+            }],
+        ));
+
+        let extracted = extractor.extract().unwrap();
+        let data = &extracted[0];
+
+        assert_eq!(data.station.code, "PAS");
+        assert_eq!(data.sampling_rate, 100.0);
+        assert_close(&data.components.ns, &NS_VALUES);
+        assert_close(&data.components.ew, &EW_VALUES);
+        assert_close(&data.components.ud, &UD_VALUES);
+    }
+
+    #[test]
+    fn test_extract_group_misaligned() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("14383980.PAS.v2");
+        // UDのみ記録開始が0.02秒(2サンプル)遅い
+        let text = [
+            component_text(1, "360 Deg", &NS_VALUES),
+            component_text(2, "90 Deg", &EW_VALUES),
+            component_text(3, "Up", &UD_VALUES).replace("03:39:40.000", "03:39:40.020"),
+        ]
+        .concat();
+        std::fs::write(&path, text).unwrap();
+
+        let extractor = UsScsnV2Extractor::new(ConversionConfig::for_test(
+            "inglewood",
+            From::UsScsnV2,
+            To::JpJmaCsv,
+            vec![FileConfig {
+                path,
+                acc_axis: None,
+            }],
+        ));
+
+        assert!(matches!(
+            &extractor.extract_group(&extractor.unextracted.group[0], 1).unwrap_err()[..],
+            [DataExtractionErr::MismatchedComponents(reason, _, _)] if reason == "Rcrd start time"
+        ));
+    }
+}
@@ -1,5 +1,12 @@
+pub mod analysis;
 pub mod analysis_config_file;
+pub mod byte_order;
 pub mod cli;
+pub mod converter;
 pub mod error;
 pub mod extractor;
+pub mod integration;
 pub mod logging;
+pub mod processing;
+pub mod record;
+pub mod sac;
@@ -1,11 +1,24 @@
-use log::{debug, error};
+use std::{collections::HashSet, path::PathBuf};
+
+use log::{debug, error, info};
 use naifuru::{
+    analysis::{
+        fourier_spectrum::{fourier_spectra, write_fourier_spectra},
+        intensity_measures::{write_intensity_measures, IntensityMeasureSummary},
+        jma_intensity::{format_jma_intensity, jma_intensity},
+        peak::{write_peak_summary, PeakSummary},
+        response_spectrum::{response_spectra, write_response_spectra},
+    },
     analysis_config_file::{read_config_from_input_file, Config},
     bail_on_error,
-    cli::Args,
-    error::AppError,
+    cli::{Args, Mode},
+    converter::{convert, create_converter},
+    error::{AnalysisErr, AppError},
     extractor::create_extractor,
+    integration::to_quantity,
     logging::init_logger,
+    processing::{process, write_processing_history},
+    record::Quantity,
 };
 
 const DEFAULT_ERROR_EXIT_CODE: i32 = 1;
@@ -45,14 +58,137 @@ fn run() -> Result<(), Vec<AppError>> {
     config.validate()?;
     debug!("The analysis configuration file has been validated successfully.");
 
+    // 同じ出力先に別の記録を上書きしないよう、書き込んだファイルを記録する
+    let mut written_paths: HashSet<PathBuf> = HashSet::new();
+
     // MEMO: グループごとに処理
     for conv_config in config.conversion {
-        let extractor = create_extractor(conv_config);
+        let extractor = create_extractor(conv_config.clone());
         debug!("The data extractor has been created successfully.");
 
-        let _extracted = extractor.extract()?;
+        let mut extracted = extractor.extract()?;
+        debug!("The data has been extracted successfully.");
+
+        // 計測震度は記録された加速度から求めるため、処理は変換時のみ適用する
+        let steps = conv_config.processing_steps();
+        if args.mode == Mode::Convert && !steps.is_empty() {
+            for data in &mut extracted {
+                process(data, &steps).map_err(|e| vec![AnalysisErr::from(e).into()])?;
+            }
+            debug!("The data has been processed successfully.");
+        }
+
+        match args.mode {
+            Mode::Convert => {
+                let converter = create_converter(&conv_config);
+                debug!("The data converter has been created successfully.");
+
+                for data in &extracted {
+                    let written =
+                        to_quantity(data, conv_config.output_quantity, &conv_config.integration)
+                            .map_err(|e| vec![AnalysisErr::from(e).into()])?;
+                    let output_path = convert(
+                        converter.as_ref(),
+                        &written,
+                        conv_config.output_unit(),
+                        &conv_config.from,
+                        &config.global.name_format,
+                        &args.output_dir_path,
+                        &mut written_paths,
+                    )?;
+                    for path in converter.output_paths(&output_path) {
+                        info!("The converted file has been written: {}", path.display());
+                    }
 
-        // TODO: 抽出されたデータを使用して、ToへのConverterを呼び出す。
+                    let integration = (conv_config.output_quantity != Quantity::Acceleration)
+                        .then_some(&conv_config.integration);
+                    if !data.processing().is_empty() || integration.is_some() {
+                        let history_path = write_processing_history(
+                            &written,
+                            integration,
+                            &output_path,
+                            &mut written_paths,
+                        )
+                        .map_err(|e| vec![AnalysisErr::from(e).into()])?;
+                        info!(
+                            "The processing history has been written: {}",
+                            history_path.display()
+                        );
+                    }
+
+                    if let Some(format) = conv_config.peak_summary {
+                        let summary = PeakSummary::new(data)
+                            .map_err(|e| vec![AnalysisErr::from(e).into()])?;
+                        let summary_path =
+                            write_peak_summary(&summary, format, &output_path, &mut written_paths)
+                                .map_err(|e| vec![AnalysisErr::from(e).into()])?;
+                        info!(
+                            "The peak ground motion summary has been written: {}",
+                            summary_path.display()
+                        );
+                    }
+
+                    if let Some(spectrum_config) = &conv_config.response_spectrum {
+                        let points = response_spectra(
+                            data,
+                            &spectrum_config.damping,
+                            &spectrum_config.periods,
+                        )
+                        .map_err(|e| vec![AnalysisErr::from(e).into()])?;
+                        let spectrum_path =
+                            write_response_spectra(&points, &output_path, &mut written_paths)
+                                .map_err(|e| vec![AnalysisErr::from(e).into()])?;
+                        info!(
+                            "The response spectra have been written: {}",
+                            spectrum_path.display()
+                        );
+                    }
+
+                    if let Some(fourier_config) = &conv_config.fourier_spectrum {
+                        let points = fourier_spectra(data, fourier_config)
+                            .map_err(|e| vec![AnalysisErr::from(e).into()])?;
+                        let fourier_path =
+                            write_fourier_spectra(&points, &output_path, &mut written_paths)
+                                .map_err(|e| vec![AnalysisErr::from(e).into()])?;
+                        info!(
+                            "The Fourier spectra have been written: {}",
+                            fourier_path.display()
+                        );
+                    }
+
+                    if let Some(format) = conv_config.intensity_measures {
+                        if let Some(summary) = IntensityMeasureSummary::new(data)
+                            .map_err(|e| vec![AnalysisErr::from(e).into()])?
+                        {
+                            let measures_path = write_intensity_measures(
+                                &summary,
+                                format,
+                                &output_path,
+                                &mut written_paths,
+                            )
+                            .map_err(|e| vec![AnalysisErr::from(e).into()])?;
+                            info!(
+                                "The intensity measures have been written: {}",
+                                measures_path.display()
+                            );
+                        }
+                    }
+                }
+            }
+            Mode::Intensity => {
+                for data in &extracted {
+                    let intensity =
+                        jma_intensity(data).map_err(|e| vec![AnalysisErr::from(e).into()])?;
+                    if intensity.is_none() {
+                        error!(
+                            "The record of {} is too short to compute the JMA instrumental seismic intensity.",
+                            data.station().code
+                        );
+                    }
+                    println!("{}", format_jma_intensity(data, intensity));
+                }
+            }
+        }
     }
 
     Ok(())
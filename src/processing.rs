@@ -0,0 +1,663 @@
+use std::{
+    collections::HashSet,
+    f64::consts::PI,
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+
+use crate::{
+    analysis::{
+        remove_linear_trend,
+        rotation::{rotate, rotd_azimuth},
+    },
+    analysis_config_file::{Azimuth, DetrendMethod, IntegrationConfig, ProcessingStep},
+    converter::sibling_path,
+    error::{DataConversionErr, DataProcessingErr},
+    record::{Quantity, Record},
+};
+
+/// Applies `steps` to every component of the record in order, and appends them to the processing history of the
+/// record.
+///
+/// Fails without changing the record if a step cannot be applied, e.g. a band-pass corner is not below the Nyquist
+/// frequency of the record. Resampling to the current sampling rate is skipped and not recorded, and rotation to
+/// RotD50 or RotD100 is recorded with the azimuth found.
+pub fn process(record: &mut Record, steps: &[ProcessingStep]) -> Result<(), DataProcessingErr> {
+    // リサンプリングの後の手順は変更後のサンプリング周波数で確認する
+    let mut sampling_rate = record.sampling_rate();
+    for step in steps {
+        check_applicable(record, sampling_rate, step)?;
+        if let ProcessingStep::Resample { hz } = step {
+            sampling_rate = *hz;
+        }
+    }
+
+    for step in steps {
+        let applied = match *step {
+            ProcessingStep::Resample { hz } if hz == record.sampling_rate => continue,
+            ProcessingStep::Resample { hz } => {
+                resample(record, hz);
+                step.clone()
+            }
+            ProcessingStep::Rotate { azimuth } => ProcessingStep::Rotate {
+                azimuth: Azimuth::Degrees(rotate_horizontals(record, azimuth)),
+            },
+            _ => {
+                let delta = record.delta();
+                let components = &mut record.components;
+                for values in [&mut components.ns, &mut components.ew, &mut components.ud] {
+                    apply(values, delta, step);
+                }
+                step.clone()
+            }
+        };
+        record.processing.push(applied);
+    }
+
+    Ok(())
+}
+
+fn check_applicable(
+    record: &Record,
+    sampling_rate: f32,
+    step: &ProcessingStep,
+) -> Result<(), DataProcessingErr> {
+    let site_code = &record.station().code;
+
+    match step {
+        ProcessingStep::PolynomialBaseline { order } if record.len() <= *order => {
+            Err(DataProcessingErr::TooShort(
+                format!("polynomial_baseline(order {})", order),
+                site_code.clone(),
+            ))
+        }
+        ProcessingStep::BandPass { high_hz, .. } => {
+            let nyquist = sampling_rate as f64 / 2.0;
+            if *high_hz >= nyquist {
+                return Err(DataProcessingErr::CornerAboveNyquist(
+                    high_hz.to_string(),
+                    nyquist.to_string(),
+                    site_code.clone(),
+                ));
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+fn apply(values: &mut [f64], delta: f64, step: &ProcessingStep) {
+    match *step {
+        ProcessingStep::Detrend {
+            method: DetrendMethod::Mean,
+        } => remove_polynomial_baseline(values, 0),
+        ProcessingStep::Detrend {
+            method: DetrendMethod::Linear,
+        } => remove_linear_trend(values),
+        ProcessingStep::PolynomialBaseline { order } => remove_polynomial_baseline(values, order),
+        ProcessingStep::Taper { fraction } => cosine_taper(values, fraction),
+        ProcessingStep::BandPass {
+            low_hz,
+            high_hz,
+            order,
+            causal,
+        } => {
+            let sections: Vec<Biquad> = butterworth_sections(order, high_hz * delta, false)
+                .into_iter()
+                .chain(butterworth_sections(order, low_hz * delta, true))
+                .collect();
+            filter(values, &sections);
+            if !causal {
+                // 逆方向にもかけて位相のずれを打ち消す
+                values.reverse();
+                filter(values, &sections);
+                values.reverse();
+            }
+        }
+        // サンプル数が変わるため、成分ごとではなく記録全体に対して行う
+        ProcessingStep::Resample { .. } => {}
+        // 水平2成分を組み合わせるため、記録全体に対して行う
+        ProcessingStep::Rotate { .. } => {}
+    }
+}
+
+/// Rotates the horizontal components of the record to `azimuth` and returns the azimuth in degrees.
+///
+/// The azimuth of RotD50 or RotD100 is found from the current components of the record.
+pub fn rotate_horizontals(record: &mut Record, azimuth: Azimuth) -> f64 {
+    let components = &mut record.components;
+    let degrees = match azimuth {
+        Azimuth::Degrees(degrees) => degrees,
+        Azimuth::RotD(rotd) => rotd_azimuth(&components.ns, &components.ew, rotd),
+    };
+
+    (components.ns, components.ew) = rotate(&components.ns, &components.ew, degrees);
+
+    degrees
+}
+
+/// Removes the polynomial of `order` fitted by least squares from the waveform.
+///
+/// The time is normalized to `[-1, 1]` to keep the normal equations well-conditioned.
+pub fn remove_polynomial_baseline(values: &mut [f64], order: usize) {
+    let n = values.len();
+    if n <= order {
+        values.iter_mut().for_each(|v| *v = 0.0);
+        return;
+    }
+
+    let x = |i: usize| {
+        if n == 1 {
+            0.0
+        } else {
+            2.0 * i as f64 / (n - 1) as f64 - 1.0
+        }
+    };
+    let powers = |x: f64| {
+        std::iter::successors(Some(1.0), move |p| Some(p * x))
+            .take(order + 1)
+            .collect::<Vec<f64>>()
+    };
+
+    // 正規方程式 (XᵀX) c = Xᵀy を組み立てる
+    let size = order + 1;
+    let mut matrix = vec![vec![0.0; size + 1]; size];
+    for (i, v) in values.iter().enumerate() {
+        let p = powers(x(i));
+        for row in 0..size {
+            for col in 0..size {
+                matrix[row][col] += p[row] * p[col];
+            }
+            matrix[row][size] += p[row] * v;
+        }
+    }
+    let coefficients = solve(matrix);
+
+    for (i, v) in values.iter_mut().enumerate() {
+        *v -= powers(x(i))
+            .iter()
+            .zip(&coefficients)
+            .map(|(p, c)| p * c)
+            .sum::<f64>();
+    }
+}
+
+// 部分ピボット選択付きのガウスの消去法で拡大係数行列を解く
+fn solve(mut matrix: Vec<Vec<f64>>) -> Vec<f64> {
+    let size = matrix.len();
+
+    for col in 0..size {
+        let pivot = (col..size)
+            .max_by(|&a, &b| matrix[a][col].abs().total_cmp(&matrix[b][col].abs()))
+            .unwrap_or(col);
+        matrix.swap(col, pivot);
+
+        for row in col + 1..size {
+            let factor = matrix[row][col] / matrix[col][col];
+            for k in col..=size {
+                matrix[row][k] -= factor * matrix[col][k];
+            }
+        }
+    }
+
+    let mut solution = vec![0.0; size];
+    for row in (0..size).rev() {
+        let sum: f64 = (row + 1..size).map(|k| matrix[row][k] * solution[k]).sum();
+        solution[row] = (matrix[row][size] - sum) / matrix[row][row];
+    }
+
+    solution
+}
+
+/// Tapers `fraction` of the samples at each end of the waveform with a half cosine(Tukey window).
+pub fn cosine_taper(values: &mut [f64], fraction: f64) {
+    let n = values.len();
+    let width = (n as f64 * fraction).floor() as usize;
+    if width == 0 {
+        return;
+    }
+
+    for i in 0..width.min(n / 2) {
+        let weight = 0.5 * (1.0 - (PI * i as f64 / width as f64).cos());
+        values[i] *= weight;
+        values[n - 1 - i] *= weight;
+    }
+}
+
+/// Second-order section of an IIR filter, normalized by `a0`.
+#[derive(Debug, Clone, Copy)]
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2],
+}
+
+/// Designs a Butterworth low-pass or high-pass filter of `order` as second-order sections with the bilinear
+/// transform, where `normalized_hz` is the corner frequency divided by the sampling rate.
+fn butterworth_sections(order: usize, normalized_hz: f64, high_pass: bool) -> Vec<Biquad> {
+    // 双一次変換による周波数のずれを補正する
+    let k = (PI * normalized_hz).tan();
+    let mut sections = Vec::with_capacity(order.div_ceil(2));
+
+    for m in 0..order / 2 {
+        // 極の負の実軸からの角度
+        let theta = if order % 2 == 0 {
+            PI * (2 * m + 1) as f64 / (2 * order) as f64
+        } else {
+            PI * (m + 1) as f64 / order as f64
+        };
+        let q = 1.0 / (2.0 * theta.cos());
+        let norm = 1.0 / (1.0 + k / q + k * k);
+        let a = [2.0 * (k * k - 1.0) * norm, (1.0 - k / q + k * k) * norm];
+        let b = if high_pass {
+            [norm, -2.0 * norm, norm]
+        } else {
+            [k * k * norm, 2.0 * k * k * norm, k * k * norm]
+        };
+        sections.push(Biquad { b, a });
+    }
+
+    if order % 2 == 1 {
+        let norm = 1.0 / (1.0 + k);
+        let a = [(k - 1.0) * norm, 0.0];
+        let b = if high_pass {
+            [norm, -norm, 0.0]
+        } else {
+            [k * norm, k * norm, 0.0]
+        };
+        sections.push(Biquad { b, a });
+    }
+
+    sections
+}
+
+// 各2次セクションを直接形IIで順にかける
+fn filter(values: &mut [f64], sections: &[Biquad]) {
+    for section in sections {
+        let (mut z1, mut z2) = (0.0, 0.0);
+        for v in values.iter_mut() {
+            let x = *v;
+            let y = section.b[0] * x + z1;
+            z1 = section.b[1] * x - section.a[0] * y + z2;
+            z2 = section.b[2] * x - section.a[1] * y;
+            *v = y;
+        }
+    }
+}
+
+// 補間に用いる窓付きsinc関数の片側の零点の数
+const RESAMPLE_ZERO_CROSSINGS: usize = 16;
+// 折り返しを防ぐため、遮断周波数を変換後のナイキスト周波数より少し下げる
+const RESAMPLE_ROLLOFF: f64 = 0.95;
+
+/// Resamples every component of the record to `sampling_rate` and updates its sampling rate, keeping the
+/// components aligned.
+///
+/// Each output sample is interpolated with a Blackman-windowed sinc whose cutoff is below the lower of the two
+/// Nyquist frequencies, so that decimation is anti-aliased and upsampling is band-limited. The output covers the same
+/// duration as the input, i.e. `floor((len - 1) * sampling_rate / original rate) + 1` samples.
+pub fn resample(record: &mut Record, sampling_rate: f32) {
+    let ratio = sampling_rate as f64 / record.sampling_rate as f64;
+    let components = &mut record.components;
+    for values in [&mut components.ns, &mut components.ew, &mut components.ud] {
+        *values = resample_values(values, ratio);
+    }
+    record.sampling_rate = sampling_rate;
+}
+
+fn resample_values(values: &[f64], ratio: f64) -> Vec<f64> {
+    if values.is_empty() {
+        return Vec::new();
+    }
+
+    // 入力のサンプル間隔を単位とした遮断周波数と窓の片側の幅
+    let cutoff = ratio.min(1.0) * RESAMPLE_ROLLOFF;
+    let half_width = RESAMPLE_ZERO_CROSSINGS as f64 / cutoff;
+    let kernel = |d: f64| {
+        let window =
+            0.42 + 0.5 * (PI * d / half_width).cos() + 0.08 * (2.0 * PI * d / half_width).cos();
+        let x = PI * cutoff * d;
+        let sinc = if x == 0.0 { 1.0 } else { x.sin() / x };
+        cutoff * sinc * window
+    };
+
+    let len = ((values.len() - 1) as f64 * ratio + 1e-9).floor() as usize + 1;
+    (0..len)
+        .map(|j| {
+            let t = j as f64 / ratio;
+            let first = (t - half_width).ceil().max(0.0) as usize;
+            let last = ((t + half_width).floor() as usize).min(values.len() - 1);
+            (first..=last)
+                .map(|i| values[i] * kernel(t - i as f64))
+                .sum()
+        })
+        .collect()
+}
+
+#[derive(Serialize)]
+struct ProcessingHistory<'a> {
+    site_code: &'a str,
+    steps: &'a [ProcessingStep],
+    quantity: Quantity,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    integration: Option<&'a IntegrationConfig>,
+}
+
+/// Writes the processing history of the written record next to the converted file, e.g. `*-knet.processing.json`
+/// for `*-knet.csv`, and returns its path.
+///
+/// The converted formats have no header field for the history, so it is kept in this sidecar file, which is added to
+/// `written` like the converted files and is an error if already written in the run. `integration` is recorded if the
+/// record has been integrated from the acceleration.
+///
+/// ```json
+/// {
+///   "site_code": "ISK005",
+///   "steps": [
+///     { "step": "detrend", "method": "linear" },
+///     { "step": "band_pass", "low_hz": 0.1, "high_hz": 20.0, "order": 4, "causal": false }
+///   ],
+///   "quantity": "velocity",
+///   "integration": { "method": "time_domain", "baseline": "linear" }
+/// }
+/// ```
+pub fn write_processing_history(
+    record: &Record,
+    integration: Option<&IntegrationConfig>,
+    converted_path: &Path,
+    written: &mut HashSet<PathBuf>,
+) -> Result<PathBuf, DataConversionErr> {
+    let output_path = sibling_path(converted_path, "processing", "json");
+    if !written.insert(output_path.clone()) {
+        return Err(DataConversionErr::DuplicateOutput(output_path));
+    }
+
+    let history = ProcessingHistory {
+        site_code: &record.station().code,
+        steps: record.processing(),
+        quantity: record.quantity(),
+        integration,
+    };
+    let contents = serde_json::to_string_pretty(&history)
+        .map_err(|e| DataConversionErr::Format(output_path.clone(), e.to_string()))?;
+    std::fs::write(&output_path, contents)
+        .map_err(|e| DataConversionErr::Io(output_path.clone(), e.into()))?;
+
+    Ok(output_path)
+}
+
+/// This module contains unit tests for the processing chain.
+///
+/// # Test Categories
+///
+/// - `test_remove_polynomial_baseline`: Tests removal of a quadratic baseline, and of the mean with order 0
+/// - `test_cosine_taper`: Tests the weights of the tapered ends and the untouched middle
+/// - `test_butterworth_gain`: Tests the gain of the low-pass and high-pass sections at DC, the corner and Nyquist
+/// - `test_band_pass`: Tests that a zero-phase band-pass keeps the pass band and removes the stop bands
+/// - `test_resample`: Tests decimation and upsampling of a sine wave, and removal of a frequency above the new Nyquist
+/// - `test_process`: Tests the processing history and the rejection of a corner above the Nyquist frequency
+/// - `test_process_resample`: Tests that the resampled components stay aligned and later steps use the new rate
+/// - `test_process_rotate`: Tests rotation by degrees and to RotD100, and the azimuth recorded in the history
+/// - `test_write_processing_history`: Tests the path and the contents of the history, and rejection of a second write
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{analysis_config_file::RotD, record::test_record};
+    use tempfile::tempdir;
+
+    fn record(ns: Vec<f64>) -> Record {
+        let len = ns.len();
+        test_record(ns, vec![0.0; len], vec![0.0; len])
+    }
+
+    fn sine(frequency: f64, len: usize) -> Vec<f64> {
+        (0..len)
+            .map(|i| (2.0 * PI * frequency * i as f64 * 0.01).sin())
+            .collect()
+    }
+
+    // 区間内の最大振幅
+    fn amplitude(values: &[f64]) -> f64 {
+        values.iter().fold(0.0, |max, v| max.max(v.abs()))
+    }
+
+    #[test]
+    fn test_remove_polynomial_baseline() {
+        let mut values: Vec<f64> = (0..100)
+            .map(|i| {
+                let t = i as f64 * 0.01;
+                3.0 - 2.0 * t + 5.0 * t * t
+            })
+            .collect();
+        remove_polynomial_baseline(&mut values, 2);
+        assert!(values.iter().all(|v| v.abs() < 1e-9));
+
+        let mut values = vec![1.0, 2.0, 6.0];
+        remove_polynomial_baseline(&mut values, 0);
+        assert_eq!(values, vec![-2.0, -1.0, 3.0]);
+    }
+
+    #[test]
+    fn test_cosine_taper() {
+        let mut values = vec![1.0; 10];
+        cosine_taper(&mut values, 0.2);
+
+        assert_eq!(values[0], 0.0);
+        assert!((values[1] - 0.5).abs() < 1e-12);
+        assert_eq!(values[9], 0.0);
+        assert!((values[8] - 0.5).abs() < 1e-12);
+        assert!(values[2..8].iter().all(|v| *v == 1.0));
+    }
+
+    #[test]
+    fn test_butterworth_gain() {
+        // z = e^{jω} における周波数応答の大きさ
+        let gain = |sections: &[Biquad], normalized_hz: f64| {
+            let w = 2.0 * PI * normalized_hz;
+            sections.iter().fold(1.0, |g, s| {
+                let z = |c: [f64; 3]| {
+                    let re = c[0] + c[1] * w.cos() + c[2] * (2.0 * w).cos();
+                    let im = -c[1] * w.sin() - c[2] * (2.0 * w).sin();
+                    re.hypot(im)
+                };
+                g * z(s.b) / z([1.0, s.a[0], s.a[1]])
+            })
+        };
+
+        for order in 1..=5 {
+            let low = butterworth_sections(order, 0.1, false);
+            assert!((gain(&low, 0.0) - 1.0).abs() < 1e-12);
+            assert!((gain(&low, 0.1) - 0.5_f64.sqrt()).abs() < 1e-9);
+            assert!(gain(&low, 0.5) < 1e-9);
+
+            let high = butterworth_sections(order, 0.1, true);
+            assert!(gain(&high, 0.0) < 1e-12);
+            assert!((gain(&high, 0.1) - 0.5_f64.sqrt()).abs() < 1e-9);
+            assert!((gain(&high, 0.5) - 1.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_band_pass() {
+        let step = ProcessingStep::BandPass {
+            low_hz: 0.5,
+            high_hz: 5.0,
+            order: 4,
+            causal: false,
+        };
+
+        // 端の過渡応答を避けるため、中央の区間で振幅を確認する
+        for (frequency, expected) in [(0.05, 0.0), (1.5, 1.0), (30.0, 0.0)] {
+            let mut values = sine(frequency, 8000);
+            apply(&mut values, 0.01, &step);
+            assert!(
+                (amplitude(&values[2000..6000]) - expected).abs() < 0.02,
+                "{} Hz",
+                frequency
+            );
+        }
+
+        // 両方向にかけると位相がずれない
+        let original = sine(1.5, 8000);
+        let mut values = original.clone();
+        apply(&mut values, 0.01, &step);
+        for (a, e) in values.iter().zip(&original).skip(2000).take(4000) {
+            assert!((a - e).abs() < 0.02);
+        }
+    }
+
+    #[test]
+    fn test_resample() {
+        let original = sine(1.0, 1000);
+
+        for (ratio, len) in [(0.5, 500), (0.4, 400), (2.0, 1999), (2.5, 2498)] {
+            let resampled = resample_values(&original, ratio);
+            assert_eq!(resampled.len(), len);
+            // 端の影響を避けるため、中央の区間で理論値と比べる
+            for (j, v) in resampled.iter().enumerate().skip(len / 4).take(len / 2) {
+                let expected = (2.0 * PI * j as f64 * 0.01 / ratio).sin();
+                assert!((v - expected).abs() < 1e-3, "ratio {}", ratio);
+            }
+        }
+
+        // 変換後のナイキスト周波数(25Hz)を超える成分は除かれる
+        let high = sine(40.0, 1000);
+        let resampled = resample_values(&high, 0.5);
+        assert!(amplitude(&resampled[100..400]) < 1e-2);
+    }
+
+    #[test]
+    fn test_process() {
+        let mut data = record(vec![1.0, 2.0, 3.0, 4.0]);
+        let steps = vec![
+            ProcessingStep::Detrend {
+                method: DetrendMethod::Linear,
+            },
+            ProcessingStep::Taper { fraction: 0.25 },
+        ];
+        process(&mut data, &steps).unwrap();
+        assert_eq!(data.processing(), steps.as_slice());
+        assert!(data.components().ns().iter().all(|v| v.abs() < 1e-12));
+
+        let before = data.clone();
+        let result = process(
+            &mut data,
+            &[ProcessingStep::BandPass {
+                low_hz: 0.1,
+                high_hz: 50.0,
+                order: 4,
+                causal: true,
+            }],
+        );
+        assert_eq!(
+            result,
+            Err(DataProcessingErr::CornerAboveNyquist(
+                "50".to_string(),
+                "50".to_string(),
+                "ISK005".to_string()
+            ))
+        );
+        assert_eq!(data, before);
+    }
+
+    #[test]
+    fn test_process_resample() {
+        let mut data = record(sine(1.0, 1000));
+        data.components.ud = sine(2.0, 1000);
+        let steps = vec![
+            ProcessingStep::Resample { hz: 100.0 },
+            ProcessingStep::Resample { hz: 50.0 },
+            ProcessingStep::BandPass {
+                low_hz: 0.1,
+                high_hz: 30.0,
+                order: 2,
+                causal: false,
+            },
+        ];
+
+        // 50Hzに変換した後は30Hzがナイキスト周波数を超える
+        assert!(matches!(
+            process(&mut data, &steps),
+            Err(DataProcessingErr::CornerAboveNyquist(..))
+        ));
+        assert_eq!(data.sampling_rate(), 100.0);
+
+        process(&mut data, &steps[..2]).unwrap();
+        assert_eq!(data.sampling_rate(), 50.0);
+        assert_eq!(data.len(), 500);
+        assert_eq!(data.components().ew().len(), 500);
+        assert_eq!(data.components().ud().len(), 500);
+        // 現在と同じサンプリング周波数へのリサンプリングは記録しない
+        assert_eq!(data.processing(), &steps[1..2]);
+    }
+
+    #[test]
+    fn test_process_rotate() {
+        let mut data = record(sine(1.0, 1000));
+        let original = data.components().clone();
+        let steps = [
+            ProcessingStep::Rotate {
+                azimuth: Azimuth::Degrees(90.0),
+            },
+            ProcessingStep::Rotate {
+                azimuth: Azimuth::RotD(RotD::RotD100),
+            },
+        ];
+
+        process(&mut data, &steps[..1]).unwrap();
+        // 90度回転すると、NSは元のEW、EWは元のNSの逆向きとなる
+        for i in 0..data.len() {
+            assert!((data.components().ns()[i] - original.ew()[i]).abs() < 1e-12);
+            assert!((data.components().ew()[i] + original.ns()[i]).abs() < 1e-12);
+        }
+        assert_eq!(data.components().ud(), original.ud());
+
+        // 元のNS方向の動きは、回転後のEWから90度の方位でRotD100となる
+        process(&mut data, &steps[1..]).unwrap();
+        assert_eq!(
+            data.processing()[1],
+            ProcessingStep::Rotate {
+                azimuth: Azimuth::Degrees(90.0)
+            }
+        );
+        for i in 0..data.len() {
+            assert!((data.components().ns()[i] + original.ns()[i]).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_write_processing_history() {
+        let dir = tempdir().unwrap();
+        let converted_path = dir.path().join("20240101-161009-ISK005-knet.csv");
+        let mut data = record(vec![0.0; 10]);
+        process(
+            &mut data,
+            &[ProcessingStep::Detrend {
+                method: DetrendMethod::Mean,
+            }],
+        )
+        .unwrap();
+
+        let mut written = HashSet::new();
+        let path = write_processing_history(&data, None, &converted_path, &mut written).unwrap();
+        assert_eq!(
+            path,
+            dir.path()
+                .join("20240101-161009-ISK005-knet.processing.json")
+        );
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(json["site_code"], "ISK005");
+        assert_eq!(json["steps"][0]["step"], "detrend");
+        assert_eq!(json["steps"][0]["method"], "mean");
+        assert_eq!(json["quantity"], "acceleration");
+        assert!(json.get("integration").is_none());
+
+        // 同じ実行で同じ履歴ファイルを上書きしない
+        assert!(written.contains(&path));
+        assert!(matches!(
+            write_processing_history(&data, None, &converted_path, &mut written),
+            Err(DataConversionErr::DuplicateOutput(p)) if p == path
+        ));
+    }
+}
@@ -0,0 +1,560 @@
+use std::borrow::Cow;
+
+use chrono::{NaiveDateTime, TimeDelta};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    analysis_config_file::{ProcessingStep, SensorPosition},
+    error::RecordErr,
+};
+
+/// Component of a three-component record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Component {
+    Ns,
+    Ew,
+    Ud,
+}
+
+impl Component {
+    /// Every component, in the order of NS, EW, UD.
+    pub const ALL: [Component; 3] = [Component::Ns, Component::Ew, Component::Ud];
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Component::Ns => "ns",
+            Component::Ew => "ew",
+            Component::Ud => "ud",
+        }
+    }
+}
+
+/// Physical quantity of the samples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Quantity {
+    #[default]
+    Acceleration,
+    Velocity,
+    Displacement,
+}
+
+impl Quantity {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Quantity::Acceleration => "acceleration",
+            Quantity::Velocity => "velocity",
+            Quantity::Displacement => "displacement",
+        }
+    }
+
+    /// Returns the unit in centimeters, e.g. gal for acceleration.
+    pub fn default_unit(&self) -> Unit {
+        match self {
+            Quantity::Acceleration => Unit::Gal,
+            Quantity::Velocity => Unit::CentimetersPerSecond,
+            Quantity::Displacement => Unit::Centimeters,
+        }
+    }
+}
+
+/// Physical unit of the samples, which also tells their quantity.
+///
+/// The counts of a source file are converted with its scale factor at extraction, so a record is always in one of
+/// these units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum Unit {
+    /// cm/s²
+    #[default]
+    #[serde(rename = "gal")]
+    Gal,
+    #[serde(rename = "mm/s2")]
+    MillimetersPerSecondSquared,
+    #[serde(rename = "m/s2")]
+    MetersPerSecondSquared,
+    /// Standard gravity, 980.665 gal.
+    #[serde(rename = "g")]
+    G,
+    #[serde(rename = "cm/s")]
+    CentimetersPerSecond,
+    #[serde(rename = "mm/s")]
+    MillimetersPerSecond,
+    #[serde(rename = "m/s")]
+    MetersPerSecond,
+    #[serde(rename = "cm")]
+    Centimeters,
+    #[serde(rename = "mm")]
+    Millimeters,
+    #[serde(rename = "m")]
+    Meters,
+}
+
+impl Unit {
+    /// Label written to the converted files, which is also the name in the configuration file.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Unit::Gal => "gal",
+            Unit::MillimetersPerSecondSquared => "mm/s2",
+            Unit::MetersPerSecondSquared => "m/s2",
+            Unit::G => "g",
+            Unit::CentimetersPerSecond => "cm/s",
+            Unit::MillimetersPerSecond => "mm/s",
+            Unit::MetersPerSecond => "m/s",
+            Unit::Centimeters => "cm",
+            Unit::Millimeters => "mm",
+            Unit::Meters => "m",
+        }
+    }
+
+    /// Parses a unit written in the header of a source file, e.g. `cm/s^2`, `m/sec/sec` or `cm/sec`.
+    pub fn from_label(label: &str) -> Option<Self> {
+        match label.trim().to_lowercase().as_str() {
+            "gal" | "cm/s2" | "cm/s^2" | "cm/s/s" | "cm/sec2" | "cm/sec^2" | "cm/sec/sec" => {
+                Some(Unit::Gal)
+            }
+            "mm/s2" | "mm/s^2" | "mm/s/s" | "mm/sec2" | "mm/sec^2" | "mm/sec/sec" => {
+                Some(Unit::MillimetersPerSecondSquared)
+            }
+            "m/s2" | "m/s^2" | "m/s/s" | "m/sec2" | "m/sec^2" | "m/sec/sec" => {
+                Some(Unit::MetersPerSecondSquared)
+            }
+            "g" => Some(Unit::G),
+            "cm/s" | "cm/sec" => Some(Unit::CentimetersPerSecond),
+            "mm/s" | "mm/sec" => Some(Unit::MillimetersPerSecond),
+            "m/s" | "m/sec" => Some(Unit::MetersPerSecond),
+            "cm" => Some(Unit::Centimeters),
+            "mm" => Some(Unit::Millimeters),
+            "m" => Some(Unit::Meters),
+            _ => None,
+        }
+    }
+
+    pub fn quantity(&self) -> Quantity {
+        match self {
+            Unit::Gal
+            | Unit::MillimetersPerSecondSquared
+            | Unit::MetersPerSecondSquared
+            | Unit::G => Quantity::Acceleration,
+            Unit::CentimetersPerSecond | Unit::MillimetersPerSecond | Unit::MetersPerSecond => {
+                Quantity::Velocity
+            }
+            Unit::Centimeters | Unit::Millimeters | Unit::Meters => Quantity::Displacement,
+        }
+    }
+
+    // 同じ物理量のcm単位(gal, cm/s, cm)に換算する係数
+    fn centimeters_per_unit(&self) -> f64 {
+        match self {
+            Unit::Gal | Unit::CentimetersPerSecond | Unit::Centimeters => 1.0,
+            Unit::MillimetersPerSecondSquared | Unit::MillimetersPerSecond | Unit::Millimeters => {
+                0.1
+            }
+            Unit::MetersPerSecondSquared | Unit::MetersPerSecond | Unit::Meters => 100.0,
+            Unit::G => 980.665,
+        }
+    }
+
+    /// Returns the factor by which a value in this unit is multiplied to be in `to`, or `None` if `to` is a unit of
+    /// another quantity.
+    pub fn factor_to(&self, to: Unit) -> Option<f64> {
+        if self.quantity() != to.quantity() {
+            return None;
+        }
+
+        if *self == to {
+            Some(1.0)
+        } else {
+            Some(self.centimeters_per_unit() / to.centimeters_per_unit())
+        }
+    }
+}
+
+/// Observation station of a record.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Station {
+    /// Station code, e.g. `ISK005`.
+    pub code: String,
+    /// Latitude in degrees, north positive, or `None` if the source format does not have it.
+    pub lat: Option<f64>,
+    /// Longitude in degrees, east positive, or `None` if the source format does not have it.
+    pub lon: Option<f64>,
+    /// Sensor position of KiK-net, which distinguishes the borehole and surface records of the same station.
+    pub sensor_position: Option<SensorPosition>,
+}
+
+/// Earthquake of a record. Each field is `None` if the source format does not have it.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Event {
+    pub origin_time: Option<NaiveDateTime>,
+    /// Latitude of the hypocenter in degrees.
+    pub lat: Option<f64>,
+    /// Longitude of the hypocenter in degrees.
+    pub lon: Option<f64>,
+    pub depth_km: Option<f64>,
+    pub magnitude: Option<f64>,
+}
+
+/// Samples of the NS, EW and UD components, all of which have the same length.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Components {
+    pub(crate) ns: Vec<f64>,
+    pub(crate) ew: Vec<f64>,
+    pub(crate) ud: Vec<f64>,
+}
+
+impl Components {
+    /// Creates the components, or fails if their lengths differ.
+    pub fn new(ns: Vec<f64>, ew: Vec<f64>, ud: Vec<f64>) -> Result<Self, RecordErr> {
+        if ns.len() != ew.len() || ns.len() != ud.len() {
+            return Err(RecordErr::MismatchedLength(ns.len(), ew.len(), ud.len()));
+        }
+
+        Ok(Self { ns, ew, ud })
+    }
+
+    pub fn ns(&self) -> &[f64] {
+        &self.ns
+    }
+
+    pub fn ew(&self) -> &[f64] {
+        &self.ew
+    }
+
+    pub fn ud(&self) -> &[f64] {
+        &self.ud
+    }
+
+    /// Returns the samples of `component`.
+    pub fn get(&self, component: Component) -> &[f64] {
+        match component {
+            Component::Ns => &self.ns,
+            Component::Ew => &self.ew,
+            Component::Ud => &self.ud,
+        }
+    }
+
+    /// Number of samples of each component.
+    pub fn len(&self) -> usize {
+        self.ns.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ns.is_empty()
+    }
+
+    /// Iterates over the samples of each time step as `[ns, ew, ud]`.
+    pub fn rows(&self) -> impl Iterator<Item = [f64; 3]> + '_ {
+        self.ns
+            .iter()
+            .zip(&self.ew)
+            .zip(&self.ud)
+            .map(|((&ns, &ew), &ud)| [ns, ew, ud])
+    }
+
+    /// Iterates over each component with its samples, in the order of NS, EW, UD.
+    pub fn iter(&self) -> impl Iterator<Item = (Component, &[f64])> {
+        Component::ALL
+            .into_iter()
+            .map(move |component| (component, self.get(component)))
+    }
+
+    /// Returns the components as `(ns, ew, ud)`.
+    pub fn into_inner(self) -> (Vec<f64>, Vec<f64>, Vec<f64>) {
+        (self.ns, self.ew, self.ud)
+    }
+}
+
+/// Three-component strong motion record, independent of the source and the destination format.
+///
+/// Every extractor produces `Record`s and every converter writes them, so a new format only has to be
+/// converted from or to this model.
+///
+/// # Example
+///
+/// ```
+/// use chrono::NaiveDate;
+/// use naifuru::record::{Component, Components, Record, Station, Unit};
+///
+/// let station = Station {
+///     code: "ISK005".to_string(),
+///     lat: Some(37.3003),
+///     lon: Some(136.7698),
+///     sensor_position: None,
+/// };
+/// let start_time = NaiveDate::from_ymd_opt(2024, 1, 1)
+///     .unwrap()
+///     .and_hms_opt(16, 10, 9)
+///     .unwrap();
+/// let components = Components::new(vec![1.0, -2.0], vec![0.5, 0.0], vec![0.0, 0.1]).unwrap();
+///
+/// let record = Record::new(station, None, start_time, 100.0, Unit::Gal, components).unwrap();
+///
+/// assert_eq!(record.len(), 2);
+/// assert_eq!(record.components().get(Component::Ns), &[1.0, -2.0]);
+/// assert_eq!(record.times().collect::<Vec<f64>>(), vec![0.0, 0.01]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub(crate) station: Station,
+    pub(crate) event: Option<Event>,
+    pub(crate) start_time: NaiveDateTime,
+    pub(crate) sampling_rate: f32,
+    pub(crate) unit: Unit,
+    pub(crate) components: Components,
+    pub(crate) processing: Vec<ProcessingStep>,
+}
+
+impl Record {
+    /// Creates a record, or fails if the sampling rate is not positive.
+    pub fn new(
+        station: Station,
+        event: Option<Event>,
+        start_time: NaiveDateTime,
+        sampling_rate: f32,
+        unit: Unit,
+        components: Components,
+    ) -> Result<Self, RecordErr> {
+        if !(sampling_rate > 0.0 && sampling_rate.is_finite()) {
+            return Err(RecordErr::InvalidSamplingRate(sampling_rate.to_string()));
+        }
+
+        Ok(Self {
+            station,
+            event,
+            start_time,
+            sampling_rate,
+            unit,
+            components,
+            processing: Vec::new(),
+        })
+    }
+
+    pub fn station(&self) -> &Station {
+        &self.station
+    }
+
+    pub fn event(&self) -> Option<&Event> {
+        self.event.as_ref()
+    }
+
+    /// Time of the first sample.
+    pub fn start_time(&self) -> NaiveDateTime {
+        self.start_time
+    }
+
+    /// Sampling rate in Hz.
+    pub fn sampling_rate(&self) -> f32 {
+        self.sampling_rate
+    }
+
+    /// Sampling interval in seconds.
+    pub fn delta(&self) -> f64 {
+        1.0 / self.sampling_rate as f64
+    }
+
+    /// Unit of the samples.
+    pub fn unit(&self) -> Unit {
+        self.unit
+    }
+
+    /// Physical quantity of the samples.
+    pub fn quantity(&self) -> Quantity {
+        self.unit.quantity()
+    }
+
+    /// Returns the record in `unit`, which is borrowed as it is if already in `unit`, or fails if `unit` is a unit
+    /// of another quantity.
+    pub fn to_unit(&self, unit: Unit) -> Result<Cow<'_, Record>, RecordErr> {
+        if self.unit == unit {
+            return Ok(Cow::Borrowed(self));
+        }
+
+        let factor = self.unit.factor_to(unit).ok_or_else(|| {
+            RecordErr::MismatchedQuantity(self.unit.as_str().to_string(), unit.as_str().to_string())
+        })?;
+        let mut converted = self.clone();
+        let components = &mut converted.components;
+        for values in [&mut components.ns, &mut components.ew, &mut components.ud] {
+            values.iter_mut().for_each(|v| *v *= factor);
+        }
+        converted.unit = unit;
+
+        Ok(Cow::Owned(converted))
+    }
+
+    pub fn components(&self) -> &Components {
+        &self.components
+    }
+
+    /// Processing steps applied to the samples since extraction, in the order of application.
+    pub fn processing(&self) -> &[ProcessingStep] {
+        &self.processing
+    }
+
+    /// Number of samples of each component.
+    pub fn len(&self) -> usize {
+        self.components.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+
+    /// Duration from the first to the last sample in seconds.
+    pub fn duration(&self) -> f64 {
+        self.len().saturating_sub(1) as f64 * self.delta()
+    }
+
+    /// Iterates over the elapsed time of each sample from the start time in seconds.
+    pub fn times(&self) -> impl Iterator<Item = f64> {
+        let delta = self.delta();
+        (0..self.len()).map(move |i| i as f64 * delta)
+    }
+
+    /// Returns the absolute time of the sample at `index`.
+    pub fn time_at(&self, index: usize) -> NaiveDateTime {
+        self.start_time
+            + TimeDelta::microseconds((index as f64 * self.delta() * 1e6).round() as i64)
+    }
+}
+
+/// Creates a record of ISK005 starting at 2024-01-01 16:10:09, sampled at 100 Hz in gal, for the unit tests.
+#[cfg(test)]
+pub(crate) fn test_record(ns: Vec<f64>, ew: Vec<f64>, ud: Vec<f64>) -> Record {
+    Record::new(
+        Station {
+            code: "ISK005".to_string(),
+            lat: Some(37.3003),
+            lon: Some(136.7698),
+            sensor_position: None,
+        },
+        None,
+        chrono::NaiveDate::from_ymd_opt(2024, 1, 1)
+            .unwrap()
+            .and_hms_opt(16, 10, 9)
+            .unwrap(),
+        100.0,
+        Unit::Gal,
+        Components::new(ns, ew, ud).unwrap(),
+    )
+    .unwrap()
+}
+
+/// This module contains unit tests for the record model.
+///
+/// # Test Categories
+///
+/// - `test_components_mismatched_length`: Tests rejection of components with different lengths
+/// - `test_record_invalid_sampling_rate`: Tests rejection of a non-positive sampling rate
+/// - `test_record_iteration`: Tests the elapsed times, absolute times and rows of a record
+/// - `test_unit_conversion`: Tests parsing of unit labels, conversion of a record between units and rejection of a
+///   unit of another quantity
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn station() -> Station {
+        Station {
+            code: "ISK005".to_string(),
+            lat: Some(37.3003),
+            lon: Some(136.7698),
+            sensor_position: None,
+        }
+    }
+
+    fn start_time() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 1, 1)
+            .unwrap()
+            .and_hms_opt(16, 10, 9)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_components_mismatched_length() {
+        assert_eq!(
+            Components::new(vec![0.0; 3], vec![0.0; 3], vec![0.0; 2]),
+            Err(RecordErr::MismatchedLength(3, 3, 2))
+        );
+    }
+
+    #[test]
+    fn test_record_invalid_sampling_rate() {
+        let components = Components::new(vec![0.0], vec![0.0], vec![0.0]).unwrap();
+        assert!(Record::new(station(), None, start_time(), 0.0, Unit::Gal, components).is_err());
+    }
+
+    #[test]
+    fn test_record_iteration() {
+        let components = Components::new(
+            vec![1.0, 2.0, 3.0],
+            vec![4.0, 5.0, 6.0],
+            vec![7.0, 8.0, 9.0],
+        )
+        .unwrap();
+        let record =
+            Record::new(station(), None, start_time(), 200.0, Unit::Gal, components).unwrap();
+
+        assert_eq!(record.times().collect::<Vec<f64>>(), vec![0.0, 0.005, 0.01]);
+        assert_eq!(record.duration(), 0.01);
+        assert_eq!(
+            record.time_at(2),
+            start_time() + TimeDelta::milliseconds(10)
+        );
+        assert_eq!(
+            record.components().rows().collect::<Vec<_>>(),
+            vec![[1.0, 4.0, 7.0], [2.0, 5.0, 8.0], [3.0, 6.0, 9.0]]
+        );
+        assert_eq!(
+            record
+                .components()
+                .iter()
+                .map(|(component, values)| (component, values[0]))
+                .collect::<Vec<_>>(),
+            vec![
+                (Component::Ns, 1.0),
+                (Component::Ew, 4.0),
+                (Component::Ud, 7.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unit_conversion() {
+        assert_eq!(Unit::from_label(" CM/S^2 "), Some(Unit::Gal));
+        assert_eq!(
+            Unit::from_label("m/sec/sec"),
+            Some(Unit::MetersPerSecondSquared)
+        );
+        assert_eq!(Unit::from_label("CM/SEC"), Some(Unit::CentimetersPerSecond));
+        assert_eq!(Unit::from_label("mm"), Some(Unit::Millimeters));
+        assert_eq!(Unit::from_label("counts"), None);
+        assert_eq!(Unit::G.factor_to(Unit::Gal), Some(980.665));
+        assert_eq!(
+            Unit::MillimetersPerSecondSquared.factor_to(Unit::MetersPerSecondSquared),
+            Some(0.001)
+        );
+
+        let components = Components::new(vec![980.665], vec![-100.0], vec![0.0]).unwrap();
+        let record =
+            Record::new(station(), None, start_time(), 100.0, Unit::Gal, components).unwrap();
+        assert!(matches!(record.to_unit(Unit::Gal), Ok(Cow::Borrowed(_))));
+
+        let converted = record.to_unit(Unit::G).unwrap();
+        assert_eq!(converted.unit(), Unit::G);
+        assert!((converted.components().ns()[0] - 1.0).abs() < 1e-12);
+        let converted = record.to_unit(Unit::MetersPerSecondSquared).unwrap();
+        assert_eq!(converted.components().ew(), &[-1.0]);
+
+        assert_eq!(Unit::Millimeters.factor_to(Unit::Meters), Some(0.001));
+        assert_eq!(Unit::MetersPerSecond.quantity(), Quantity::Velocity);
+        assert_eq!(Unit::Gal.factor_to(Unit::Centimeters), None);
+        assert_eq!(
+            record.to_unit(Unit::CentimetersPerSecond),
+            Err(RecordErr::MismatchedQuantity(
+                "gal".to_string(),
+                "cm/s".to_string()
+            ))
+        );
+    }
+}
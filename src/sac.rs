@@ -0,0 +1,78 @@
+/// Size of the SAC header in bytes.
+pub const SAC_HEADER_SIZE: usize = 632;
+
+// ヘッダーは70個の実数、40個の整数、192バイトの文字列で構成される
+pub(crate) const FLOAT_OFFSET: usize = 0;
+pub(crate) const INT_OFFSET: usize = 280;
+pub(crate) const STRING_OFFSET: usize = 440;
+pub(crate) const NUM_FLOATS: usize = 70;
+pub(crate) const NUM_INTS: usize = 40;
+
+// 実数ヘッダーのインデックス
+pub(crate) const DELTA: usize = 0;
+pub(crate) const DEPMIN: usize = 1;
+pub(crate) const DEPMAX: usize = 2;
+pub(crate) const B: usize = 5;
+pub(crate) const E: usize = 6;
+pub(crate) const O: usize = 7;
+pub(crate) const STLA: usize = 31;
+pub(crate) const STLO: usize = 32;
+pub(crate) const EVLA: usize = 35;
+pub(crate) const EVLO: usize = 36;
+pub(crate) const EVDP: usize = 38;
+pub(crate) const MAG: usize = 39;
+pub(crate) const DEPMEN: usize = 56;
+pub(crate) const CMPAZ: usize = 57;
+pub(crate) const CMPINC: usize = 58;
+
+// 整数、列挙型、論理ヘッダーのインデックス
+pub(crate) const NZYEAR: usize = 0;
+pub(crate) const NZJDAY: usize = 1;
+pub(crate) const NZHOUR: usize = 2;
+pub(crate) const NZMIN: usize = 3;
+pub(crate) const NZSEC: usize = 4;
+pub(crate) const NZMSEC: usize = 5;
+pub(crate) const NVHDR: usize = 6;
+pub(crate) const NPTS: usize = 9;
+pub(crate) const IFTYPE: usize = 15;
+pub(crate) const IDEP: usize = 16;
+pub(crate) const IZTYPE: usize = 17;
+pub(crate) const LEVEN: usize = 35;
+pub(crate) const LPSPOL: usize = 36;
+pub(crate) const LOVROK: usize = 37;
+pub(crate) const LCALDA: usize = 38;
+
+// 列挙型ヘッダーの値
+pub(crate) const ITIME: i32 = 1;
+pub(crate) const IUNKN: i32 = 5;
+pub(crate) const IDISP: i32 = 6;
+pub(crate) const IVEL: i32 = 7;
+pub(crate) const IACC: i32 = 8;
+pub(crate) const IB: i32 = 9;
+
+// 文字列ヘッダーのバイト位置と長さ
+pub(crate) const KSTNM: (usize, usize) = (440, 8);
+pub(crate) const KEVNM: (usize, usize) = (448, 16);
+pub(crate) const KUSER0: (usize, usize) = (576, 8);
+pub(crate) const KCMPNM: (usize, usize) = (600, 8);
+pub(crate) const KNETWK: (usize, usize) = (608, 8);
+
+// 値が未定義であることを示す値
+pub(crate) const UNDEFINED_FLOAT: f32 = -12345.0;
+pub(crate) const UNDEFINED_INT: i32 = -12345;
+pub(crate) const UNDEFINED_STRING: &str = "-12345";
+
+/// Header version written by naifuru.
+pub(crate) const HEADER_VERSION: i32 = 6;
+/// Header versions read by naifuru.
+pub(crate) const SUPPORTED_NVHDR: [i32; 2] = [6, 7];
+
+/// Reads a string field, or `None` if it is empty or undefined.
+pub(crate) fn read_string(bytes: &[u8], (offset, len): (usize, usize)) -> Option<String> {
+    let value = String::from_utf8_lossy(&bytes[offset..offset + len])
+        .trim_end_matches(['\0', ' '])
+        .trim()
+        .to_string();
+
+    Some(value).filter(|v| !v.is_empty() && v != UNDEFINED_STRING)
+}